@@ -3,8 +3,76 @@
 
 /// 命令行参数结构的辅助函数
 pub mod helpers {
-    /// 验证输出格式
-    pub fn validate_output_format(format: &str) -> bool {
-        matches!(format.to_lowercase().as_str(), "table" | "json")
+    use crate::utils::i18n::{self, Locale};
+    use clap::Command;
+
+    /// 帮助文本的消息键：根命令为 `cli.<arg>`，子命令为 `cli.<sub>.<arg>`
+    fn help_key(prefix: &str, id: &str) -> String {
+        format!("{}.{}", prefix, id)
+    }
+
+    /// 用消息目录中的文本替换 clap 命令树的说明和参数帮助
+    pub fn localize_command(cmd: Command, locale: Locale) -> Command {
+        // 先构建以生成内置的 --help / --version 参数，再统一替换
+        let mut cmd = cmd;
+        cmd.build();
+        localize_recursive(cmd, "cli", locale)
+    }
+
+    fn localize_recursive(mut cmd: Command, prefix: &str, locale: Locale) -> Command {
+        cmd = cmd.about(i18n::translate_in(locale, &help_key(prefix, "about"), &[]));
+
+        let args: Vec<(String, String)> = cmd
+            .get_arguments()
+            .map(|a| (a.get_id().to_string(), arg_key(prefix, a)))
+            .collect();
+        for (id, key) in args {
+            let text = i18n::translate_in(locale, &key, &[]);
+            cmd = cmd.mut_arg(id, |a| a.help(text));
+        }
+
+        // help 子命令下的子命令只是占位，不需要翻译
+        let names: Vec<String> = cmd
+            .get_subcommands()
+            .filter(|_| cmd.get_name() != "help")
+            .map(|s| s.get_name().to_string())
+            .collect();
+        for name in names {
+            let sub_prefix = sub_prefix(prefix, &name);
+            cmd = cmd.mut_subcommand(name, |s| localize_recursive(s, &sub_prefix, locale));
+        }
+
+        cmd
+    }
+
+    /// 子命令的键前缀：各级自动生成的 help 子命令共用 `cli.help`
+    fn sub_prefix(prefix: &str, name: &str) -> String {
+        if name == "help" {
+            help_key("cli", name)
+        } else {
+            help_key(prefix, name)
+        }
+    }
+
+    /// 参数的消息键：全局参数和内置的 --help / --version 统一使用根命令的键
+    fn arg_key(prefix: &str, arg: &clap::Arg) -> String {
+        let id = arg.get_id().as_str();
+        if arg.is_global_set() || id == "help" || id == "version" {
+            help_key("cli", id)
+        } else {
+            help_key(prefix, id)
+        }
+    }
+
+    /// 收集命令树中所有需要翻译的帮助键（供测试检查目录完整性）
+    #[cfg(test)]
+    pub fn collect_help_keys(cmd: &Command, prefix: &str, keys: &mut Vec<String>) {
+        keys.push(help_key(prefix, "about"));
+        for arg in cmd.get_arguments() {
+            keys.push(arg_key(prefix, arg));
+        }
+        for sub in cmd.get_subcommands().filter(|_| cmd.get_name() != "help") {
+            collect_help_keys(sub, &sub_prefix(prefix, sub.get_name()), keys);
+        }
     }
 }
@@ -0,0 +1,291 @@
+// Shell 补全：静态部分由 clap_complete 根据命令定义生成，
+// 动态部分（worktree 名称、分支、配置项）由隐藏的 `work __complete` 命令实时提供。
+//
+// `work __complete <index> <words...>` 中 words 是 `work` 之后的所有参数，
+// index 是正在补全的参数位置；index 等于 words 长度时表示正在补全一个空参数。
+// 这样 shell 不需要传递空字符串（PowerShell 调用外部命令时会丢弃空参数）。
+
+use crate::core::worktree::Worktree;
+use clap::Command;
+use clap_complete::Shell;
+
+/// 动态补全的候选类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionContext {
+    /// worktree 目录名、别名和分支名
+    Worktree,
+    /// 本地和远程分支
+    Branch,
+    /// 配置项名称
+    ConfigKey,
+}
+
+/// 根据已输入的参数判断需要哪类动态候选，返回 None 时由静态补全处理
+pub fn context(cmd: &Command, words: &[String], index: usize) -> Option<CompletionContext> {
+    let before = &words[..index.min(words.len())];
+
+    // 第一个非选项参数是子命令（根命令上只有 --error-format 带值）
+    let mut sub_index = 0;
+    while let Some(word) = before.get(sub_index) {
+        if word == "--error-format" {
+            sub_index += 2;
+        } else if word.starts_with('-') {
+            sub_index += 1;
+        } else {
+            break;
+        }
+    }
+    let sub = cmd.find_subcommand(before.get(sub_index)?)?;
+
+    let rest = &before[sub_index + 1..];
+    if let Some(prev) = rest.last() {
+        if let Some(arg) = find_option(sub, prev) {
+            if sub.get_name() == "create" && arg.get_id() == "branch" {
+                return Some(CompletionContext::Branch);
+            }
+            if arg.get_action().takes_values() {
+                return None;
+            }
+        }
+    }
+
+    let positionals = count_positionals(sub, rest);
+    match sub.get_name() {
+        "delete" => Some(CompletionContext::Worktree),
+        "switch" | "info" | "open" | "session" | "snapshot" | "env" if positionals == 0 => Some(CompletionContext::Worktree),
+        "carry" if positionals < 2 => Some(CompletionContext::Worktree),
+        // alias set <ALIAS> <NAME>
+        "alias" if rest.first().is_some_and(|w| w == "set") && positionals == 2 => Some(CompletionContext::Worktree),
+        "config" if positionals == 0 => Some(CompletionContext::ConfigKey),
+        _ => None,
+    }
+}
+
+/// 按 `-x` / `--long` 查找子命令的选项
+fn find_option<'a>(cmd: &'a Command, word: &str) -> Option<&'a clap::Arg> {
+    if let Some(long) = word.strip_prefix("--") {
+        cmd.get_arguments().find(|a| a.get_long() == Some(long))
+    } else if let Some(short) = word.strip_prefix('-') {
+        let mut chars = short.chars();
+        let c = chars.next()?;
+        if chars.next().is_some() {
+            return None;
+        }
+        cmd.get_arguments().find(|a| a.get_short() == Some(c))
+    } else {
+        None
+    }
+}
+
+/// 统计已输入的位置参数个数（跳过选项及其值）
+fn count_positionals(cmd: &Command, words: &[String]) -> usize {
+    let mut count = 0;
+    let mut skip_value = false;
+
+    for word in words {
+        if skip_value {
+            skip_value = false;
+        } else if word.starts_with('-') {
+            skip_value = !word.contains('=')
+                && find_option(cmd, word).is_some_and(|a| a.get_action().takes_values());
+        } else {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// worktree 名称的候选：目录名、别名和分支名（分离 HEAD 时没有分支），不包括裸仓库
+pub fn worktree_candidates(worktrees: &[Worktree]) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for wt in worktrees.iter().filter(|wt| !wt.is_bare) {
+        let branch = (!wt.is_detached).then_some(&wt.branch_name);
+        for name in std::iter::once(&wt.dirname).chain(&wt.alias).chain(branch) {
+            if !candidates.contains(name) {
+                candidates.push(name.clone());
+            }
+        }
+    }
+    candidates
+}
+
+/// 正在补全的参数（可能为空）
+pub fn current_word(words: &[String], index: usize) -> &str {
+    words.get(index).map(String::as_str).unwrap_or_default()
+}
+
+/// 生成指定 shell 的补全脚本：clap 的静态补全加上调用 `work __complete` 的动态补全
+pub fn generate_script(shell: Shell, cmd: &mut Command) -> String {
+    let name = cmd.get_name().to_string();
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, cmd, &name, &mut buffer);
+    let script = String::from_utf8_lossy(&buffer).into_owned();
+
+    match shell {
+        Shell::Bash => format!("{}\n{}", script, BASH_DYNAMIC),
+        Shell::Zsh => format!("{}\n{}", script, ZSH_DYNAMIC),
+        Shell::Fish => format!("{}\n{}", script, FISH_DYNAMIC),
+        // 保留静态补全的脚本块，动态候选为空时回退到它
+        Shell::PowerShell => format!(
+            "{}\n{}",
+            script.replacen(POWERSHELL_REGISTER, "$__workStaticCompleter =", 1),
+            POWERSHELL_DYNAMIC
+        ),
+        _ => script,
+    }
+}
+
+const POWERSHELL_REGISTER: &str = "Register-ArgumentCompleter -Native -CommandName 'work' -ScriptBlock";
+
+const BASH_DYNAMIC: &str = r#"_work_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local candidates
+    candidates=$(work __complete "$((COMP_CWORD - 1))" "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null)
+    if [ -n "$candidates" ]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$candidates" -- "$cur"))
+        return 0
+    fi
+    _work "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _work_dynamic -o nosort -o bashdefault -o default work
+else
+    complete -F _work_dynamic -o bashdefault -o default work
+fi
+"#;
+
+const ZSH_DYNAMIC: &str = r#"_work_dynamic() {
+    local -a candidates
+    candidates=("${(@f)$(work __complete "$((CURRENT - 2))" "${(@)words[2,CURRENT-1]}" 2>/dev/null)}")
+    if [[ -n "${candidates[1]}" ]]; then
+        compadd -a candidates
+        return
+    fi
+    _work "$@"
+}
+
+compdef _work_dynamic work
+"#;
+
+const FISH_DYNAMIC: &str = r#"function __work_dynamic
+    set -l words (commandline -opc)[2..-1]
+    work __complete (count $words) $words (commandline -ct) 2>/dev/null
+end
+
+complete -c work -f -a '(__work_dynamic)'
+"#;
+
+const POWERSHELL_DYNAMIC: &str = r#"Register-ArgumentCompleter -Native -CommandName 'work' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | Where-Object {
+        $_.Extent.EndOffset -le $cursorPosition
+    } | ForEach-Object { $_.ToString() })
+    $index = $words.Count
+    if ($wordToComplete -ne '') { $index -= 1 }
+
+    $candidates = @(& work __complete $index @words 2>$null)
+    if ($candidates.Count -gt 0) {
+        $candidates | ForEach-Object {
+            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+        }
+        return
+    }
+    & $__workStaticCompleter $wordToComplete $commandAst $cursorPosition
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn words(input: &str) -> Vec<String> {
+        input.split_whitespace().map(String::from).collect()
+    }
+
+    fn test_command() -> Command {
+        Command::new("work")
+            .arg(Arg::new("error_format").long("error-format").global(true))
+            .subcommand(Command::new("switch").arg(Arg::new("name")))
+            .subcommand(
+                Command::new("create")
+                    .arg(Arg::new("name"))
+                    .arg(Arg::new("branch").short('b').long("branch"))
+                    .arg(Arg::new("path").short('p').long("path")),
+            )
+            .subcommand(
+                Command::new("delete")
+                    .alias("rm")
+                    .arg(Arg::new("names").num_args(0..))
+                    .arg(Arg::new("force").short('f').long("force").action(ArgAction::SetTrue)),
+            )
+            .subcommand(Command::new("config").arg(Arg::new("key")).arg(Arg::new("value")))
+            .subcommand(Command::new("alias").subcommand(Command::new("set").arg(Arg::new("alias")).arg(Arg::new("name"))))
+    }
+
+    #[test]
+    fn test_context_for_positionals() {
+        let cmd = test_command();
+        assert_eq!(context(&cmd, &words("switch"), 1), Some(CompletionContext::Worktree));
+        assert_eq!(context(&cmd, &words("switch feat"), 1), Some(CompletionContext::Worktree));
+        assert_eq!(context(&cmd, &words("switch feat"), 2), None);
+        assert_eq!(context(&cmd, &words("alias set api"), 3), Some(CompletionContext::Worktree));
+        assert_eq!(context(&cmd, &words("alias set"), 2), None);
+        assert_eq!(context(&cmd, &words("rm -f a b"), 4), Some(CompletionContext::Worktree));
+        assert_eq!(context(&cmd, &words("config"), 1), Some(CompletionContext::ConfigKey));
+        assert_eq!(context(&cmd, &words("config language"), 2), None);
+    }
+
+    #[test]
+    fn test_context_for_options() {
+        let cmd = test_command();
+        assert_eq!(context(&cmd, &words("create feat -b"), 3), Some(CompletionContext::Branch));
+        assert_eq!(context(&cmd, &words("create feat --branch ma"), 3), Some(CompletionContext::Branch));
+        assert_eq!(context(&cmd, &words("create feat -p"), 3), None);
+        assert_eq!(context(&cmd, &words("create"), 1), None);
+    }
+
+    #[test]
+    fn test_context_for_subcommand_name() {
+        let cmd = test_command();
+        assert_eq!(context(&cmd, &words(""), 0), None);
+        assert_eq!(context(&cmd, &words("sw"), 0), None);
+        assert_eq!(context(&cmd, &words("unknown"), 1), None);
+    }
+
+    #[test]
+    fn test_worktree_candidates() {
+        let mut bare = Worktree::new("repo".into(), "(bare)".into(), "/w/repo/.bare".into(), false, true, false, None, None);
+        bare.is_main = true;
+        let mut feature = Worktree::new("feat-x".into(), "feat/x".into(), "/w/feat-x".into(), false, false, false, None, None);
+        feature.alias = Some("fx".into());
+        let main = Worktree::new("main".into(), "main".into(), "/w/main".into(), false, false, false, None, None);
+        let detached = Worktree::new("scratch".into(), "HEAD".into(), "/w/scratch".into(), false, false, true, None, None);
+
+        assert_eq!(
+            worktree_candidates(&[bare, feature, main, detached]),
+            vec!["feat-x", "fx", "feat/x", "main", "scratch"]
+        );
+    }
+
+    #[test]
+    fn test_current_word() {
+        let w = words("create feat -b ma");
+        assert_eq!(current_word(&w, 3), "ma");
+        assert_eq!(current_word(&w, 4), "");
+    }
+
+    #[test]
+    fn test_generated_scripts_include_dynamic_part() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let script = generate_script(shell, &mut test_command());
+            assert!(script.contains("work __complete"), "{:?}", shell);
+        }
+        let powershell = generate_script(Shell::PowerShell, &mut test_command());
+        assert!(powershell.contains("$__workStaticCompleter ="));
+    }
+}
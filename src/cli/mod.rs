@@ -1,2 +1,5 @@
 pub mod commands;
+pub mod completion;
 pub mod output;
+pub mod schema;
+pub mod template;
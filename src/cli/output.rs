@@ -1,20 +1,37 @@
 use comfy_table::{Table, Cell, Color};
 use serde::{Deserialize, Serialize};
 use colored::Colorize;
+use crate::cli::template::Template;
+use crate::utils::i18n::t;
 
 /// 输出格式枚举
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Table,
     Compact,
     Json,
+    /// 稳定的 NDJSON 输出（每行一个 v1 记录）
+    Ndjson,
+    /// 稳定的行格式输出（porcelain v1）
+    Porcelain,
+    /// 自定义模板（`-o template='{{dirname}}\t{{branch}}'`）
+    Template(String),
 }
 
 impl OutputFormat {
     pub fn from_str(s: &str) -> Self {
+        // 模板内容区分大小写，只对前缀做不区分大小写的匹配
+        if let Some(prefix) = s.get(..9) {
+            if prefix.eq_ignore_ascii_case("template=") {
+                return OutputFormat::Template(s[9..].to_string());
+            }
+        }
+
         match s.to_lowercase().as_str() {
             "json" => OutputFormat::Json,
+            "ndjson" | "jsonl" => OutputFormat::Ndjson,
+            "porcelain" => OutputFormat::Porcelain,
             "compact" | "simple" | "short" => OutputFormat::Compact,
             _ => OutputFormat::Table,
         }
@@ -25,20 +42,45 @@ impl OutputFormat {
 pub fn format_worktree_table(worktrees: Vec<crate::core::worktree::Worktree>) -> String {
     let mut table = Table::new();
     table
-        .set_header(vec!["NAME", "BRANCH", "PATH", "CURRENT", "STATUS"])
+        .set_header(vec![
+            t("list.header.name"),
+            t("list.header.branch"),
+            t("list.header.path"),
+            t("list.header.current"),
+            t("list.header.status"),
+        ])
         .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
 
     for wt in worktrees {
         let current_marker = if wt.is_current { "*" } else { "" };
-        let status = if wt.is_detached {
-            "Detached HEAD"
+        let mut status = if wt.is_bare {
+            t("list.status.bare")
+        } else if wt.is_prunable {
+            t("list.status.prunable")
+        } else if let Some(operation) = &wt.operation {
+            operation.marker()
+        } else if wt.is_detached {
+            t("list.status.detached")
         } else {
-            "Healthy"
+            t("list.status.healthy")
+        };
+        if wt.is_locked {
+            status = format!("{} ({})", status, t("list.locked"));
+        }
+        if wt.is_expired(chrono::Utc::now()) {
+            status = format!("{} ({})", status, t("list.expired"));
+        } else if wt.is_temporary {
+            status = format!("{} ({})", status, t("list.temporary"));
+        }
+
+        let name = match &wt.alias {
+            Some(alias) => format!("{} @{}", wt.dirname, alias),
+            None => wt.dirname.clone(),
         };
 
         table.add_row(vec![
-            Cell::new(&wt.dirname).fg(Color::Cyan),
-            Cell::new(&wt.branch_name),
+            Cell::new(name).fg(Color::Cyan),
+            Cell::new(if wt.is_bare { "-" } else { &wt.branch_name }),
             Cell::new(&wt.path),
             Cell::new(current_marker).fg(Color::Green),
             Cell::new(status),
@@ -53,8 +95,7 @@ pub fn format_worktree_compact(worktrees: Vec<crate::core::worktree::Worktree>)
     let mut output = String::new();
 
     for wt in worktrees {
-        // 判断是否为主目录（不在 .worktrees 中）
-        let is_main = !wt.path.contains(".worktrees");
+        let is_main = wt.is_main;
 
         // 当前标记：绿色
         let current_marker = if wt.is_current {
@@ -77,19 +118,43 @@ pub fn format_worktree_compact(worktrees: Vec<crate::core::worktree::Worktree>)
             wt.dirname.cyan().to_string()
         };
 
+        // 别名：洋红色
+        let alias = match &wt.alias {
+            Some(alias) => format!(" {}", format!("@{}", alias).magenta()),
+            None => String::new(),
+        };
+
         // 当前分支：黄色（如果与目录名不同）
-        let branch_info = if wt.branch_name != wt.dirname && wt.branch_name != "HEAD" {
-            format!(" on {}", wt.branch_name.yellow())
+        let branch_info = if wt.is_bare {
+            String::new()
+        } else if wt.branch_name != wt.dirname && wt.branch_name != "HEAD" {
+            format!(" {} {}", t("list.on"), wt.branch_name.yellow())
         } else if wt.is_detached {
-            format!(" on {}", "HEAD".yellow())
+            format!(" {} {}", t("list.on"), "HEAD".yellow())
         } else {
             String::new()
         };
 
         // 构建状态标记
         let mut status_markers = Vec::new();
-        if wt.has_uncommitted_changes() {
-            status_markers.push("modified".red().to_string());
+        if wt.is_bare {
+            // 裸仓库没有工作目录，不检查更改
+            status_markers.push(t("list.bare").dimmed().to_string());
+        } else if wt.is_prunable {
+            status_markers.push(t("list.prunable").red().to_string());
+        } else if let Some(operation) = &wt.operation {
+            // 进行中的操作通常伴随未提交的更改，只显示操作标记
+            status_markers.push(operation.marker().red().bold().to_string());
+        } else if wt.has_uncommitted_changes() {
+            status_markers.push(t("list.modified").red().to_string());
+        }
+        if wt.is_locked {
+            status_markers.push(t("list.locked").yellow().to_string());
+        }
+        if wt.is_expired(chrono::Utc::now()) {
+            status_markers.push(t("list.expired").red().to_string());
+        } else if wt.is_temporary {
+            status_markers.push(t("list.temporary").blue().to_string());
         }
         let status_marker = if status_markers.is_empty() {
             String::new()
@@ -99,17 +164,18 @@ pub fn format_worktree_compact(worktrees: Vec<crate::core::worktree::Worktree>)
 
         // 主目录路径显示（仅主目录）
         let path_info = if is_main {
-            format!(" at {}", wt.path.dimmed())
+            format!(" {} {}", t("list.at"), wt.path.dimmed())
         } else {
             String::new()
         };
 
-        // 简化显示：目录名 + 分支 + 状态 + 路径（主目录）
+        // 简化显示：目录名 + 别名 + 分支 + 状态 + 路径（主目录）
         output.push_str(&format!(
-            "{}{} {}{}{}{}\n",
+            "{}{} {}{}{}{}{}\n",
             current_marker,
             main_marker,
             name,
+            alias,
             branch_info,
             status_marker,
             path_info
@@ -119,36 +185,218 @@ pub fn format_worktree_compact(worktrees: Vec<crate::core::worktree::Worktree>)
     output.trim_end().to_string()
 }
 
-/// 格式化 worktree 列表为 JSON
+/// 格式化 worktree 列表为 JSON 数组（元素为 v1 稳定记录）
 pub fn format_worktree_json(worktrees: Vec<crate::core::worktree::Worktree>) -> String {
-    serde_json::to_string_pretty(&worktrees).unwrap_or_else(|_| {
-        format!("{{\"error\": \"Failed to serialize worktrees\"}}")
+    let records: Vec<_> = worktrees
+        .iter()
+        .map(crate::cli::schema::WorktreeRecordV1::from_worktree)
+        .collect();
+    serde_json::to_string_pretty(&records).unwrap_or_else(|_| {
+        "{\"error\": \"Failed to serialize worktrees\"}".to_string()
     })
 }
 
-/// 格式化单个 worktree 的详细信息
-pub fn format_worktree_info(worktree: &crate::core::worktree::Worktree) -> String {
-    format!(
-        "Worktree: {}
-  Branch: {}
-  Path: {}
-  HEAD: {}
-  Current: {}
-  Detached: {}
-{}{}",
-        worktree.dirname,
-        worktree.branch_name,
-        worktree.path,
-        worktree.head_commit.as_ref().unwrap_or(&"N/A".to_string()),
-        if worktree.is_current { "Yes" } else { "No" },
-        if worktree.is_detached { "Yes" } else { "No" },
-        if let Some(upstream) = &worktree.upstream_branch {
-            format!("  Upstream: {}\n", upstream)
+/// 按模板格式化 worktree 列表（无颜色，每条记录一行）
+///
+/// 模板以 `\0` 结尾时记录之间不再追加换行，便于配合 `xargs -0` 使用
+pub fn format_worktree_template(worktrees: &[crate::core::worktree::Worktree], template: &Template) -> String {
+    let separator = if template.ends_with_nul() { "" } else { "\n" };
+
+    worktrees
+        .iter()
+        .map(|wt| template.render(wt))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// 格式化磁盘占用为表格（各列均为实际占用，多于一个 worktree 时追加合计行）
+pub fn format_du_table(usages: &[crate::core::du::WorktreeUsage]) -> String {
+    use crate::core::du::{format_size, Usage};
+
+    let mut table = Table::new();
+    table
+        .set_header(vec![
+            t("list.header.name"),
+            t("du.header.disk"),
+            t("du.header.apparent"),
+            t("du.header.tracked"),
+            t("du.header.untracked"),
+            t("du.header.ignored"),
+            t("du.header.top_ignored"),
+        ])
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+
+    let mut total = [Usage::default(); 4];
+    for usage in usages {
+        let top = usage
+            .top_ignored
+            .iter()
+            .map(|entry| format!("{} {}", entry.path, format_size(entry.size.disk)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        table.add_row(vec![
+            Cell::new(&usage.dirname).fg(Color::Cyan),
+            Cell::new(format_size(usage.total.disk)).fg(Color::Yellow),
+            Cell::new(format_size(usage.total.apparent)),
+            Cell::new(format_size(usage.tracked.disk)),
+            Cell::new(format_size(usage.untracked.disk)),
+            Cell::new(format_size(usage.ignored.disk)),
+            Cell::new(top),
+        ]);
+
+        for (sum, part) in total.iter_mut().zip([usage.total, usage.tracked, usage.untracked, usage.ignored]) {
+            sum.apparent += part.apparent;
+            sum.disk += part.disk;
+        }
+    }
+
+    if usages.len() > 1 {
+        table.add_row(vec![
+            Cell::new(t("du.total")),
+            Cell::new(format_size(total[0].disk)).fg(Color::Yellow),
+            Cell::new(format_size(total[0].apparent)),
+            Cell::new(format_size(total[1].disk)),
+            Cell::new(format_size(total[2].disk)),
+            Cell::new(format_size(total[3].disk)),
+            Cell::new(""),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// 格式化操作日志为表格（调用方决定顺序）
+pub fn format_oplog_table(ops: &[crate::core::oplog::Operation]) -> String {
+    use crate::core::oplog::OpResult;
+
+    let mut table = Table::new();
+    table
+        .set_header(vec![
+            t("log.header.time"),
+            t("log.header.user"),
+            t("log.header.operation"),
+            t("log.header.worktree"),
+            t("log.header.result"),
+            t("log.header.command"),
+        ])
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+
+    for op in ops {
+        let result = match op.result {
+            OpResult::Ok => Cell::new(t("log.result.ok")).fg(Color::Green),
+            OpResult::Failed => Cell::new(op.error.as_deref().unwrap_or_default()).fg(Color::Red),
+        };
+
+        table.add_row(vec![
+            Cell::new(op.time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(&op.user),
+            Cell::new(op.kind.as_str()).fg(Color::Yellow),
+            Cell::new(&op.dirname).fg(Color::Cyan),
+            result,
+            Cell::new(format!("work {}", op.args.join(" "))),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// 格式化切换历史
+pub fn format_recent_table(visits: &[crate::core::history::Visit]) -> String {
+    let mut table = Table::new();
+    table
+        .set_header(vec![t("recent.header.name"), t("recent.header.last_visited"), t("recent.header.visits")])
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+
+    for visit in visits {
+        table.add_row(vec![
+            Cell::new(&visit.dirname).fg(Color::Cyan),
+            Cell::new(visit.last_visited.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(visit.visits),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// 格式化多仓库工作区的 worktree 列表
+pub fn format_ws_table(worktrees: &[crate::core::multi_repo::RepoWorktree]) -> String {
+    let mut table = Table::new();
+    table
+        .set_header(vec![
+            t("ws.header.repo"),
+            t("list.header.name"),
+            t("list.header.branch"),
+            t("list.header.path"),
+            t("list.header.current"),
+        ])
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+
+    for wt in worktrees {
+        table.add_row(vec![
+            Cell::new(&wt.repo).fg(Color::Yellow),
+            Cell::new(&wt.worktree.dirname).fg(Color::Cyan),
+            Cell::new(&wt.worktree.branch_name),
+            Cell::new(&wt.worktree.path),
+            Cell::new(if wt.worktree.is_current { "*" } else { "" }).fg(Color::Green),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// 格式化所有仓库的 worktree 列表（`work ls --all`）
+pub fn format_global_table(worktrees: &[crate::core::registry::GlobalWorktree]) -> String {
+    let now = chrono::Utc::now();
+    let mut table = Table::new();
+    table
+        .set_header(vec![
+            t("ws.header.repo"),
+            t("list.header.name"),
+            t("list.header.branch"),
+            t("list.header.status"),
+            t("global.header.age"),
+            t("list.header.path"),
+            t("list.header.current"),
+        ])
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+
+    for wt in worktrees {
+        let status = if wt.worktree.is_bare {
+            Cell::new("-")
+        } else if wt.worktree.is_prunable {
+            Cell::new(t("list.status.prunable")).fg(Color::Red)
+        } else if let Some(operation) = &wt.worktree.operation {
+            Cell::new(operation.marker()).fg(Color::Red)
+        } else if wt.dirty {
+            Cell::new(t("global.dirty")).fg(Color::Yellow)
         } else {
-            String::new()
-        },
-        format!("  Last Modified: {}\n", worktree.last_modified.format("%Y-%m-%d %H:%M:%S"))
-    )
+            Cell::new(t("global.clean")).fg(Color::Green)
+        };
+        table.add_row(vec![
+            Cell::new(&wt.repo).fg(Color::Yellow),
+            Cell::new(&wt.worktree.dirname).fg(Color::Cyan),
+            Cell::new(if wt.worktree.is_bare { "-" } else { &wt.worktree.branch_name }),
+            status,
+            Cell::new(wt.committed_at.map_or_else(|| "-".to_string(), |time| format_age(now - time))),
+            Cell::new(&wt.worktree.path),
+            Cell::new(if wt.worktree.is_current { "*" } else { "" }).fg(Color::Green),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// 把时间间隔格式化为简短的相对时间，如 `5m`、`3h`、`2d`、`4w`、`1y`
+pub fn format_age(age: chrono::Duration) -> String {
+    let minutes = age.num_minutes().max(0);
+    match minutes {
+        0..=59 => format!("{}m", minutes),
+        60..=1439 => format!("{}h", minutes / 60),
+        1440..=10079 => format!("{}d", minutes / 1440),
+        10080..=525_599 => format!("{}w", minutes / 10080),
+        _ => format!("{}y", minutes / 525_600),
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +404,49 @@ mod tests {
     use super::*;
     use crate::core::worktree::Worktree;
 
+    #[test]
+    fn test_format_oplog_table() {
+        use crate::core::oplog::Operation;
+        use crate::utils::errors::WorktreeError;
+
+        let ok = Operation::create("feat-x", "/nonexistent/feat-x", Some("feat/x"), true);
+        let failed = Operation::create("dup", "/nonexistent/dup", None, false)
+            .failed(&WorktreeError::AlreadyExists("dup".to_string()));
+        let table = format_oplog_table(&[ok, failed]);
+
+        assert!(table.contains("feat-x"));
+        assert!(table.contains("create"));
+        assert!(table.contains("dup"));
+    }
+
+    #[test]
+    fn test_format_recent_table() {
+        use crate::core::history::Visit;
+
+        let visit = Visit {
+            path: "/nonexistent/feat-x".to_string(),
+            dirname: "feat-x".to_string(),
+            visits: 3,
+            last_visited: chrono::Utc::now(),
+        };
+        let table = format_recent_table(&[visit]);
+
+        assert!(table.contains("feat-x"));
+        assert!(table.contains('3'));
+    }
+
+    #[test]
+    fn test_format_age() {
+        use chrono::Duration;
+
+        assert_eq!(format_age(Duration::seconds(30)), "0m");
+        assert_eq!(format_age(Duration::minutes(90)), "1h");
+        assert_eq!(format_age(Duration::days(3)), "3d");
+        assert_eq!(format_age(Duration::days(20)), "2w");
+        assert_eq!(format_age(Duration::days(800)), "2y");
+        assert_eq!(format_age(Duration::minutes(-5)), "0m");
+    }
+
     #[test]
     fn test_format_worktree_table() {
         let worktrees = vec![
@@ -201,11 +492,15 @@ mod tests {
         )];
 
         let json = format_worktree_json(worktrees);
-        // JSON output may have different spacing, check for key-value pairs more flexibly
-        assert!(json.contains("\"name\""));
-        assert!(json.contains("main"));
-        assert!(json.contains("\"is_current\""));
-        assert!(json.contains("true"));
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let record = &parsed[0];
+        assert_eq!(record["schema_version"], 1);
+        assert_eq!(record["dirname"], "main");
+        assert_eq!(record["branch"], "main");
+        assert_eq!(record["head"], "abc123");
+        assert_eq!(record["upstream"], "origin/main");
+        assert_eq!(record["is_current"], true);
+        assert!(record.get("name").is_none());
     }
 
     #[test]
@@ -214,5 +509,46 @@ mod tests {
         assert!(matches!(OutputFormat::from_str("JSON"), OutputFormat::Json));
         assert!(matches!(OutputFormat::from_str("table"), OutputFormat::Table));
         assert!(matches!(OutputFormat::from_str("invalid"), OutputFormat::Table));
+        assert!(matches!(OutputFormat::from_str("ndjson"), OutputFormat::Ndjson));
+        assert!(matches!(OutputFormat::from_str("porcelain"), OutputFormat::Porcelain));
+        assert!(matches!(
+            OutputFormat::from_str("Template={{dirname}}"),
+            OutputFormat::Template(t) if t == "{{dirname}}"
+        ));
+    }
+
+    #[test]
+    fn test_format_worktree_template() {
+        let worktrees = vec![
+            Worktree::new(
+                "main".to_string(),
+                "main".to_string(),
+                "/home/user/project".to_string(),
+                true,
+                false,
+                false,
+                Some("abc123".to_string()),
+                None,
+            ),
+            Worktree::new(
+                "feat-x".to_string(),
+                "feat/x".to_string(),
+                "/home/user/project.worktrees/feat-x".to_string(),
+                false,
+                false,
+                false,
+                Some("def456".to_string()),
+                None,
+            ),
+        ];
+
+        let template = Template::parse("{{dirname}}\\t{{branch}}").unwrap();
+        let output = format_worktree_template(&worktrees, &template);
+        assert_eq!(output, "main\tmain\nfeat-x\tfeat/x");
+        assert!(!output.contains('\u{1b}'));
+
+        let template = Template::parse("{{dirname}}\\0").unwrap();
+        let output = format_worktree_template(&worktrees, &template);
+        assert_eq!(output, "main\0feat-x\0");
     }
 }
@@ -0,0 +1,313 @@
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use serde::Serialize;
+
+/// 当前稳定输出格式的版本号
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// v1 记录的 JSON Schema（同时发布在仓库的 `schema/` 目录下）
+pub const WORKTREE_SCHEMA_V1: &str = include_str!("../../schema/worktree.v1.schema.json");
+
+/// 稳定的机器可读 worktree 记录（v1）
+///
+/// 与内部 `Worktree` 结构解耦：v1 内字段只增不删、不改名，
+/// 新增字段必须同步写入 `schema/worktree.v1.schema.json`。
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeRecordV1 {
+    pub schema_version: u32,
+    pub dirname: String,
+    pub path: String,
+    /// 分离 HEAD 或裸仓库时为 null
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    pub is_current: bool,
+    pub is_detached: bool,
+    pub is_bare: bool,
+    pub status: &'static str,
+    pub is_temporary: bool,
+    /// RFC 3339 时间，非临时 worktree 为 null
+    pub expires_at: Option<String>,
+    /// 没有别名时为 null
+    pub alias: Option<String>,
+    pub is_locked: bool,
+    /// 未锁定或没有原因时为 null
+    pub lock_reason: Option<String>,
+    pub is_prunable: bool,
+    /// 不可清除时为 null
+    pub prune_reason: Option<String>,
+    /// 进行中的操作（merge、rebase、cherry-pick、bisect），没有时为 null
+    pub operation: Option<&'static str>,
+    /// 变基的当前步骤和总步骤数，其他情况为 null
+    pub operation_step: Option<usize>,
+    pub operation_total: Option<usize>,
+}
+
+impl WorktreeRecordV1 {
+    /// 从内部 Worktree 结构构建稳定记录
+    pub fn from_worktree(worktree: &Worktree) -> Self {
+        WorktreeRecordV1 {
+            schema_version: SCHEMA_VERSION,
+            dirname: worktree.dirname.clone(),
+            path: worktree.path.clone(),
+            branch: if worktree.is_detached || worktree.is_bare {
+                None
+            } else {
+                Some(worktree.branch_name.clone())
+            },
+            head: worktree.head_commit.clone(),
+            upstream: worktree.upstream_branch.clone(),
+            is_current: worktree.is_current,
+            is_detached: worktree.is_detached,
+            is_bare: worktree.is_bare,
+            status: worktree.get_status().as_str(),
+            is_temporary: worktree.is_temporary,
+            expires_at: worktree.expires_at.map(|t| t.to_rfc3339()),
+            alias: worktree.alias.clone(),
+            is_locked: worktree.is_locked,
+            lock_reason: worktree.lock_reason.clone(),
+            is_prunable: worktree.is_prunable,
+            prune_reason: worktree.prune_reason.clone(),
+            operation: worktree.operation.as_ref().map(|op| op.kind.as_str()),
+            operation_step: worktree.operation.as_ref().and_then(|op| op.step),
+            operation_total: worktree.operation.as_ref().and_then(|op| op.total),
+        }
+    }
+}
+
+/// 校验 porcelain / schema 版本参数，目前只支持 v1
+pub fn check_version(version: &str) -> Result<()> {
+    match version {
+        "v1" | "1" => Ok(()),
+        other => Err(WorktreeError::UnsupportedSchemaVersion(other.to_string())),
+    }
+}
+
+/// 获取指定版本的 JSON Schema
+pub fn schema_for_version(version: &str) -> Result<&'static str> {
+    check_version(version)?;
+    Ok(WORKTREE_SCHEMA_V1)
+}
+
+/// 格式化为 NDJSON（每行一个 v1 记录）
+pub fn format_worktree_ndjson(worktrees: &[Worktree]) -> String {
+    worktrees
+        .iter()
+        .map(|wt| {
+            serde_json::to_string(&WorktreeRecordV1::from_worktree(wt))
+                .unwrap_or_else(|_| "{\"error\": \"Failed to serialize worktree\"}".to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 格式化为 porcelain v1 输出
+///
+/// 每个 worktree 是一组 `key value` 行（布尔值只在为真时输出为单独的键），
+/// 记录之间以空行分隔，与 `git worktree list --porcelain` 风格一致。
+pub fn format_worktree_porcelain_v1(worktrees: &[Worktree]) -> String {
+    let mut output = String::new();
+
+    for wt in worktrees {
+        let record = WorktreeRecordV1::from_worktree(wt);
+
+        output.push_str(&format!("worktree {}\n", record.path));
+        output.push_str(&format!("dirname {}\n", record.dirname));
+        if let Some(alias) = &record.alias {
+            output.push_str(&format!("alias {}\n", alias));
+        }
+        if let Some(head) = &record.head {
+            output.push_str(&format!("head {}\n", head));
+        }
+        if let Some(branch) = &record.branch {
+            output.push_str(&format!("branch {}\n", branch));
+        }
+        if let Some(upstream) = &record.upstream {
+            output.push_str(&format!("upstream {}\n", upstream));
+        }
+        output.push_str(&format!("status {}\n", record.status));
+        if let Some(operation) = record.operation {
+            match (record.operation_step, record.operation_total) {
+                (Some(step), Some(total)) => output.push_str(&format!("operation {} {}/{}\n", operation, step, total)),
+                _ => output.push_str(&format!("operation {}\n", operation)),
+            }
+        }
+        if record.is_current {
+            output.push_str("current\n");
+        }
+        if record.is_detached {
+            output.push_str("detached\n");
+        }
+        if record.is_bare {
+            output.push_str("bare\n");
+        }
+        if record.is_temporary {
+            output.push_str("temporary\n");
+        }
+        if let Some(expires_at) = &record.expires_at {
+            output.push_str(&format!("expires {}\n", expires_at));
+        }
+        // 与 git 相同：有原因时跟在键后面
+        for (flag, key, reason) in [
+            (record.is_locked, "locked", &record.lock_reason),
+            (record.is_prunable, "prunable", &record.prune_reason),
+        ] {
+            match reason {
+                Some(reason) if flag => output.push_str(&format!("{} {}\n", key, reason)),
+                _ if flag => output.push_str(&format!("{}\n", key)),
+                _ => {}
+            }
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn sample_worktree(detached: bool) -> Worktree {
+        Worktree::new(
+            "feat-x".to_string(),
+            if detached { "HEAD".to_string() } else { "feat/x".to_string() },
+            "/nonexistent/project.worktrees/feat-x".to_string(),
+            false,
+            false,
+            detached,
+            Some("abc123".to_string()),
+            None,
+        )
+    }
+
+    fn schema() -> serde_json::Value {
+        serde_json::from_str(WORKTREE_SCHEMA_V1).expect("schema file must be valid JSON")
+    }
+
+    #[test]
+    fn test_record_fields_match_schema() {
+        let schema = schema();
+        let properties: BTreeSet<String> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        let required: BTreeSet<String> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect();
+
+        let record = serde_json::to_value(WorktreeRecordV1::from_worktree(&sample_worktree(false))).unwrap();
+        let keys: BTreeSet<String> = record.as_object().unwrap().keys().cloned().collect();
+
+        // 每个输出字段都必须在 schema 中声明，且 v1 的必需字段都必须存在
+        assert_eq!(keys, properties);
+        assert!(required.is_subset(&keys));
+        assert_eq!(schema["properties"]["schema_version"]["const"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_record_field_types_match_schema() {
+        let record = serde_json::to_value(WorktreeRecordV1::from_worktree(&sample_worktree(true))).unwrap();
+
+        assert_eq!(record["schema_version"], 1);
+        assert!(record["branch"].is_null());
+        assert_eq!(record["head"], "abc123");
+        assert!(record["upstream"].is_null());
+        assert_eq!(record["is_detached"], true);
+        assert_eq!(record["status"], "detached");
+
+        let statuses = schema()["properties"]["status"]["enum"].clone();
+        assert!(statuses.as_array().unwrap().contains(&record["status"]));
+    }
+
+    #[test]
+    fn test_format_ndjson_one_object_per_line() {
+        let worktrees = vec![sample_worktree(false), sample_worktree(true)];
+        let output = format_worktree_ndjson(&worktrees);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["dirname"], "feat-x");
+        }
+    }
+
+    #[test]
+    fn test_format_porcelain_v1() {
+        let output = format_worktree_porcelain_v1(&[sample_worktree(false), sample_worktree(true)]);
+
+        assert_eq!(
+            output,
+            "worktree /nonexistent/project.worktrees/feat-x\n\
+             dirname feat-x\n\
+             head abc123\n\
+             branch feat/x\n\
+             status healthy\n\
+             \n\
+             worktree /nonexistent/project.worktrees/feat-x\n\
+             dirname feat-x\n\
+             head abc123\n\
+             status detached\n\
+             detached\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn test_bare_and_locked_records() {
+        let mut bare = Worktree::new("project".into(), "HEAD".into(), "/code/project/.bare".into(), false, true, false, None, None);
+        bare.is_main = true;
+        let record = WorktreeRecordV1::from_worktree(&bare);
+        assert!(record.branch.is_none());
+        assert_eq!(record.status, "bare");
+
+        let mut locked = sample_worktree(false);
+        locked.is_locked = true;
+        locked.lock_reason = Some("on usb drive".to_string());
+        locked.is_prunable = true;
+        let output = format_worktree_porcelain_v1(&[bare, locked]);
+        assert!(output.contains("dirname project\nstatus bare\nbare\n\n"));
+        assert!(output.contains("status prunable\nlocked on usb drive\nprunable\n\n"));
+
+        let statuses = schema()["properties"]["status"]["enum"].clone();
+        for status in ["bare", "prunable"] {
+            assert!(statuses.as_array().unwrap().contains(&serde_json::json!(status)));
+        }
+    }
+
+    #[test]
+    fn test_operation_in_progress_record() {
+        use crate::core::worktree::{GitOperation, OperationKind};
+
+        let mut rebasing = sample_worktree(true);
+        rebasing.operation = Some(GitOperation { kind: OperationKind::Rebase, step: Some(3), total: Some(7) });
+        let mut merging = sample_worktree(false);
+        merging.operation = Some(GitOperation { kind: OperationKind::Merge, step: None, total: None });
+
+        let record = serde_json::to_value(WorktreeRecordV1::from_worktree(&rebasing)).unwrap();
+        assert_eq!(record["operation"], "rebase");
+        assert_eq!(record["operation_step"], 3);
+        assert_eq!(record["operation_total"], 7);
+        let operations = schema()["properties"]["operation"]["enum"].clone();
+        assert!(operations.as_array().unwrap().contains(&record["operation"]));
+
+        let output = format_worktree_porcelain_v1(&[rebasing, merging]);
+        assert!(output.contains("status detached\noperation rebase 3/7\ndetached\n"));
+        assert!(output.contains("status healthy\noperation merge\n"));
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert!(check_version("v1").is_ok());
+        assert!(check_version("1").is_ok());
+        assert!(check_version("v2").is_err());
+        assert!(schema_for_version("v1").unwrap().contains("\"$schema\""));
+    }
+}
@@ -0,0 +1,350 @@
+use crate::core::git_ops::{self, WorktreeStatusInfo};
+use crate::core::worktree::{Worktree, WorktreeStatus};
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use std::cell::OnceCell;
+use std::path::Path;
+
+/// 模板中可引用的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+    Dirname,
+    Alias,
+    Branch,
+    Path,
+    IsCurrent,
+    IsBare,
+    IsDetached,
+    IsLocked,
+    IsPrunable,
+    Head,
+    Upstream,
+    LastModified,
+    Status,
+    Dirty,
+    Staged,
+    Modified,
+    Untracked,
+    Conflicts,
+    Operation,
+    Ahead,
+    Behind,
+}
+
+impl TemplateField {
+    /// 所有字段名及其对应字段（包含别名）
+    pub const NAMES: &'static [(&'static str, TemplateField)] = &[
+        ("dirname", TemplateField::Dirname),
+        ("name", TemplateField::Dirname),
+        ("alias", TemplateField::Alias),
+        ("branch", TemplateField::Branch),
+        ("branch_name", TemplateField::Branch),
+        ("path", TemplateField::Path),
+        ("is_current", TemplateField::IsCurrent),
+        ("current", TemplateField::IsCurrent),
+        ("is_bare", TemplateField::IsBare),
+        ("bare", TemplateField::IsBare),
+        ("is_detached", TemplateField::IsDetached),
+        ("detached", TemplateField::IsDetached),
+        ("is_locked", TemplateField::IsLocked),
+        ("locked", TemplateField::IsLocked),
+        ("is_prunable", TemplateField::IsPrunable),
+        ("prunable", TemplateField::IsPrunable),
+        ("head", TemplateField::Head),
+        ("head_commit", TemplateField::Head),
+        ("upstream", TemplateField::Upstream),
+        ("upstream_branch", TemplateField::Upstream),
+        ("last_modified", TemplateField::LastModified),
+        ("status", TemplateField::Status),
+        ("dirty", TemplateField::Dirty),
+        ("staged", TemplateField::Staged),
+        ("modified", TemplateField::Modified),
+        ("untracked", TemplateField::Untracked),
+        ("conflicts", TemplateField::Conflicts),
+        ("operation", TemplateField::Operation),
+        ("ahead", TemplateField::Ahead),
+        ("behind", TemplateField::Behind),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, field)| *field)
+    }
+}
+
+/// 模板片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(TemplateField),
+}
+
+/// 已解析的输出模板
+///
+/// 占位符写作 `{{field}}` 或 `{field}`，字面量中支持 `\t`、`\n`、`\r`、`\0`、
+/// `\\`、`\{`、`\}` 转义。
+#[derive(Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// 解析模板字符串
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some('t') => '\t',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('0') => '\0',
+                        Some('\\') => '\\',
+                        Some('{') => '{',
+                        Some('}') => '}',
+                        Some(other) => {
+                            return Err(WorktreeError::InvalidTemplate(tf(
+                                "error.detail.template_escape",
+                                &[("escape", &other.to_string())],
+                            )));
+                        }
+                        None => {
+                            return Err(WorktreeError::InvalidTemplate(t("error.detail.template_dangling")));
+                        }
+                    };
+                    literal.push(escaped);
+                }
+                '{' => {
+                    // 支持 {{field}} 与 {field} 两种写法
+                    let double = chars.peek() == Some(&'{');
+                    if double {
+                        chars.next();
+                    }
+
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+
+                    if !closed || (double && chars.next() != Some('}')) {
+                        return Err(WorktreeError::InvalidTemplate(tf(
+                            "error.detail.template_unterminated",
+                            &[("name", &name)],
+                        )));
+                    }
+
+                    let field = TemplateField::from_name(name.trim()).ok_or_else(|| {
+                        let available = TemplateField::NAMES.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ");
+                        WorktreeError::InvalidTemplate(tf(
+                            "error.detail.template_field",
+                            &[("name", name.trim()), ("available", &available)],
+                        ))
+                    })?;
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field));
+                }
+                '}' => {
+                    return Err(WorktreeError::InvalidTemplate(
+                        "Unmatched '}' (use '\\}' for a literal brace)".to_string(),
+                    ));
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Template { segments })
+    }
+
+    /// 模板是否以 NUL 结尾（此时记录之间不再追加换行）
+    pub fn ends_with_nul(&self) -> bool {
+        matches!(self.segments.last(), Some(Segment::Literal(s)) if s.ends_with('\0'))
+    }
+
+    /// 使用单个 worktree 渲染模板
+    pub fn render(&self, worktree: &Worktree) -> String {
+        let context = RenderContext::new(worktree);
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(s) => output.push_str(s),
+                Segment::Field(field) => output.push_str(&context.value(*field)),
+            }
+        }
+
+        output
+    }
+}
+
+/// 渲染上下文：按需获取需要调用 git 的状态字段，每个 worktree 最多查询一次
+struct RenderContext<'a> {
+    worktree: &'a Worktree,
+    status: OnceCell<Option<WorktreeStatusInfo>>,
+    ahead_behind: OnceCell<Option<(usize, usize)>>,
+}
+
+impl<'a> RenderContext<'a> {
+    fn new(worktree: &'a Worktree) -> Self {
+        RenderContext {
+            worktree,
+            status: OnceCell::new(),
+            ahead_behind: OnceCell::new(),
+        }
+    }
+
+    /// 裸仓库和目录已不存在的 worktree 没有工作区状态
+    fn status(&self) -> Option<&WorktreeStatusInfo> {
+        self.status
+            .get_or_init(|| {
+                if self.worktree.is_bare || self.worktree.is_prunable {
+                    return None;
+                }
+                git_ops::get_worktree_status(Path::new(&self.worktree.path)).ok()
+            })
+            .as_ref()
+    }
+
+    fn ahead_behind(&self) -> Option<(usize, usize)> {
+        *self.ahead_behind.get_or_init(|| {
+            git_ops::get_ahead_behind(Path::new(&self.worktree.path))
+                .ok()
+                .flatten()
+        })
+    }
+
+    fn value(&self, field: TemplateField) -> String {
+        let wt = self.worktree;
+        match field {
+            TemplateField::Dirname => wt.dirname.clone(),
+            TemplateField::Alias => wt.alias.clone().unwrap_or_default(),
+            TemplateField::Branch => wt.branch_name.clone(),
+            TemplateField::Path => wt.path.clone(),
+            TemplateField::IsCurrent => wt.is_current.to_string(),
+            TemplateField::IsBare => wt.is_bare.to_string(),
+            TemplateField::IsDetached => wt.is_detached.to_string(),
+            TemplateField::IsLocked => wt.is_locked.to_string(),
+            TemplateField::IsPrunable => wt.is_prunable.to_string(),
+            TemplateField::Head => wt.head_commit.clone().unwrap_or_default(),
+            TemplateField::Upstream => wt.upstream_branch.clone().unwrap_or_default(),
+            TemplateField::LastModified => wt.last_modified.to_rfc3339(),
+            TemplateField::Status => {
+                let status = if wt.is_bare {
+                    WorktreeStatus::Bare
+                } else if wt.is_prunable {
+                    WorktreeStatus::Prunable
+                } else if self.status().is_some_and(|s| !s.conflicted.is_empty()) {
+                    WorktreeStatus::Conflict
+                } else if wt.is_detached {
+                    WorktreeStatus::Detached
+                } else if self.status().map(|s| !s.is_clean()).unwrap_or(false) {
+                    WorktreeStatus::Modified
+                } else {
+                    WorktreeStatus::Healthy
+                };
+                status.as_str().to_string()
+            }
+            TemplateField::Dirty => self
+                .status()
+                .map(|s| !s.is_clean())
+                .unwrap_or(false)
+                .to_string(),
+            TemplateField::Staged => self.status().map(|s| s.staged.len()).unwrap_or(0).to_string(),
+            TemplateField::Modified => self.status().map(|s| s.modified.len()).unwrap_or(0).to_string(),
+            TemplateField::Untracked => self.status().map(|s| s.untracked.len()).unwrap_or(0).to_string(),
+            TemplateField::Conflicts => self.status().map(|s| s.conflicted.len()).unwrap_or(0).to_string(),
+            TemplateField::Operation => wt.operation.as_ref().map(|op| op.marker()).unwrap_or_default(),
+            TemplateField::Ahead => self
+                .ahead_behind()
+                .map(|(ahead, _)| ahead.to_string())
+                .unwrap_or_default(),
+            TemplateField::Behind => self
+                .ahead_behind()
+                .map(|(_, behind)| behind.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_worktree() -> Worktree {
+        Worktree::new(
+            "feat-login".to_string(),
+            "feat/login".to_string(),
+            "/nonexistent/project.worktrees/feat-login".to_string(),
+            false,
+            false,
+            false,
+            Some("abc123".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_render_double_and_single_braces() {
+        let wt = sample_worktree();
+        let template = Template::parse("{{dirname}} {branch}").unwrap();
+        assert_eq!(template.render(&wt), "feat-login feat/login");
+    }
+
+    #[test]
+    fn test_render_escapes() {
+        let wt = sample_worktree();
+        let template = Template::parse("{{dirname}}\\t{{head}}\\0").unwrap();
+        assert_eq!(template.render(&wt), "feat-login\tabc123\0");
+        assert!(template.ends_with_nul());
+
+        let template = Template::parse("\\{literal\\} \\\\").unwrap();
+        assert_eq!(template.render(&wt), "{literal} \\");
+        assert!(!template.ends_with_nul());
+    }
+
+    #[test]
+    fn test_render_missing_optional_fields_are_empty() {
+        let wt = sample_worktree();
+        let template = Template::parse("[{{upstream}}][{{operation}}]").unwrap();
+        assert_eq!(template.render(&wt), "[][]");
+    }
+
+    #[test]
+    fn test_render_operation_marker() {
+        use crate::core::worktree::{GitOperation, OperationKind};
+
+        let mut wt = sample_worktree();
+        wt.operation = Some(GitOperation { kind: OperationKind::Rebase, step: Some(3), total: Some(7) });
+        assert_eq!(Template::parse("{{operation}}").unwrap().render(&wt), "REBASING 3/7");
+
+        wt.operation = Some(GitOperation { kind: OperationKind::CherryPick, step: None, total: None });
+        assert_eq!(Template::parse("{{operation}}").unwrap().render(&wt), "CHERRY-PICKING");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{unknown}}").is_err());
+        assert!(Template::parse("{{dirname").is_err());
+        assert!(Template::parse("{{dirname}").is_err());
+        assert!(Template::parse("oops}").is_err());
+        assert!(Template::parse("\\x").is_err());
+        assert!(Template::parse("trailing\\").is_err());
+    }
+}
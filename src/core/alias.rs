@@ -0,0 +1,76 @@
+// worktree 别名：为目录名很长的 worktree 设置一个短名称，保存在 metadata.json 中，
+// 所有接受 worktree 名称的命令都可以使用（见 `Worktree::resolve`）。
+
+use crate::core::git_ops;
+use crate::core::metadata;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::path::validate_worktree_name;
+
+/// 检查别名是否可用：必须是合法的名称，且不能与其他 worktree 的目录名、别名或任何本地分支重名
+pub fn validate(alias: &str, target: &Worktree, worktrees: &[Worktree], branches: &[String]) -> Result<()> {
+    validate_worktree_name(alias)?;
+
+    let taken_by_worktree = worktrees.iter().any(|wt| {
+        wt.dirname == alias || (wt.path != target.path && wt.alias.as_deref() == Some(alias))
+    });
+    if taken_by_worktree || branches.iter().any(|branch| branch == alias) {
+        return Err(WorktreeError::AliasConflict(alias.to_string()));
+    }
+
+    Ok(())
+}
+
+/// 为 worktree 设置别名，替换它原有的别名
+pub fn set(alias: &str, target: &Worktree, worktrees: &[Worktree]) -> Result<()> {
+    validate(alias, target, worktrees, &git_ops::list_local_branches()?)?;
+    metadata::update(|metadata| {
+        metadata.worktrees.entry(target.path.clone()).or_default().alias = Some(alias.to_string());
+    })
+}
+
+/// 删除别名，别名不存在时返回 NotFound
+pub fn remove(alias: &str) -> Result<()> {
+    let mut metadata = metadata::Metadata::load()?;
+    let (path, meta) = metadata
+        .worktrees
+        .iter_mut()
+        .find(|(_, meta)| meta.alias.as_deref() == Some(alias))
+        .ok_or_else(|| WorktreeError::NotFound(alias.to_string()))?;
+    meta.alias = None;
+    // 没有其他属性时删除整个条目
+    if *meta == metadata::WorktreeMeta::default() {
+        let path = path.clone();
+        metadata.worktrees.remove(&path);
+    }
+    metadata.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worktree(dirname: &str, branch: &str, alias: Option<&str>) -> Worktree {
+        let mut wt = Worktree::new(dirname.to_string(), branch.to_string(), format!("/w/{}", dirname), false, false, false, None, None);
+        wt.alias = alias.map(str::to_string);
+        wt
+    }
+
+    #[test]
+    fn test_validate_rejects_collisions() {
+        let target = worktree("feature-auth-oauth", "feature/auth-oauth", Some("api"));
+        let other = worktree("web", "web", Some("ui"));
+        let worktrees = vec![target.clone(), other];
+        let branches = vec!["main".to_string(), "feature/auth-oauth".to_string(), "web".to_string()];
+
+        assert!(validate("oauth", &target, &worktrees, &branches).is_ok());
+        // 重新设置自己的别名
+        assert!(validate("api", &target, &worktrees, &branches).is_ok());
+
+        for taken in ["web", "ui", "main", "feature-auth-oauth"] {
+            assert!(matches!(validate(taken, &target, &worktrees, &branches), Err(WorktreeError::AliasConflict(_))), "{}", taken);
+        }
+        assert!(validate("a/b", &target, &worktrees, &branches).is_err());
+        assert!(validate("", &target, &worktrees, &branches).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+// 共享构建缓存：`work create` 时按 `work.cache.*` 配置为新 worktree 写入本地配置文件，
+// 或从已有 worktree 复制构建目录，避免每个 worktree 都从头编译。默认全部关闭。
+//
+// - cache.cargo-target-dir  共享的 CARGO_TARGET_DIR 模板，写入 `.cargo/config.toml` 的 build.target-dir
+// - cache.sccache           为真时写入 build.rustc-wrapper = "sccache"
+// - cache.pnpm-store        共享的 pnpm store 路径，写入 `.npmrc` 的 store-dir
+// - cache.seed              从源 worktree 复制的目录（逗号分隔，如 `target,node_modules`）
+// - cache.seed-mode         复制方式：reflink（默认，不支持时退回普通复制）、hardlink 或 copy
+//
+// 路径模板支持 `~` 和占位符 `{repo}`、`{branch}`、`{family}`（分支名第一个 `/` 之前的部分）、`{dirname}`。
+// 写入的文件会加入仓库的 info/exclude，新 worktree 不会因此显示为有未提交的更改。
+
+use crate::core::config;
+use crate::core::git_ops;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 构建目录的复制方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedMode {
+    Reflink,
+    Hardlink,
+    Copy,
+}
+
+impl SeedMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "reflink" => Some(SeedMode::Reflink),
+            "hardlink" => Some(SeedMode::Hardlink),
+            "copy" => Some(SeedMode::Copy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeedMode::Reflink => "reflink",
+            SeedMode::Hardlink => "hardlink",
+            SeedMode::Copy => "copy",
+        }
+    }
+}
+
+/// `work.cache.*` 配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub cargo_target_dir: Option<String>,
+    pub sccache: bool,
+    pub pnpm_store: Option<String>,
+    pub seed: Vec<String>,
+    pub seed_mode: SeedMode,
+}
+
+impl CacheConfig {
+    pub fn from_config() -> Result<Self> {
+        let seed_mode = match config::get("cache.seed-mode") {
+            Some(value) => SeedMode::parse(&value).ok_or_else(|| {
+                WorktreeError::InvalidArgument(tf("error.detail.unknown_seed_mode", &[("value", &value)]))
+            })?,
+            None => SeedMode::Reflink,
+        };
+
+        Ok(CacheConfig {
+            cargo_target_dir: config::get("cache.cargo-target-dir"),
+            sccache: config::get_bool("cache.sccache"),
+            pnpm_store: config::get("cache.pnpm-store"),
+            seed: config::get_all("cache.seed")
+                .iter()
+                .flat_map(|value| value.split(','))
+                .map(|dir| dir.trim().trim_matches('/').to_string())
+                .filter(|dir| !dir.is_empty())
+                .collect(),
+            seed_mode,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.cargo_target_dir.is_some() || self.sccache || self.pnpm_store.is_some() || !self.seed.is_empty()
+    }
+}
+
+/// 对新 worktree 执行的一项缓存设置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheAction {
+    /// 写入了配置文件（相对 worktree 的路径）
+    Wrote(String),
+    /// 文件已存在，未覆盖
+    SkippedExisting(String),
+    /// 从源 worktree 复制了目录
+    Seeded { dir: String, mode: SeedMode },
+}
+
+/// 分支族：分支名第一个 `/` 之前的部分，如 `feat/login` 为 `feat`
+pub fn branch_family(branch: &str) -> &str {
+    branch.split('/').next().unwrap_or(branch)
+}
+
+/// 展开路径模板
+pub fn expand(template: &str, repo: &str, branch: &str, dirname: &str) -> String {
+    let expanded = template
+        .replace("{repo}", repo)
+        .replace("{family}", &git_ops::branch_to_dirname(branch_family(branch)))
+        .replace("{branch}", &git_ops::branch_to_dirname(branch))
+        .replace("{dirname}", dirname);
+
+    match (expanded.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home.trim_end_matches('/'), rest),
+        _ => expanded,
+    }
+}
+
+/// `.cargo/config.toml` 的内容
+pub fn cargo_config(target_dir: Option<&str>, sccache: bool) -> String {
+    let mut content = String::from("# Generated by work (work.cache.*)\n[build]\n");
+    if let Some(dir) = target_dir {
+        content.push_str(&format!("target-dir = {}\n", toml_string(dir)));
+    }
+    if sccache {
+        content.push_str("rustc-wrapper = \"sccache\"\n");
+    }
+    content
+}
+
+/// TOML 基本字符串
+pub fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 为新 worktree 设置共享缓存；seed_from 为复制构建目录的源 worktree
+pub fn apply(config: &CacheConfig, worktree_path: &Path, branch: &str, dirname: &str, seed_from: Option<&Path>) -> Result<Vec<CacheAction>> {
+    let repo = repo_name()?;
+    let mut actions = Vec::new();
+    let mut written = Vec::new();
+
+    if config.cargo_target_dir.is_some() || config.sccache {
+        let target_dir = config.cargo_target_dir.as_deref().map(|t| expand(t, &repo, branch, dirname));
+        let content = cargo_config(target_dir.as_deref(), config.sccache);
+        actions.push(write_new(worktree_path, ".cargo/config.toml", &content, &mut written)?);
+    }
+
+    if let Some(store) = &config.pnpm_store {
+        let content = format!("# Generated by work (work.cache.pnpm-store)\nstore-dir={}\n", expand(store, &repo, branch, dirname));
+        actions.push(write_new(worktree_path, ".npmrc", &content, &mut written)?);
+    }
+
+    if let Some(source) = seed_from.filter(|source| *source != worktree_path) {
+        for dir in &config.seed {
+            let from = source.join(dir);
+            let to = worktree_path.join(dir);
+            if !from.is_dir() || to.exists() {
+                continue;
+            }
+            seed(&from, &to, config.seed_mode)?;
+            actions.push(CacheAction::Seeded { dir: dir.clone(), mode: config.seed_mode });
+        }
+    }
+
+    if !written.is_empty() {
+        git_ops::add_info_exclude(&written)?;
+    }
+
+    Ok(actions)
+}
+
+/// 写入新文件，已存在（如仓库中已跟踪）时不覆盖
+fn write_new(root: &Path, relative: &str, content: &str, written: &mut Vec<String>) -> Result<CacheAction> {
+    let path = root.join(relative);
+    if path.exists() {
+        return Ok(CacheAction::SkippedExisting(relative.to_string()));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, content)?;
+    written.push(relative.to_string());
+    Ok(CacheAction::Wrote(relative.to_string()))
+}
+
+/// 仓库名：主 worktree 的目录名
+fn repo_name() -> Result<String> {
+    let root = git_ops::get_main_repository_root()?;
+    Ok(root.file_name().and_then(|n| n.to_str()).unwrap_or("repo").to_string())
+}
+
+/// 复制构建目录
+pub fn seed(from: &Path, to: &Path, mode: SeedMode) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    match mode {
+        // 文件系统不支持时 cp 会退回普通复制
+        SeedMode::Reflink if reflink_copy(from, to) => Ok(()),
+        SeedMode::Hardlink => copy_tree(from, to, true),
+        _ => copy_tree(from, to, false),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reflink_copy(from: &Path, to: &Path) -> bool {
+    Command::new("cp").arg("-a").arg("--reflink=auto").arg(from).arg(to).status().is_ok_and(|s| s.success())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink_copy(from: &Path, to: &Path) -> bool {
+    Command::new("cp").arg("-c").arg("-pR").arg(from).arg(to).status().is_ok_and(|s| s.success())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink_copy(_from: &Path, _to: &Path) -> bool {
+    false
+}
+
+/// 递归复制目录，link 为 true 时为文件创建硬链接；保留符号链接
+fn copy_tree(from: &Path, to: &Path, link: bool) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target: PathBuf = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_tree(&source, &target, link)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else if link {
+            std::fs::hard_link(&source, &target)?;
+        } else {
+            std::fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    std::fs::copy(source, target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_branch_family() {
+        assert_eq!(branch_family("feat/login"), "feat");
+        assert_eq!(branch_family("release/1.2/hotfix"), "release");
+        assert_eq!(branch_family("main"), "main");
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("/cache/{repo}/{family}/{branch}/{dirname}", "app", "feat/login", "feat-login"),
+            "/cache/app/feat/feat-login/feat-login"
+        );
+        if let Ok(home) = std::env::var("HOME") {
+            assert_eq!(expand("~/.cache/{repo}", "app", "main", "main"), format!("{}/.cache/app", home.trim_end_matches('/')));
+        }
+    }
+
+    #[test]
+    fn test_cargo_config() {
+        let content = cargo_config(Some("/cache/app/feat"), true);
+        assert!(content.contains("[build]\n"));
+        assert!(content.contains("target-dir = \"/cache/app/feat\"\n"));
+        assert!(content.contains("rustc-wrapper = \"sccache\"\n"));
+        assert!(!cargo_config(None, true).contains("target-dir"));
+    }
+
+    #[test]
+    fn test_parse_seed_mode() {
+        assert_eq!(SeedMode::parse("Reflink"), Some(SeedMode::Reflink));
+        assert_eq!(SeedMode::parse("hardlink"), Some(SeedMode::Hardlink));
+        assert_eq!(SeedMode::parse("rsync"), None);
+    }
+
+    #[test]
+    fn test_seed_copies_and_links() {
+        let temp = TempDir::new().unwrap();
+        let from = temp.path().join("a/target");
+        std::fs::create_dir_all(from.join("debug")).unwrap();
+        std::fs::write(from.join("debug/bin"), "bin").unwrap();
+
+        for mode in [SeedMode::Reflink, SeedMode::Hardlink, SeedMode::Copy] {
+            let to = temp.path().join(mode.as_str()).join("target");
+            seed(&from, &to, mode).unwrap();
+            assert_eq!(std::fs::read_to_string(to.join("debug/bin")).unwrap(), "bin", "{:?}", mode);
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(std::fs::metadata(from.join("debug/bin")).unwrap().nlink(), 2);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// work carry：把一个 worktree 中未提交的更改转移到另一个 worktree
+//
+// 更改以二进制补丁的形式转移（包含未跟踪文件），目标 worktree 中应用成功后
+// 才会从源 worktree 中清除；应用到目标后所有更改都是未暂存状态。
+
+use crate::core::git_ops::{self, WorktreeStatusInfo};
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use std::path::Path;
+
+/// 从状态项中取出实际文件（重命名记录为 `old -> new`，两者都需要处理）
+fn status_paths(entry: &str) -> Vec<String> {
+    entry.split(" -> ").map(str::to_string).collect()
+}
+
+/// 选出要转移的文件：暂存、修改和未跟踪的文件，可按路径过滤（路径本身或其下的文件）
+pub fn select_files(status: &WorktreeStatusInfo, filters: &[String]) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    let all = status.staged.iter().chain(&status.modified).chain(&status.untracked);
+
+    for file in all.flat_map(|entry| status_paths(entry)) {
+        if !files.contains(&file) && matches_filter(&file, filters) {
+            files.push(file);
+        }
+    }
+
+    files
+}
+
+fn matches_filter(file: &str, filters: &[String]) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+
+    filters.iter().any(|filter| {
+        let filter = filter.trim_end_matches('/');
+        let file = file.trim_end_matches('/');
+        // 未跟踪目录显示为 `dir/`，过滤条件可能指向其中的文件
+        file == filter || file.starts_with(&format!("{}/", filter)) || filter.starts_with(&format!("{}/", file))
+    })
+}
+
+/// 把更改从 from 转移到 to，返回转移的文件；copy 为 true 时保留源 worktree 中的更改
+pub fn carry(from: &Worktree, to: &Worktree, filters: &[String], copy: bool) -> Result<Vec<String>> {
+    if from.path == to.path {
+        return Err(WorktreeError::InvalidArgument(t("error.detail.carry_same_worktree")));
+    }
+
+    let from_path = Path::new(&from.path);
+    let status = git_ops::get_worktree_status(from_path)?;
+    let files = select_files(&status, filters);
+    let nothing = || WorktreeError::NothingToSelect(tf("error.detail.nothing_to_carry", &[("name", &from.dirname)]));
+    if files.is_empty() {
+        return Err(nothing());
+    }
+
+    let patch = git_ops::diff_with_untracked(from_path, &files)?;
+    if patch.is_empty() {
+        return Err(nothing());
+    }
+
+    // 目标应用失败时源 worktree 保持不变
+    git_ops::apply_patch(Path::new(&to.path), &patch, false)?;
+
+    if !copy {
+        let staged: Vec<String> = status
+            .staged
+            .iter()
+            .flat_map(|entry| status_paths(entry))
+            .filter(|file| files.contains(file))
+            .collect();
+        git_ops::unstage_paths(from_path, &staged)?;
+        git_ops::apply_patch(from_path, &patch, true)?;
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    fn worktree(dirname: &str, path: &Path) -> Worktree {
+        let path = path.to_string_lossy().to_string();
+        Worktree::new(dirname.to_string(), dirname.to_string(), path, false, false, false, None, None)
+    }
+
+    /// 创建带一个提交的仓库和一个额外的 worktree
+    fn setup() -> (TempDir, Worktree, Worktree) {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test User"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("tracked.txt"), "one\n").unwrap();
+        std::fs::write(repo.join("staged.txt"), "one\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+
+        let other = temp.path().join("other");
+        git(&repo, &["worktree", "add", "-q", "-b", "other", other.to_str().unwrap()]);
+
+        let from = worktree("repo", &repo);
+        let to = worktree("other", &other);
+        (temp, from, to)
+    }
+
+    #[test]
+    fn test_select_files_with_filters() {
+        let status = WorktreeStatusInfo {
+            staged: vec!["a.txt".to_string(), "old.txt -> new.txt".to_string()],
+            modified: vec!["a.txt".to_string(), "src/lib.rs".to_string()],
+            untracked: vec!["notes/".to_string()],
+            conflicted: Vec::new(),
+        };
+
+        assert_eq!(select_files(&status, &[]), vec!["a.txt", "old.txt", "new.txt", "src/lib.rs", "notes/"]);
+        assert_eq!(select_files(&status, &["src".to_string()]), vec!["src/lib.rs"]);
+        assert_eq!(select_files(&status, &["notes/todo.md".to_string()]), vec!["notes/"]);
+    }
+
+    #[test]
+    fn test_carry_moves_all_kinds_of_changes() {
+        let (_temp, from, to) = setup();
+        let src = Path::new(&from.path);
+        let dst = Path::new(&to.path);
+
+        std::fs::write(src.join("tracked.txt"), "two\n").unwrap();
+        std::fs::write(src.join("staged.txt"), "staged\n").unwrap();
+        git(src, &["add", "staged.txt"]);
+        std::fs::write(src.join("new.bin"), [0u8, 159, 146, 150, 0, 1]).unwrap();
+
+        let files = carry(&from, &to, &[], false).unwrap();
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(std::fs::read_to_string(dst.join("tracked.txt")).unwrap(), "two\n");
+        assert_eq!(std::fs::read_to_string(dst.join("staged.txt")).unwrap(), "staged\n");
+        assert_eq!(std::fs::read(dst.join("new.bin")).unwrap(), vec![0u8, 159, 146, 150, 0, 1]);
+
+        // 源 worktree 恢复干净
+        assert_eq!(git(src, &["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn test_carry_copy_and_failed_apply_keep_source() {
+        let (_temp, from, to) = setup();
+        let src = Path::new(&from.path);
+        let dst = Path::new(&to.path);
+
+        std::fs::write(src.join("tracked.txt"), "two\n").unwrap();
+        carry(&from, &to, &["tracked.txt".to_string()], true).unwrap();
+        assert_eq!(std::fs::read_to_string(src.join("tracked.txt")).unwrap(), "two\n");
+        assert_eq!(std::fs::read_to_string(dst.join("tracked.txt")).unwrap(), "two\n");
+
+        // 目标中同一文件已有不同的更改，应用失败后源保持不变
+        std::fs::write(src.join("tracked.txt"), "three\n").unwrap();
+        assert!(matches!(carry(&from, &to, &[], false), Err(WorktreeError::GitError(_))));
+        assert_eq!(std::fs::read_to_string(src.join("tracked.txt")).unwrap(), "three\n");
+
+        assert!(matches!(carry(&from, &from, &[], false), Err(WorktreeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_carry_non_ascii_paths() {
+        let (_temp, from, to) = setup();
+        let src = Path::new(&from.path);
+        let dst = Path::new(&to.path);
+
+        std::fs::write(src.join("café.txt"), "one\n").unwrap();
+        git(src, &["add", "café.txt"]);
+        std::fs::create_dir(src.join("文档")).unwrap();
+        std::fs::write(src.join("文档/说明 1.md"), "hi\n").unwrap();
+
+        let files = carry(&from, &to, &["文档".to_string(), "café.txt".to_string()], false).unwrap();
+        assert_eq!(files, vec!["café.txt", "文档/"]);
+        assert_eq!(std::fs::read_to_string(dst.join("café.txt")).unwrap(), "one\n");
+        assert_eq!(std::fs::read_to_string(dst.join("文档/说明 1.md")).unwrap(), "hi\n");
+        assert_eq!(git(src, &["status", "--porcelain"]), "");
+    }
+}
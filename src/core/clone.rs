@@ -0,0 +1,138 @@
+// 以裸仓库为主的克隆布局（`work clone`）
+//
+//   <目录>/.bare     裸仓库
+//   <目录>/.git      文件 `gitdir: ./.bare`，让 git 和 work 在目录中找到仓库
+//   <目录>/<dirname> 每个分支一个 worktree，第一个为默认分支
+//
+// 裸克隆默认不配置 `remote.origin.fetch`，这里补上并重新获取，使远程跟踪分支和 `origin/HEAD` 可用。
+
+use crate::core::git_ops;
+use crate::utils::errors::{Result, WorktreeError};
+use std::path::{Path, PathBuf};
+
+/// 裸仓库的目录名
+pub const BARE_DIR: &str = ".bare";
+
+/// 克隆的结果
+#[derive(Debug)]
+pub struct Cloned {
+    /// 仓库目录（包含 `.bare` 和 `.git`）
+    pub root: PathBuf,
+    /// 默认分支
+    pub branch: String,
+    /// 默认分支的 worktree 路径，远程仓库为空时为 None
+    pub worktree_path: Option<String>,
+}
+
+/// 从 URL 或路径推断目录名：最后一段去掉 `.git` 后缀
+pub fn default_dir(source: &str) -> Option<String> {
+    let last = source.trim_end_matches('/').rsplit(['/', ':', '\\']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (!name.is_empty() && name != "." && name != "..").then(|| name.to_string())
+}
+
+/// 把 source 克隆到 dir（不存在或为空）并检出默认分支；失败时还原目录
+pub fn clone(source: &str, dir: &Path) -> Result<Cloned> {
+    let existed = dir.exists();
+    if existed && std::fs::read_dir(dir)?.next().is_some() {
+        return Err(WorktreeError::AlreadyExists(dir.display().to_string()));
+    }
+    std::fs::create_dir_all(dir)?;
+    let root = dir.canonicalize()?;
+
+    let result = clone_into(source, &root);
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&root);
+        if existed {
+            let _ = std::fs::create_dir(&root);
+        }
+    }
+    result
+}
+
+fn clone_into(source: &str, root: &Path) -> Result<Cloned> {
+    // git 在 root 中执行，本地路径需要先转为绝对路径
+    let source = match Path::new(source).canonicalize() {
+        Ok(path) => path.to_string_lossy().to_string(),
+        Err(_) => source.to_string(),
+    };
+    let git = |args: &[&str]| -> Result<String> {
+        let output = git_ops::run_git_bytes_in(root, args)?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    };
+
+    git(&["clone", "--bare", "--quiet", &source, BARE_DIR])?;
+    std::fs::write(root.join(".git"), format!("gitdir: ./{}\n", BARE_DIR))?;
+    git(&["config", "remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*"])?;
+    git(&["fetch", "--quiet", "origin"])?;
+
+    let branch = git(&["symbolic-ref", "--short", "HEAD"])?;
+    if git(&["rev-parse", "--verify", "--quiet", &format!("refs/remotes/origin/{}", branch)]).is_err() {
+        // 空仓库：没有可检出的分支
+        return Ok(Cloned { root: root.to_path_buf(), branch, worktree_path: None });
+    }
+
+    git(&["symbolic-ref", "refs/remotes/origin/HEAD", &format!("refs/remotes/origin/{}", branch)])?;
+    git(&["branch", "--quiet", &format!("--set-upstream-to=origin/{}", branch), &branch])?;
+
+    let worktree_path = root.join(git_ops::branch_to_dirname(&branch)).to_string_lossy().to_string();
+    git_ops::add_worktree_in(root, &branch, &worktree_path)?;
+
+    Ok(Cloned { root: root.to_path_buf(), branch, worktree_path: Some(worktree_path) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_default_dir() {
+        assert_eq!(default_dir("https://github.com/org/project.git").as_deref(), Some("project"));
+        assert_eq!(default_dir("git@github.com:org/project.git").as_deref(), Some("project"));
+        assert_eq!(default_dir("git@host:project").as_deref(), Some("project"));
+        assert_eq!(default_dir("../code/project/").as_deref(), Some("project"));
+        assert_eq!(default_dir("/"), None);
+        assert_eq!(default_dir(".."), None);
+    }
+
+    #[test]
+    fn test_clone_creates_bare_layout() {
+        let temp = TempDir::new().unwrap();
+        let origin = temp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        git(&origin, &["init", "--quiet", "--initial-branch=trunk"]);
+        git(&origin, &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "--allow-empty", "-m", "init"]);
+        git(&origin, &["branch", "feat/x"]);
+
+        let cloned = clone(origin.to_str().unwrap(), &temp.path().join("project")).unwrap();
+        let root = &cloned.root;
+        assert_eq!(cloned.branch, "trunk");
+        assert!(git_ops::is_bare_layout(root));
+        assert_eq!(git(root, &["config", "--bool", "core.bare"]), "true");
+        assert_eq!(git(root, &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"]), "origin/trunk");
+        assert_eq!(git(root, &["rev-parse", "--verify", "--quiet", "refs/remotes/origin/feat/x"]).len(), 40);
+
+        let worktree = PathBuf::from(cloned.worktree_path.unwrap());
+        assert_eq!(worktree, root.join("trunk"));
+        assert_eq!(git(&worktree, &["rev-parse", "--abbrev-ref", "@{upstream}"]), "origin/trunk");
+
+        // 目录非空时拒绝克隆
+        assert!(matches!(clone(origin.to_str().unwrap(), root), Err(WorktreeError::AlreadyExists(_))));
+    }
+
+    #[test]
+    fn test_clone_failure_removes_directory() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("project");
+        assert!(clone(temp.path().join("missing").to_str().unwrap(), &dir).is_err());
+        assert!(!dir.exists());
+    }
+}
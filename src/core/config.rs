@@ -0,0 +1,131 @@
+use crate::core::git_ops;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+
+/// 所有支持的配置项（不含 `work.` 前缀）
+pub const KNOWN_KEYS: &[&str] = &[
+    "language", "editor", "workspace.auto", "session.multiplexer", "session.window", "scratch.ttl", "trash.retention",
+    "cache.cargo-target-dir", "cache.sccache", "cache.pnpm-store", "cache.seed", "cache.seed-mode",
+    "env.ports", "env.port-block", "env.template", "env.loader", "env.allow",
+];
+
+/// 读取配置项 `work.<key>`（git config，可用 `--global` 设置全局默认），未设置时返回 None
+pub fn get(key: &str) -> Option<String> {
+    let output = git_ops::git()
+        .args(["config", "--get", &format!("work.{}", key)])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// 读取布尔配置项：true / yes / on / 1 为真，未设置时为假
+pub fn get_bool(key: &str) -> bool {
+    get(key).is_some_and(|v| matches!(v.to_lowercase().as_str(), "true" | "yes" | "on" | "1"))
+}
+
+/// 读取多值配置项的所有值（如 `work.session.window`），按配置顺序返回
+pub fn get_all(key: &str) -> Vec<String> {
+    let Ok(output) = git_ops::git()
+        .args(["config", "--get-all", &format!("work.{}", key)])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 写入配置项，global 为 true 时写入用户级配置
+///
+/// 多值配置项已有多个值时，全部替换为这一个值
+pub fn set(key: &str, value: &str, global: bool) -> Result<()> {
+    check_key(key)?;
+    run_config(global, &["--replace-all", &format!("work.{}", key), value])
+}
+
+/// 为多值配置项追加一个值
+pub fn add(key: &str, value: &str, global: bool) -> Result<()> {
+    check_key(key)?;
+    run_config(global, &["--add", &format!("work.{}", key), value])
+}
+
+/// 删除配置项，未设置时不报错
+pub fn unset(key: &str, global: bool) -> Result<()> {
+    check_key(key)?;
+    match run_config(global, &["--unset-all", &format!("work.{}", key)]) {
+        // git config --unset-all 在配置项不存在时返回 5
+        Err(WorktreeError::GitError(_)) if get_all(key).is_empty() => Ok(()),
+        result => result,
+    }
+}
+
+/// 检查是否为支持的配置项
+pub fn check_key(key: &str) -> Result<()> {
+    if KNOWN_KEYS.contains(&key) {
+        Ok(())
+    } else {
+        Err(WorktreeError::InvalidArgument(tf(
+            "error.detail.unknown_config_key",
+            &[("key", key), ("known", &KNOWN_KEYS.join(", "))],
+        )))
+    }
+}
+
+fn run_config(global: bool, args: &[&str]) -> Result<()> {
+    let mut command = git_ops::git();
+    command.arg("config");
+    if global {
+        command.arg("--global");
+    }
+    let output = command.args(args).output().map_err(WorktreeError::git_spawn)?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_key() {
+        assert!(check_key("language").is_ok());
+        assert!(matches!(check_key("nope"), Err(WorktreeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_set_replaces_all_values_of_multi_valued_key() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Command::new("git").args(["init", "--quiet"]).current_dir(temp_dir.path()).status().unwrap().success());
+
+        git_ops::in_repo(temp_dir.path(), || {
+            add("session.window", "edit: nvim", false).unwrap();
+            add("session.window", "shell", false).unwrap();
+            assert_eq!(get_all("session.window"), vec!["edit: nvim", "shell"]);
+
+            set("session.window", "dev: npm run dev", false).unwrap();
+            assert_eq!(get_all("session.window"), vec!["dev: npm run dev"]);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,406 @@
+// 磁盘占用统计：按 git 的视角把 worktree 中的内容分为已跟踪、未跟踪和被忽略三类
+//
+// 被忽略的内容来自 `git ls-files --others --ignored --exclude-standard --directory`，
+// 整个被忽略的目录只出现一次（如 `target/`），因此可以直接统计“最重”的被忽略目录，
+// 也保证 `--clean-ignored` 只会删除 git 认为被忽略的内容。
+
+use crate::core::git_ops;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 大小：apparent 为文件长度之和，disk 为实际占用的磁盘块（同一文件的多个硬链接只计一次）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    pub apparent: u64,
+    pub disk: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.apparent += other.apparent;
+        self.disk += other.disk;
+    }
+}
+
+/// 一个被忽略的目录或文件
+#[derive(Debug, Clone, Serialize)]
+pub struct IgnoredEntry {
+    /// 相对 worktree 根目录的路径，目录以 `/` 结尾
+    pub path: String,
+    pub size: Usage,
+}
+
+/// 单个 worktree 的占用情况
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeUsage {
+    pub dirname: String,
+    pub path: String,
+    pub total: Usage,
+    pub tracked: Usage,
+    pub untracked: Usage,
+    pub ignored: Usage,
+    /// 最大的被忽略目录，按占用从大到小
+    pub top_ignored: Vec<IgnoredEntry>,
+}
+
+/// 排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Apparent,
+    Disk,
+    Ignored,
+}
+
+impl SortKey {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "name" => Some(SortKey::Name),
+            "apparent" => Some(SortKey::Apparent),
+            "disk" => Some(SortKey::Disk),
+            "ignored" => Some(SortKey::Ignored),
+            _ => None,
+        }
+    }
+}
+
+/// 排序：名称升序，其余按大小降序
+pub fn sort(usages: &mut [WorktreeUsage], key: SortKey) {
+    match key {
+        SortKey::Name => usages.sort_by(|a, b| a.dirname.cmp(&b.dirname)),
+        SortKey::Apparent => usages.sort_by_key(|u| std::cmp::Reverse(u.total.apparent)),
+        SortKey::Disk => usages.sort_by_key(|u| std::cmp::Reverse(u.total.disk)),
+        SortKey::Ignored => usages.sort_by_key(|u| std::cmp::Reverse(u.ignored.disk)),
+    }
+}
+
+/// 统计多个 worktree 的占用，每个 worktree 在单独的线程中遍历
+pub fn measure_all(worktrees: &[&Worktree], top: usize) -> Result<Vec<WorktreeUsage>> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = worktrees
+            .iter()
+            .map(|wt| scope.spawn(move || measure(wt, top)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|_| Err(WorktreeError::GitError("du worker panicked".to_string()))))
+            .collect()
+    })
+}
+
+/// 统计单个 worktree 的占用（不含 `.git`，主仓库的对象库由所有 worktree 共享）
+pub fn measure(worktree: &Worktree, top: usize) -> Result<WorktreeUsage> {
+    let root = Path::new(&worktree.path);
+    let tracked: HashSet<String> = git_paths(root, &["ls-files", "-z"])?.into_iter().collect();
+    let ignored: HashSet<String> = ignored_paths(root)?.into_iter().collect();
+
+    let mut walker = Walker::new(&tracked, &ignored);
+    walker.walk(root, "")?;
+
+    let mut total = walker.tracked_usage;
+    total.add(walker.untracked_usage);
+    total.add(walker.ignored_usage);
+
+    let mut top_ignored = walker.ignored_entries;
+    top_ignored.retain(|entry| entry.path.ends_with('/'));
+    top_ignored.sort_by_key(|entry| std::cmp::Reverse(entry.size.disk));
+    top_ignored.truncate(top);
+
+    Ok(WorktreeUsage {
+        dirname: worktree.dirname.clone(),
+        path: worktree.path.clone(),
+        total,
+        tracked: walker.tracked_usage,
+        untracked: walker.untracked_usage,
+        ignored: walker.ignored_usage,
+        top_ignored,
+    })
+}
+
+struct Walker<'a> {
+    tracked: &'a HashSet<String>,
+    ignored: &'a HashSet<String>,
+    seen_inodes: HashSet<(u64, u64)>,
+    tracked_usage: Usage,
+    untracked_usage: Usage,
+    ignored_usage: Usage,
+    ignored_entries: Vec<IgnoredEntry>,
+}
+
+impl<'a> Walker<'a> {
+    fn new(tracked: &'a HashSet<String>, ignored: &'a HashSet<String>) -> Self {
+        Walker {
+            tracked,
+            ignored,
+            seen_inodes: HashSet::new(),
+            tracked_usage: Usage::default(),
+            untracked_usage: Usage::default(),
+            ignored_usage: Usage::default(),
+            ignored_entries: Vec::new(),
+        }
+    }
+
+    fn walk(&mut self, dir: &Path, prefix: &str) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if prefix.is_empty() && name == ".git" {
+                continue;
+            }
+            let relative = format!("{}{}", prefix, name);
+            let metadata = entry.path().symlink_metadata()?;
+
+            if metadata.is_dir() {
+                let dir_key = format!("{}/", relative);
+                if self.ignored.contains(&dir_key) {
+                    let size = self.tree_size(&entry.path())?;
+                    self.ignored_usage.add(size);
+                    self.ignored_entries.push(IgnoredEntry { path: dir_key, size });
+                } else if self.tracked.contains(&relative) {
+                    // 子模块在父仓库中只是一个已跟踪的条目
+                    let size = self.tree_size(&entry.path())?;
+                    self.tracked_usage.add(size);
+                } else {
+                    self.walk(&entry.path(), &dir_key)?;
+                }
+                continue;
+            }
+
+            let size = self.file_size(&metadata);
+            if self.ignored.contains(&relative) {
+                self.ignored_usage.add(size);
+                self.ignored_entries.push(IgnoredEntry { path: relative, size });
+            } else if self.tracked.contains(&relative) {
+                self.tracked_usage.add(size);
+            } else {
+                self.untracked_usage.add(size);
+            }
+        }
+        Ok(())
+    }
+
+    /// 目录下所有内容的大小
+    fn tree_size(&mut self, dir: &Path) -> Result<Usage> {
+        let mut usage = Usage::default();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.path().symlink_metadata()?;
+            if metadata.is_dir() {
+                usage.add(self.tree_size(&entry.path())?);
+            } else {
+                usage.add(self.file_size(&metadata));
+            }
+        }
+        Ok(usage)
+    }
+
+    #[cfg(unix)]
+    fn file_size(&mut self, metadata: &std::fs::Metadata) -> Usage {
+        use std::os::unix::fs::MetadataExt;
+
+        // 硬链接只计算第一次出现
+        if metadata.nlink() > 1 && !self.seen_inodes.insert((metadata.dev(), metadata.ino())) {
+            return Usage::default();
+        }
+        Usage { apparent: metadata.len(), disk: metadata.blocks() * 512 }
+    }
+
+    #[cfg(not(unix))]
+    fn file_size(&mut self, metadata: &std::fs::Metadata) -> Usage {
+        Usage { apparent: metadata.len(), disk: metadata.len() }
+    }
+}
+
+/// git 列出的以 NUL 分隔的路径
+fn git_paths(root: &Path, args: &[&str]) -> Result<Vec<String>> {
+    Ok(git_ops::run_git_bytes_in(root, args)?
+        .split(|b| *b == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect())
+}
+
+/// 被忽略的文件和目录（目录以 `/` 结尾，整个被忽略的目录只出现一次）
+pub fn ignored_paths(root: &Path) -> Result<Vec<String>> {
+    git_paths(root, &["ls-files", "--others", "--ignored", "--exclude-standard", "--directory", "-z"])
+}
+
+/// 被忽略且匹配 pattern 的条目
+///
+/// pattern 不含 `/` 时匹配路径的最后一段（类似 .gitignore），如 `target` 匹配 `crates/a/target/`；
+/// 否则匹配完整的相对路径，`*` 不跨越 `/`，`**` 可以跨越。
+///
+/// git 会同时列出只含被忽略内容的目录和其中被忽略的子目录（如 `sub/` 和 `sub/logs/`），
+/// 已匹配目录下的条目会随目录一起删除，因此不再单独返回。
+pub fn matching_ignored(root: &Path, pattern: &str) -> Result<Vec<String>> {
+    let pattern = pattern.trim_end_matches('/');
+    let match_name = !pattern.contains('/');
+
+    let mut paths = ignored_paths(root)?;
+    paths.sort();
+
+    let mut matched: Vec<String> = Vec::new();
+    for path in paths {
+        if matched.iter().any(|dir| dir.ends_with('/') && path.starts_with(dir.as_str())) {
+            continue;
+        }
+        let trimmed = path.trim_end_matches('/');
+        let subject = if match_name { trimmed.rsplit('/').next().unwrap_or(trimmed) } else { trimmed };
+        if glob_match(pattern, subject) {
+            matched.push(path);
+        }
+    }
+    Ok(matched)
+}
+
+/// 删除 worktree 中的一个被忽略条目，返回释放的大小
+pub fn remove_ignored(root: &Path, relative: &str) -> Result<Usage> {
+    let empty = HashSet::new();
+    let mut walker = Walker::new(&empty, &empty);
+
+    let path = root.join(relative.trim_end_matches('/'));
+    let metadata = path.symlink_metadata()?;
+    if metadata.is_dir() {
+        let size = walker.tree_size(&path)?;
+        std::fs::remove_dir_all(&path)?;
+        Ok(size)
+    } else {
+        let size = walker.file_size(&metadata);
+        std::fs::remove_file(&path)?;
+        Ok(size)
+    }
+}
+
+/// 简单的通配符匹配：`*` 匹配除 `/` 外的任意字符，`**` 匹配任意字符，`?` 匹配单个非 `/` 字符
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match p.first() {
+            None => t.is_empty(),
+            // `**/` 匹配零个或多个完整的目录
+            Some('*') if p.get(1) == Some(&'*') && p.get(2) == Some(&'/') => (0..=t.len())
+                .filter(|&i| i == 0 || t[i - 1] == '/')
+                .any(|i| matches(&p[3..], &t[i..])),
+            Some('*') if p.get(1) == Some(&'*') => (0..=t.len()).any(|i| matches(&p[2..], &t[i..])),
+            Some('*') => (0..=t.len())
+                .take_while(|&i| i == 0 || t[i - 1] != '/')
+                .any(|i| matches(&p[1..], &t[i..])),
+            Some('?') => t.first().is_some_and(|c| *c != '/') && matches(&p[1..], &t[1..]),
+            Some(c) => t.first() == Some(c) && matches(&p[1..], &t[1..]),
+        }
+    }
+
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    matches(&p, &t)
+}
+
+/// 人类可读的大小（1024 进制）
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("target", "target"));
+        assert!(glob_match("tar*", "target"));
+        assert!(glob_match("*.log", "build.log"));
+        assert!(!glob_match("*.log", "logs/build.log"));
+        assert!(glob_match("**/target", "crates/a/target"));
+        assert!(glob_match("**/target", "target"));
+        assert!(!glob_match("**/target", "xtarget"));
+        assert!(glob_match("crates/**", "crates/a/b"));
+        assert!(glob_match("crates/*/target", "crates/a/target"));
+        assert!(!glob_match("crates/*/target", "crates/a/b/target"));
+        assert!(glob_match("node_module?", "node_modules"));
+        assert!(!glob_match("target", "targets"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_measure_and_clean_ignored() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join("tracked.txt"), "x".repeat(100)).unwrap();
+        std::fs::create_dir_all(root.join("crates/a")).unwrap();
+        std::fs::write(root.join("crates/a/lib.rs"), "").unwrap();
+        git(root, &["add", "."]);
+        std::fs::write(root.join("new.txt"), "y".repeat(10)).unwrap();
+        std::fs::write(root.join("build.log"), "z".repeat(5)).unwrap();
+        std::fs::create_dir_all(root.join("crates/a/target/debug")).unwrap();
+        std::fs::write(root.join("crates/a/target/debug/bin"), vec![0u8; 1000]).unwrap();
+
+        let path = root.to_string_lossy().to_string();
+        let wt = Worktree::new("repo".into(), "main".into(), path, false, false, false, None, None);
+        let usage = measure(&wt, 5).unwrap();
+
+        assert_eq!(usage.tracked.apparent, 100 + "target/\n*.log\n".len() as u64);
+        assert_eq!(usage.untracked.apparent, 10);
+        assert_eq!(usage.ignored.apparent, 1005);
+        assert_eq!(usage.top_ignored.len(), 1);
+        assert_eq!(usage.top_ignored[0].path, "crates/a/target/");
+        assert_eq!(usage.total.apparent, usage.tracked.apparent + 10 + 1005);
+
+        let matched = matching_ignored(root, "target/").unwrap();
+        assert_eq!(matched, vec!["crates/a/target/"]);
+        assert_eq!(remove_ignored(root, &matched[0]).unwrap().apparent, 1000);
+        assert!(!root.join("crates/a/target").exists());
+        assert!(root.join("build.log").exists());
+    }
+
+    #[test]
+    fn test_matching_ignored_skips_entries_inside_matched_dirs() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join(".gitignore"), "logs\n*.log\n").unwrap();
+        git(root, &["add", "."]);
+        std::fs::create_dir_all(root.join("sub/logs")).unwrap();
+        std::fs::write(root.join("sub/logs/b.log"), "b").unwrap();
+
+        // git 同时列出 sub/ 和 sub/logs/
+        assert_eq!(ignored_paths(root).unwrap().len(), 2);
+
+        let matched = matching_ignored(root, "*").unwrap();
+        assert_eq!(matched, vec!["sub/"]);
+        assert_eq!(matching_ignored(root, "logs").unwrap(), vec!["sub/logs/"]);
+        for relative in &matched {
+            remove_ignored(root, relative).unwrap();
+        }
+        assert!(!root.join("sub").exists());
+    }
+}
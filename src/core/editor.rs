@@ -0,0 +1,62 @@
+use crate::core::config;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use std::process::Command;
+
+/// 确定编辑器命令：`--editor` 参数优先，其次是配置项 `work.editor`、$VISUAL、$EDITOR，默认 vi
+pub fn resolve(explicit: Option<&str>) -> String {
+    explicit
+        .map(str::to_string)
+        .or_else(|| config::get("editor"))
+        .or_else(|| non_empty_env("VISUAL"))
+        .or_else(|| non_empty_env("EDITOR"))
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// 在编辑器中打开目录，等待编辑器命令退出
+///
+/// 编辑器命令可以带参数（如 `code -n`），目录作为最后一个参数传入。
+/// 终端编辑器（vi、nvim）会占用当前终端直到退出，GUI 编辑器的启动器通常立即返回。
+pub fn open(editor: &str, path: &str) -> Result<()> {
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| WorktreeError::EditorFailed(t("error.detail.empty_editor")))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .current_dir(path)
+        .status()
+        .map_err(|e| WorktreeError::EditorFailed(format!("{}: {}", program, e)))?;
+
+    if !status.success() {
+        return Err(WorktreeError::EditorFailed(tf(
+            "error.detail.exited_with",
+            &[("program", program), ("status", &status.to_string())],
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_editor_wins() {
+        assert_eq!(resolve(Some("nvim")), "nvim");
+    }
+
+    #[test]
+    fn test_open_reports_missing_editor() {
+        let dir = std::env::temp_dir();
+        let result = open("work-no-such-editor", dir.to_str().unwrap());
+        assert!(matches!(result, Err(WorktreeError::EditorFailed(_))));
+        assert!(matches!(open("  ", "."), Err(WorktreeError::EditorFailed(_))));
+    }
+}
@@ -0,0 +1,515 @@
+// 每个 worktree 的环境变量：从配置的端口范围中为 worktree 分配一个不冲突的端口块，
+// 并按模板生成 `.env.work`，多个 worktree 的开发服务器可以同时运行。
+//
+// - env.ports       端口范围，如 `3000-3999`，设置后 `work create` 才会分配端口
+// - env.port-block  每个 worktree 占用的端口数（默认 10）
+// - env.template    模板文件路径（相对新 worktree），默认为 `.env.work.template`，
+//                   不存在时使用 `PORT={{port}}`
+//
+// 模板占位符：`{{port}}`、`{{port+N}}`（块内第 N 个端口，N 须小于块大小）、`{{slug}}`（只含小写字母、数字和 `_`
+// 的目录名，适合作为数据库名）、`{{dirname}}`、`{{branch}}`、`{{path}}`。
+// 分配结果保存在 metadata.json 中，删除 worktree 时随元数据一起释放。
+//
+// - env.loader      为新 worktree 写入 direnv 的 `.envrc` 或 mise 的 `mise.local.toml`，
+//                   导出 WORK_DIRNAME、WORK_BRANCH、WORK_MAIN_PATH 并加载 `.env.work`
+// - env.allow       为真时写入后自动执行 `direnv allow` / `mise trust`
+
+use crate::core::cache;
+use crate::core::config;
+use crate::core::git_ops;
+use crate::core::metadata::{self, Metadata};
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use std::path::Path;
+use std::process::Command;
+
+/// 生成的环境文件
+pub const ENV_FILE: &str = ".env.work";
+/// 默认模板文件
+pub const DEFAULT_TEMPLATE_FILE: &str = ".env.work.template";
+/// 没有模板文件时使用的模板
+pub const DEFAULT_TEMPLATE: &str = "PORT={{port}}\n";
+/// 每个 worktree 默认占用的端口数
+pub const DEFAULT_BLOCK_SIZE: u16 = 10;
+
+/// 端口范围（包含两端）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// 解析 `3000-3999`
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || WorktreeError::InvalidArgument(tf("error.detail.invalid_port_range", &[("value", value)]));
+        let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+        let start: u16 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u16 = end.trim().parse().map_err(|_| invalid())?;
+        if start == 0 || start > end {
+            return Err(invalid());
+        }
+        Ok(PortRange { start, end })
+    }
+}
+
+/// 模板变量
+#[derive(Debug, Clone, Default)]
+pub struct EnvContext {
+    pub port: Option<u16>,
+    /// 端口块大小，`{{port+N}}` 中的 N 必须小于它
+    pub block_size: u16,
+    pub dirname: String,
+    pub branch: String,
+    pub path: String,
+}
+
+/// 为新 worktree 设置环境的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvSetup {
+    pub port: Option<u16>,
+    pub block_size: u16,
+    /// 是否写入了 `.env.work`
+    pub written: bool,
+}
+
+impl EnvSetup {
+    /// 端口块中的最后一个端口
+    pub fn last_port(&self) -> Option<u16> {
+        self.port.and_then(|port| last_port(port, self.block_size))
+    }
+}
+
+/// 进入 worktree 时自动加载环境的工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loader {
+    Direnv,
+    Mise,
+}
+
+impl Loader {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "direnv" | "envrc" => Some(Loader::Direnv),
+            "mise" => Some(Loader::Mise),
+            _ => None,
+        }
+    }
+
+    /// 读取配置项 env.loader，未设置或为 off 时返回 None
+    pub fn from_config() -> Result<Option<Self>> {
+        match config::get("env.loader") {
+            None => Ok(None),
+            Some(value) if matches!(value.trim(), "" | "off" | "none") => Ok(None),
+            Some(value) => Loader::parse(&value).map(Some).ok_or_else(|| {
+                WorktreeError::InvalidArgument(tf("error.detail.unknown_loader", &[("value", &value)]))
+            }),
+        }
+    }
+
+    /// 写入的文件（相对 worktree）
+    pub fn file(&self) -> &'static str {
+        match self {
+            Loader::Direnv => ".envrc",
+            Loader::Mise => "mise.local.toml",
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            Loader::Direnv => "direnv",
+            Loader::Mise => "mise",
+        }
+    }
+}
+
+/// 写入加载文件的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoaderSetup {
+    pub loader: Loader,
+    /// 是否写入了文件（已存在时不覆盖）
+    pub written: bool,
+    /// 是否执行了 `direnv allow` / `mise trust`
+    pub allowed: bool,
+}
+
+/// 在 range 中找到第一个空闲的端口块：不与 taken 中的块重叠，且 is_free 对块内每个端口都成立
+pub fn allocate(range: PortRange, block: u16, taken: &[u16], is_free: impl Fn(u16) -> bool) -> Option<u16> {
+    let block = block.max(1);
+    let overlaps = |base: u16| taken.iter().any(|&other| base < other.saturating_add(block) && other < base.saturating_add(block));
+
+    (range.start..=range.end)
+        .step_by(block as usize)
+        .filter_map(|base| last_port(base, block).filter(|&last| last <= range.end).map(|last| (base, last)))
+        .find(|&(base, last)| !overlaps(base) && (base..=last).all(&is_free))
+        .map(|(base, _)| base)
+}
+
+/// 从 base 开始、大小为 block 的端口块中的最后一个端口，超出 65535 时为 None
+fn last_port(base: u16, block: u16) -> Option<u16> {
+    base.checked_add(block.max(1) - 1)
+}
+
+/// 端口当前是否可以绑定（没有其他进程在监听）
+pub fn port_available(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// 只含小写字母、数字和 `_` 的名称
+pub fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// 渲染模板，未知的占位符或没有分配端口时引用端口会报错
+pub fn render(template: &str, ctx: &EnvContext) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| WorktreeError::InvalidArgument(t("error.detail.unclosed_placeholder")))?;
+        let name = rest[start + 2..start + end].trim();
+        output.push_str(&resolve(name, ctx)?);
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn resolve(name: &str, ctx: &EnvContext) -> Result<String> {
+    let port = |offset: u16| {
+        let port = ctx.port.ok_or_else(|| WorktreeError::InvalidArgument(t("error.detail.port_not_configured")))?;
+        // 块外的端口可能分配给了其他 worktree
+        if offset >= ctx.block_size.max(1) {
+            return Err(WorktreeError::InvalidArgument(tf(
+                "error.detail.port_outside_block",
+                &[("offset", &offset.to_string()), ("size", &ctx.block_size.to_string())],
+            )));
+        }
+        port
+            .checked_add(offset)
+            .map(|port| port.to_string())
+            .ok_or_else(|| WorktreeError::InvalidArgument(tf("error.detail.port_above_max", &[("offset", &offset.to_string())])))
+    };
+
+    match name {
+        "port" => port(0),
+        "slug" => Ok(slug(&ctx.dirname)),
+        "dirname" => Ok(ctx.dirname.clone()),
+        "branch" => Ok(ctx.branch.clone()),
+        "path" => Ok(ctx.path.clone()),
+        _ => match name.strip_prefix("port+").and_then(|n| n.trim().parse::<u16>().ok()) {
+            Some(offset) => port(offset),
+            None => Err(WorktreeError::InvalidArgument(tf("error.detail.unknown_placeholder", &[("name", name)]))),
+        },
+    }
+}
+
+/// 解析 dotenv 格式：`KEY=VALUE`，忽略空行、注释和 `export ` 前缀，去掉值两侧的引号
+pub fn parse_dotenv(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// 读取 worktree 的 `.env.work`，不存在时返回空
+pub fn load(worktree_path: &Path) -> Result<Vec<(String, String)>> {
+    match std::fs::read_to_string(worktree_path.join(ENV_FILE)) {
+        Ok(content) => Ok(parse_dotenv(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// worktree 的基本环境变量：WORK_DIRNAME、WORK_BRANCH（分离 HEAD 时为空）和 WORK_MAIN_PATH
+pub fn base_vars(dirname: &str, branch: &str, main_path: &Path) -> Vec<(String, String)> {
+    vec![
+        ("WORK_DIRNAME".to_string(), dirname.to_string()),
+        ("WORK_BRANCH".to_string(), branch.to_string()),
+        ("WORK_MAIN_PATH".to_string(), main_path.to_string_lossy().to_string()),
+    ]
+}
+
+/// 完整的 worktree 环境：基本变量加上 `.env.work` 中的变量
+pub fn worktree_vars(worktree_path: &Path, dirname: &str, branch: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = base_vars(dirname, branch, &git_ops::get_main_repository_root()?);
+    vars.extend(load(worktree_path)?);
+    Ok(vars)
+}
+
+/// `.envrc` 的内容；with_env_file 为真时用 direnv 的 dotenv_if_exists 加载 `.env.work`
+pub fn envrc(vars: &[(String, String)], with_env_file: bool) -> String {
+    let mut content = String::from("# Generated by work (work.env.loader)\n");
+    for (key, value) in vars {
+        content.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+    }
+    if with_env_file {
+        content.push_str(&format!("dotenv_if_exists {}\n", ENV_FILE));
+    }
+    content
+}
+
+/// `mise.local.toml` 的内容；with_env_file 为真时用 `_.file` 加载 `.env.work`
+pub fn mise_toml(vars: &[(String, String)], with_env_file: bool) -> String {
+    let mut content = String::from("# Generated by work (work.env.loader)\n[env]\n");
+    if with_env_file {
+        content.push_str(&format!("_.file = {}\n", cache::toml_string(ENV_FILE)));
+    }
+    for (key, value) in vars {
+        content.push_str(&format!("{} = {}\n", key, cache::toml_string(value)));
+    }
+    content
+}
+
+/// 按 env.loader 为新 worktree 写入 `.envrc` 或 `mise.local.toml`，未配置时不做任何事
+///
+/// 只写入基本变量，`.env.work` 在进入目录时由 direnv / mise 加载，修改后无需重新生成。
+pub fn setup_loader(worktree_path: &Path, dirname: &str, branch: &str) -> Result<Option<LoaderSetup>> {
+    let Some(loader) = Loader::from_config()? else {
+        return Ok(None);
+    };
+
+    let file = worktree_path.join(loader.file());
+    if file.exists() {
+        return Ok(Some(LoaderSetup { loader, written: false, allowed: false }));
+    }
+
+    let vars = base_vars(dirname, branch, &git_ops::get_main_repository_root()?);
+    let with_env_file = worktree_path.join(ENV_FILE).exists();
+    let content = match loader {
+        Loader::Direnv => envrc(&vars, with_env_file),
+        Loader::Mise => mise_toml(&vars, with_env_file),
+    };
+    std::fs::write(&file, content)?;
+    git_ops::add_info_exclude(&[loader.file().to_string()])?;
+
+    let allowed = config::get_bool("env.allow") && allow(loader, worktree_path)?;
+    Ok(Some(LoaderSetup { loader, written: true, allowed }))
+}
+
+/// 执行 `direnv allow` / `mise trust`，程序未安装时返回 false
+fn allow(loader: Loader, worktree_path: &Path) -> Result<bool> {
+    let args: &[&str] = match loader {
+        Loader::Direnv => &["allow"],
+        Loader::Mise => &["trust"],
+    };
+    let output = match Command::new(loader.program()).args(args).current_dir(worktree_path).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    if !output.status.success() {
+        return Err(WorktreeError::InvalidArgument(tf(
+            "error.detail.loader_failed",
+            &[
+                ("command", &format!("{} {}", loader.program(), args[0])),
+                ("error", String::from_utf8_lossy(&output.stderr).trim()),
+            ],
+        )));
+    }
+    Ok(true)
+}
+
+/// POSIX shell 单引号转义
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 为新 worktree 分配端口并生成 `.env.work`；既没有配置端口范围也没有模板文件时不做任何事
+pub fn setup(worktree_path: &Path, dirname: &str, branch: &str) -> Result<Option<EnvSetup>> {
+    let range = config::get("env.ports").map(|r| PortRange::parse(&r)).transpose()?;
+    let block_size = match config::get("env.port-block") {
+        Some(value) => value
+            .trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| WorktreeError::InvalidArgument(tf("error.detail.invalid_port_block", &[("value", &value)])))?,
+        None => DEFAULT_BLOCK_SIZE,
+    };
+    let template_file = worktree_path.join(config::get("env.template").unwrap_or_else(|| DEFAULT_TEMPLATE_FILE.to_string()));
+    let template = match std::fs::read_to_string(&template_file) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    if range.is_none() && template.is_none() {
+        return Ok(None);
+    }
+
+    let port = match range {
+        Some(range) => Some(allocate_for(dirname, worktree_path, range, block_size)?),
+        None => None,
+    };
+
+    let ctx = EnvContext {
+        port,
+        block_size,
+        dirname: dirname.to_string(),
+        branch: branch.to_string(),
+        path: worktree_path.to_string_lossy().to_string(),
+    };
+    let content = render(template.as_deref().unwrap_or(DEFAULT_TEMPLATE), &ctx)?;
+
+    let env_file = worktree_path.join(ENV_FILE);
+    let written = !env_file.exists();
+    if written {
+        std::fs::write(&env_file, content)?;
+        git_ops::add_info_exclude(&[ENV_FILE.to_string()])?;
+    }
+
+    Ok(Some(EnvSetup { port, block_size, written }))
+}
+
+/// 分配端口块并写入元数据；只考虑仍然存在的 worktree 占用的端口
+fn allocate_for(dirname: &str, worktree_path: &Path, range: PortRange, block_size: u16) -> Result<u16> {
+    let worktrees = git_ops::list_worktrees()?;
+    // 以 git 记录的路径为键，与 list_worktrees 的结果一致
+    let key = worktrees
+        .iter()
+        .find(|wt| wt.dirname == dirname)
+        .map(|wt| wt.path.clone())
+        .unwrap_or_else(|| worktree_path.to_string_lossy().to_string());
+    let live: Vec<String> = worktrees.into_iter().map(|wt| wt.path).collect();
+    let metadata = Metadata::load()?;
+    if let Some(port) = metadata.worktrees.get(&key).and_then(|meta| meta.port) {
+        // 之前分配的端口在调大 work.env.port-block 后可能超出 65535
+        if last_port(port, block_size).is_none() {
+            return Err(WorktreeError::InvalidArgument(tf(
+                "error.detail.port_block_above_max",
+                &[("size", &block_size.to_string()), ("port", &port.to_string())],
+            )));
+        }
+        return Ok(port);
+    }
+
+    let taken: Vec<u16> = metadata
+        .worktrees
+        .iter()
+        .filter(|(path, _)| live.contains(path))
+        .filter_map(|(_, meta)| meta.port)
+        .collect();
+    let port = allocate(range, block_size, &taken, port_available).ok_or_else(|| {
+        WorktreeError::InvalidArgument(tf(
+            "error.detail.no_free_ports",
+            &[("size", &block_size.to_string()), ("start", &range.start.to_string()), ("end", &range.end.to_string())],
+        ))
+    })?;
+
+    metadata::update(|metadata| {
+        metadata.worktrees.entry(key).or_default().port = Some(port);
+    })?;
+    Ok(port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_range() {
+        assert_eq!(PortRange::parse("3000-3999").unwrap(), PortRange { start: 3000, end: 3999 });
+        for invalid in ["3000", "0-10", "4000-3000", "a-b", "3000-70000"] {
+            assert!(PortRange::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_allocate() {
+        let range = PortRange { start: 3000, end: 3039 };
+        assert_eq!(allocate(range, 10, &[], |_| true), Some(3000));
+        assert_eq!(allocate(range, 10, &[3000, 3010], |_| true), Some(3020));
+        // 已占用的块大小不同时也不能重叠
+        assert_eq!(allocate(range, 10, &[3005], |_| true), Some(3020));
+        // 块内有端口被其他进程占用时跳过整个块
+        assert_eq!(allocate(range, 10, &[], |port| port != 3003), Some(3010));
+        // 最后一块超出范围时不分配
+        assert_eq!(allocate(PortRange { start: 3000, end: 3015 }, 10, &[3000], |_| true), None);
+        // 范围到 65535 时不溢出
+        let top = PortRange { start: 65526, end: 65535 };
+        assert_eq!(allocate(top, 10, &[], |_| true), Some(65526));
+        assert_eq!(allocate(top, 10, &[65526], |_| true), None);
+        assert_eq!(allocate(PortRange { start: 65535, end: 65535 }, 1, &[], |_| true), Some(65535));
+    }
+
+    #[test]
+    fn test_render() {
+        let ctx = EnvContext {
+            port: Some(3010),
+            block_size: 10,
+            dirname: "feat-Login".to_string(),
+            branch: "feat/Login".to_string(),
+            path: "/w/feat-Login".to_string(),
+        };
+        assert_eq!(
+            render("PORT={{port}}\nAPI_PORT={{ port+1 }}\nDB_NAME=app_{{slug}}\n", &ctx).unwrap(),
+            "PORT=3010\nAPI_PORT=3011\nDB_NAME=app_feat_login\n"
+        );
+        assert!(render("{{unknown}}", &ctx).is_err());
+        assert!(render("{{port", &ctx).is_err());
+        assert!(render("PORT={{port}}", &EnvContext::default()).is_err());
+        // 块外的端口和超出 65535 的端口
+        assert!(render("{{port+10}}", &ctx).is_err());
+        let top = EnvContext { port: Some(65530), block_size: 10, ..ctx };
+        assert_eq!(render("{{port+5}}", &top).unwrap(), "65535");
+        assert!(render("{{port+6}}", &top).is_err());
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let vars = parse_dotenv("# comment\nPORT=3010\nexport NAME=\"a b\"\nQUOTED='x'\n\ninvalid\n");
+        assert_eq!(
+            vars,
+            vec![
+                ("PORT".to_string(), "3010".to_string()),
+                ("NAME".to_string(), "a b".to_string()),
+                ("QUOTED".to_string(), "x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loader_files() {
+        let vars = base_vars("feat-a", "feat/a", Path::new("/w/app"));
+        assert_eq!(
+            envrc(&vars, true),
+            "# Generated by work (work.env.loader)\n\
+             export WORK_DIRNAME='feat-a'\n\
+             export WORK_BRANCH='feat/a'\n\
+             export WORK_MAIN_PATH='/w/app'\n\
+             dotenv_if_exists .env.work\n"
+        );
+        let toml = mise_toml(&vars, false);
+        assert!(toml.contains("[env]\nWORK_DIRNAME = \"feat-a\"\n"));
+        assert!(!toml.contains("_.file"));
+        assert!(mise_toml(&vars, true).contains("_.file = \".env.work\"\n"));
+        assert_eq!(Loader::parse("envrc"), Some(Loader::Direnv));
+        assert_eq!(Loader::parse("asdf"), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
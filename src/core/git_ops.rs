@@ -1,8 +1,48 @@
 use crate::utils::errors::{Result, WorktreeError};
-use crate::core::worktree::Worktree;
+use crate::core::worktree::{GitOperation, OperationKind, Worktree};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+thread_local! {
+    /// [`in_repo`] 指定的仓库目录
+    static REPO_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// 新建 git 命令：在 [`in_repo`] 指定的仓库目录中执行，未指定时使用当前目录
+pub fn git() -> Command {
+    let mut command = Command::new("git");
+    if let Some(dir) = REPO_DIR.with(|dir| dir.borrow().clone()) {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// git 命令执行时所在的目录
+fn repo_dir() -> Result<PathBuf> {
+    match REPO_DIR.with(|dir| dir.borrow().clone()) {
+        Some(dir) => Ok(dir),
+        None => Ok(std::env::current_dir()?),
+    }
+}
+
+/// 在仓库目录 root 中执行 f：其间 git_ops、配置、元数据等都以 root 所在的仓库为准
+///
+/// 不改变进程的当前目录，worktree 的 is_current 仍以当前目录为准；f 返回或 panic 后恢复之前的仓库目录。
+pub fn in_repo<T>(root: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    struct Restore(Option<PathBuf>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            REPO_DIR.with(|dir| *dir.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(REPO_DIR.with(|dir| dir.replace(Some(root.to_path_buf()))));
+    f()
+}
+
 /// 将分支名转换为目录名（将所有 / 替换为 -）
 pub fn branch_to_dirname(branch_name: &str) -> String {
     branch_name.replace('/', "-")
@@ -50,13 +90,16 @@ pub fn check_dirname_conflict(
 
 /// 运行 git 命令并返回输出
 fn run_git(args: &[&str]) -> Result<String> {
-    let output = Command::new("git")
+    let output = git()
         .args(args)
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not a git repository") {
+            return Err(WorktreeError::NotGitRepository(repo_dir()?));
+        }
         return Err(WorktreeError::GitError(stderr.to_string()));
     }
 
@@ -64,101 +107,204 @@ fn run_git(args: &[&str]) -> Result<String> {
         .map_err(|e| WorktreeError::GitError(format!("Invalid UTF-8 output: {}", e)))
 }
 
-/// 获取仓库根目录
-pub fn get_repository_root() -> Result<PathBuf> {
-    let output = run_git(&["rev-parse", "--show-toplevel"])?;
-    let path = output.trim();
-    Ok(PathBuf::from(path))
+/// 在指定目录中执行 git 命令，返回原始字节输出（用于补丁等二进制内容）
+pub fn run_git_bytes_in(path: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = git()
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .map_err(WorktreeError::git_spawn)?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// 在指定目录中执行 git 命令
+fn run_git_in(path: &Path, args: &[&str]) -> Result<String> {
+    let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+    let mut full_args = vec!["-C", path_str];
+    full_args.extend_from_slice(args);
+    run_git(&full_args)
+}
+
+/// 获取主仓库的 .git 目录（所有 worktree 共享的 git common dir）的绝对路径
+pub fn get_git_common_dir() -> Result<PathBuf> {
+    let output = git()
+        .args(["rev-parse", "--git-common-dir"])
+        .output()
+        .map_err(WorktreeError::git_spawn)?;
+
+    let current_dir = repo_dir()?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::NotGitRepository(current_dir));
+    }
+
+    let git_common_dir_cow = String::from_utf8_lossy(&output.stdout);
+    let git_common_dir_relative = git_common_dir_cow.trim();
+
+    // 如果是相对路径，需要转换为绝对路径
+    let git_dir = if git_common_dir_relative.starts_with('/') || git_common_dir_relative.starts_with('.') {
+        // 绝对路径或相对路径，需要规范化
+        current_dir.join(git_common_dir_relative).canonicalize()
+            .unwrap_or_else(|_| current_dir.join(git_common_dir_relative))
+    } else {
+        // 可能是简单的 ".git"，需要基于当前目录
+        current_dir.join(git_common_dir_relative)
+    };
+
+    Ok(git_dir)
+}
+
+/// 获取主仓库目录（git common dir 的父目录）
+pub fn get_main_repository_root() -> Result<PathBuf> {
+    // 获取主仓库的 .git 目录（不是当前 worktree 的 .git 文件）
+    // 它的父目录就是主仓库所在目录
+    let git_dir = get_git_common_dir()?;
+    git_dir
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| WorktreeError::InvalidPath("Cannot determine repository root".to_string()))
+}
+
+/// 仓库目录中的 `.git` 是否为指向同目录下裸仓库的文件（`work clone` 创建的 `<仓库>/.bare` 布局）
+pub fn is_bare_layout(root: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(root.join(".git")) else {
+        return false;
+    };
+    let Some(git_dir) = content.trim().strip_prefix("gitdir:") else {
+        return false;
+    };
+    let git_dir = root.join(git_dir.trim());
+    git_dir.parent().and_then(|p| p.canonicalize().ok()) == root.canonicalize().ok() && git_dir.is_dir()
+}
+
+/// 计算新 worktree 的默认路径：`<主仓库同级>/<仓库名>.worktrees/<dirname>`，
+/// `<仓库>/.bare` 布局中为 `<仓库>/<dirname>`
+pub fn default_worktree_path(dirname: &str) -> Result<String> {
+    let repo_root = get_main_repository_root()?;
+
+    if is_bare_layout(&repo_root) {
+        return Ok(crate::utils::path::normalize_path_for_git(&repo_root.join(dirname))
+            .to_string_lossy()
+            .to_string());
+    }
+
+    // 获取主仓库目录的名称
+    let dir_name = repo_root
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("repo");
+
+    // 在主仓库目录的同一级创建 <dirname>.worktrees 目录
+    let worktrees_parent = repo_root
+        .parent()
+        .ok_or_else(|| WorktreeError::InvalidPath("Cannot determine parent directory".to_string()))?;
+
+    let worktrees_dir_name = format!("{}.worktrees", dir_name);
+    let worktree_path_buf = worktrees_parent.join(worktrees_dir_name).join(dirname);
+
+    // 规范化路径以用于 Git 命令
+    // 这会处理 Windows 的扩展路径语法 (//?/C:/)
+    Ok(crate::utils::path::normalize_path_for_git(&worktree_path_buf)
+        .to_string_lossy()
+        .to_string())
 }
 
 /// 列出所有 worktree
 pub fn list_worktrees() -> Result<Vec<Worktree>> {
     let output = run_git(&["worktree", "list", "--porcelain"])?;
 
+    let mut result: Vec<Worktree> = parse_worktree_list(&output)
+        .into_iter()
+        .filter_map(|wt_data| wt_data.into_worktree().ok())
+        .collect();
+
+    // git 总是先列出主 worktree（裸仓库时为仓库本身）
+    if let Some(main) = result.first_mut() {
+        main.is_main = true;
+    }
+
+    // 附加 work 自己记录的属性（元数据损坏时忽略，不影响列表）
+    if let Ok(metadata) = crate::core::metadata::Metadata::load() {
+        metadata.apply(&mut result);
+    }
+
+    Ok(result)
+}
+
+/// 解析 git worktree list --porcelain 输出
+///
+/// 格式示例（每个 worktree 一组，以空行分隔）:
+/// ```text
+/// worktree /path/to/repo/.bare
+/// bare
+///
+/// worktree /path/to/worktree
+/// HEAD abc123def456
+/// branch refs/heads/main
+/// locked [reason]
+/// prunable [reason]
+/// ```
+fn parse_worktree_list(output: &str) -> Vec<WorktreeData> {
     let mut result = Vec::new();
     let mut current_worktree: Option<WorktreeData> = None;
 
-    // 解析 git worktree list --porcelain 输出
-    // 格式示例:
-    // worktree /path/to/worktree
-    // HEAD abc123def456
-    // branch refs/heads/main
-    // detached
     for line in output.lines() {
-        if line.is_empty() {
-            // 空行表示新 worktree 开始
-            if let Some(wt_data) = current_worktree.take() {
-                if let Ok(worktree) = wt_data.to_worktree() {
-                    result.push(worktree);
-                }
+        // 部分行只有键没有值（如 bare、detached、locked）
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let reason = (!value.is_empty()).then(|| value.to_string());
+
+        if key == "worktree" {
+            result.extend(current_worktree.replace(WorktreeData::new(value.to_string())));
+            continue;
+        }
+        // 空行表示当前 worktree 结束
+        let Some(wt) = current_worktree.as_mut() else {
+            continue;
+        };
+        match key {
+            "" => result.extend(current_worktree.take()),
+            "HEAD" => wt.head_commit = Some(value.to_string()),
+            "branch" => {
+                // 分支格式: refs/heads/main or refs/remotes/origin/main
+                wt.branch = value
+                    .strip_prefix("refs/heads/")
+                    .or_else(|| value.strip_prefix("refs/remotes/"))
+                    .unwrap_or(value)
+                    .to_string();
+                wt.is_detached = false;
             }
-        } else if let Some((key, value)) = line.split_once(' ') {
-            match key {
-                "worktree" => {
-                    current_worktree = Some(WorktreeData::new(value.to_string()));
-                }
-                "HEAD" => {
-                    if let Some(ref mut wt) = current_worktree {
-                        wt.head_commit = Some(value.to_string());
-                    }
-                }
-                "branch" => {
-                    if let Some(ref mut wt) = current_worktree {
-                        // 分支格式: refs/heads/main or refs/remotes/origin/main
-                        wt.branch = value
-                            .strip_prefix("refs/heads/")
-                            .or_else(|| value.strip_prefix("refs/remotes/"))
-                            .unwrap_or(value)
-                            .to_string();
-                        wt.is_detached = false;
-                    }
-                }
-                "detached" => {
-                    if let Some(ref mut wt) = current_worktree {
-                        wt.is_detached = true;
-                    }
-                }
-                _ => {}
+            "detached" => wt.is_detached = true,
+            "bare" => wt.is_bare = true,
+            "locked" => {
+                wt.is_locked = true;
+                wt.lock_reason = reason;
             }
+            "prunable" => {
+                wt.is_prunable = true;
+                wt.prune_reason = reason;
+            }
+            _ => {}
         }
     }
 
-    // 添加最后一个 worktree
-    if let Some(wt_data) = current_worktree {
-        if let Ok(worktree) = wt_data.to_worktree() {
-            result.push(worktree);
-        }
-    }
-
-    Ok(result)
+    result.extend(current_worktree);
+    result
 }
 
 /// 检查路径是否在 Git 仓库中
 pub fn is_inside_repository<P: AsRef<Path>>(path: P) -> bool {
-    Command::new("git")
+    git()
         .args(["-C", path.as_ref().to_str().unwrap_or("."), "rev-parse", "--is-inside-work-tree"])
         .output()
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
-/// 检查是否为裸仓库
-pub fn is_bare_repository() -> Result<bool> {
-    let output = run_git(&["rev-parse", "--is-bare-repository"])?;
-    Ok(output.trim() == "true")
-}
-
-/// 获取当前分支名
-pub fn get_current_branch() -> Result<String> {
-    let output = run_git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
-    let branch = output.trim();
-    if branch == "HEAD" {
-        Ok("HEAD".to_string()) // detached HEAD
-    } else {
-        Ok(branch.to_string())
-    }
-}
-
 /// 获取 upstream 分支
 pub fn get_upstream_branch() -> Result<Option<String>> {
     let output = run_git(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"]);
@@ -196,10 +342,10 @@ fn create_worktree_internal(
     }
 
     // T016: 使用原始分支名和提供的路径创建 worktree
-    let output = Command::new("git")
+    let output = git()
         .args(["worktree", "add", path, branch_name])
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -225,10 +371,10 @@ pub fn create_worktree_with_new_branch(branch_name: &str, path: &str, upstream:
         args.push("HEAD");
     }
 
-    let output = Command::new("git")
+    let output = git()
         .args(&args)
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -249,20 +395,63 @@ pub fn delete_worktree(path: &str, force: bool) -> Result<String> {
 
     args.push(path);
 
-    let output = Command::new("git")
+    let output = git()
         .args(&args)
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(WorktreeError::GitError(stderr.to_string()));
     }
 
+    // 删除 work 为它记录的属性和不再需要的排除条目（失败不影响删除结果）
+    let _ = crate::core::metadata::update(|metadata| {
+        metadata.worktrees.remove(path);
+    });
+    let _ = prune_info_exclude();
+
     String::from_utf8(output.stdout)
         .map_err(|e| WorktreeError::GitError(format!("Invalid UTF-8 output: {}", e)))
 }
 
+/// 解析修订（分支、标签、SHA 等）为完整的提交 SHA
+pub fn resolve_revision(rev: &str) -> Result<String> {
+    run_git(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+        .map(|sha| sha.trim().to_string())
+        .map_err(|_| WorktreeError::RevisionNotFound(rev.to_string()))
+}
+
+/// 在指定提交上创建分离 HEAD 的 worktree
+pub fn create_detached_worktree(rev: &str, path: &str) -> Result<String> {
+    let sha = resolve_revision(rev)?;
+    run_git(&["worktree", "add", "--detach", path, &sha])
+}
+
+/// 锁定 worktree（防止被 prune 或误删）
+pub fn lock_worktree(path: &str, reason: Option<&str>) -> Result<String> {
+    let mut args = vec!["worktree", "lock"];
+
+    if let Some(reason) = reason {
+        args.push("--reason");
+        args.push(reason);
+    }
+
+    args.push(path);
+    run_git(&args)
+}
+
+/// 解锁 worktree
+pub fn unlock_worktree(path: &str) -> Result<String> {
+    run_git(&["worktree", "unlock", path])
+}
+
+/// 同步 worktree：从上游拉取并快进当前分支
+pub fn sync_worktree(path: &Path) -> Result<String> {
+    let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+    run_git(&["-C", path_str, "pull", "--ff-only"])
+}
+
 /// 获取所有本地分支列表
 pub fn list_local_branches() -> Result<Vec<String>> {
     let output = run_git(&["branch", "--format=%(refname:short)"])?;
@@ -276,13 +465,26 @@ pub fn list_local_branches() -> Result<Vec<String>> {
     Ok(branches)
 }
 
+/// 获取所有远程跟踪分支列表（如 `origin/main`），不包含 `origin/HEAD`
+pub fn list_remote_branches() -> Result<Vec<String>> {
+    let output = run_git(&["branch", "-r", "--format=%(refname:short)"])?;
+
+    let branches: Vec<String> = output
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| line.contains('/') && !line.ends_with("/HEAD"))
+        .collect();
+
+    Ok(branches)
+}
+
 /// 检查 worktree 路径是否有未提交的更改
 pub fn has_uncommitted_changes(path: &Path) -> Result<bool> {
-    let output = Command::new("git")
+    let output = git()
         .args(["-C", path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?,
                "status", "--porcelain"])
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
     if !output.status.success() {
         // 如果 git status 失败，可能不是一个有效的 worktree
@@ -293,9 +495,125 @@ pub fn has_uncommitted_changes(path: &Path) -> Result<bool> {
     Ok(!stdout.trim().is_empty())
 }
 
+/// worktree 中 HEAD 提交的提交时间，没有提交时为 None
+pub fn head_commit_time(path: &Path) -> Option<DateTime<Utc>> {
+    let output = run_git_in(path, &["log", "-1", "--format=%ct", "HEAD"]).ok()?;
+    DateTime::from_timestamp(output.trim().parse().ok()?, 0)
+}
+
+/// info/exclude 中 work 写入的条目所在区块的首尾标记
+const EXCLUDE_BEGIN: &str = "# work: begin (generated files, removed when no worktree has them)";
+const EXCLUDE_END: &str = "# work: end";
+
+/// 把 work 生成的文件（相对 worktree 根目录）加入仓库共享的 info/exclude，只影响未跟踪的文件
+///
+/// info/exclude 对所有 worktree 生效（包括主 worktree），条目写在单独的区块中，
+/// 删除 worktree 后由 [`prune_info_exclude`] 移除不再有 worktree 使用的条目。
+pub fn add_info_exclude(paths: &[String]) -> Result<()> {
+    let file = get_git_common_dir()?.join("info").join("exclude");
+    let existing = std::fs::read_to_string(&file).unwrap_or_default();
+
+    let mut patterns = work_excludes(&existing);
+    let before = patterns.len();
+    for pattern in paths.iter().map(|path| format!("/{}", path)) {
+        if !existing.lines().any(|line| line.trim() == pattern) {
+            patterns.push(pattern);
+        }
+    }
+    if patterns.len() == before {
+        return Ok(());
+    }
+
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&file, with_work_excludes(&existing, &patterns))?;
+    Ok(())
+}
+
+/// 从 info/exclude 中移除 work 写入、但已没有任何 worktree 存在对应文件的条目
+pub fn prune_info_exclude() -> Result<()> {
+    let file = get_git_common_dir()?.join("info").join("exclude");
+    let Ok(existing) = std::fs::read_to_string(&file) else {
+        return Ok(());
+    };
+
+    let patterns = work_excludes(&existing);
+    if patterns.is_empty() {
+        return Ok(());
+    }
+    let roots: Vec<PathBuf> = list_worktrees()?
+        .into_iter()
+        .filter(|wt| !wt.is_bare)
+        .map(|wt| PathBuf::from(wt.path))
+        .collect();
+    let used: Vec<String> = patterns
+        .iter()
+        .filter(|pattern| {
+            let relative = pattern.trim_start_matches('/');
+            roots.iter().any(|root| root.join(relative).exists())
+        })
+        .cloned()
+        .collect();
+
+    if used.len() != patterns.len() {
+        std::fs::write(&file, with_work_excludes(&existing, &used))?;
+    }
+    Ok(())
+}
+
+/// work 区块中的条目
+fn work_excludes(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .skip_while(|line| line.trim() != EXCLUDE_BEGIN)
+        .skip(1)
+        .take_while(|line| line.trim() != EXCLUDE_END)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// 把 work 区块替换为 patterns（为空时去掉区块），区块外的内容保持不变
+fn with_work_excludes(content: &str, patterns: &[String]) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        match line.trim() {
+            EXCLUDE_BEGIN => in_block = true,
+            EXCLUDE_END if in_block => in_block = false,
+            _ if !in_block => lines.push(line),
+            _ => {}
+        }
+    }
+
+    let mut result: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    if !patterns.is_empty() {
+        result.push_str(EXCLUDE_BEGIN);
+        result.push('\n');
+        for pattern in patterns {
+            result.push_str(pattern);
+            result.push('\n');
+        }
+        result.push_str(EXCLUDE_END);
+        result.push('\n');
+    }
+    result
+}
+
+/// 检查 repo_path 所属仓库中分支是否存在
+pub fn branch_exists_in(repo_path: &Path, branch_name: &str) -> bool {
+    run_git_bytes_in(repo_path, &["show-ref", "--verify", "--quiet", &format!("refs/heads/{}", branch_name)]).is_ok()
+}
+
+/// 删除已合并的分支（`git branch -d`），分支上有未合并的提交时失败
+pub fn delete_merged_branch(branch_name: &str) -> Result<String> {
+    run_git(&["branch", "-d", branch_name])
+}
+
 /// 检查分支是否存在
 pub fn branch_exists(branch_name: &str) -> bool {
-    Command::new("git")
+    git()
         .args(["show-ref", "--verify", "--quiet", &format!("refs/heads/{}", branch_name)])
         .output()
         .map(|output| output.status.success())
@@ -316,10 +634,10 @@ pub fn prune_worktrees(dry_run: bool) -> Result<Vec<String>> {
                 pruned.push(format!("Would prune: {} (directory not found)", wt.dirname));
             } else {
                 // 使用 git worktree prune 清理无效的 worktree
-                let output = Command::new("git")
+                let output = git()
                     .args(["worktree", "prune"])
                     .output()
-                    .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+                    .map_err(WorktreeError::git_spawn)?;
 
                 if output.status.success() {
                     pruned.push(format!("Pruned: {} (directory not found)", wt.dirname));
@@ -331,39 +649,153 @@ pub fn prune_worktrees(dry_run: bool) -> Result<Vec<String>> {
     Ok(pruned)
 }
 
+/// 生成 worktree 中指定文件相对 HEAD 的二进制补丁（包含未跟踪文件）
+///
+/// 使用临时索引文件暂存这些文件，不影响 worktree 自己的暂存区
+pub fn diff_with_untracked(path: &Path, files: &[String]) -> Result<Vec<u8>> {
+    let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+    // 同一进程中可能并发调用（如测试），文件名加上计数器区分
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let sequence = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let index_file = std::env::temp_dir().join(format!("work-patch-{}-{}.index", std::process::id(), sequence));
+
+    let run = |args: &[&str]| -> Result<Vec<u8>> {
+        let output = git()
+            .args(["-C", path_str])
+            .args(args)
+            .env("GIT_INDEX_FILE", &index_file)
+            .output()
+            .map_err(WorktreeError::git_spawn)?;
+        if !output.status.success() {
+            return Err(WorktreeError::GitError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(output.stdout)
+    };
+
+    let result = (|| {
+        run(&["read-tree", "HEAD"])?;
+        let mut add_args = vec!["add", "-A", "--"];
+        add_args.extend(files.iter().map(String::as_str));
+        run(&add_args)?;
+        run(&["diff", "--cached", "--binary", "--no-renames", "HEAD"])
+    })();
+
+    let _ = std::fs::remove_file(&index_file);
+    result
+}
+
+/// 把补丁应用到 worktree 的工作区，reverse 为 true 时反向应用（撤销补丁中的更改）
+pub fn apply_patch(path: &Path, patch: &[u8], reverse: bool) -> Result<()> {
+    apply_patch_with(path, patch, if reverse { &["-R"] } else { &[] })
+}
+
+/// 把补丁同时应用到 worktree 的暂存区和工作区
+pub fn apply_patch_to_index(path: &Path, patch: &[u8]) -> Result<()> {
+    apply_patch_with(path, patch, &["--index"])
+}
+
+fn apply_patch_with(path: &Path, patch: &[u8], extra_args: &[&str]) -> Result<()> {
+    use std::io::Write;
+
+    let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+    let mut args = vec!["-C", path_str, "apply", "--binary"];
+    args.extend_from_slice(extra_args);
+
+    let mut child = git()
+        .args(&args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(WorktreeError::git_spawn)?;
+    child.stdin.take().expect("stdin is piped").write_all(patch)?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::GitError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+/// 把 worktree 中的所有更改（包括未跟踪文件）保存到 stash
+pub fn stash_push(path: &Path, message: &str) -> Result<()> {
+    run_git_in(path, &["stash", "push", "--include-untracked", "-m", message]).map(|_| ())
+}
+
+/// 在 worktree 中恢复最近一次 stash（保留暂存状态）
+pub fn stash_pop(path: &Path) -> Result<()> {
+    run_git_in(path, &["stash", "pop", "--index"]).map(|_| ())
+}
+
+/// 把 worktree 切换到指定分支
+pub fn switch_branch(path: &Path, branch: &str) -> Result<()> {
+    run_git_in(path, &["switch", "--quiet", branch]).map(|_| ())
+}
+
+/// 在 repo_path 所属的仓库中为已有分支添加 worktree（不做目录名检查）
+pub fn add_worktree_in(repo_path: &Path, branch: &str, path: &str) -> Result<()> {
+    run_git_in(repo_path, &["worktree", "add", "--quiet", path, branch]).map(|_| ())
+}
+
+/// 取消暂存 worktree 中的指定文件（工作区内容不变）
+pub fn unstage_paths(path: &Path, files: &[String]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+    let mut args = vec!["-C", path_str, "reset", "-q", "HEAD", "--"];
+    args.extend(files.iter().map(String::as_str));
+    run_git(&args).map(|_| ())
+}
+
 /// 获取 worktree 的详细状态信息
 pub fn get_worktree_status(path: &Path) -> Result<WorktreeStatusInfo> {
-    // 检查未提交的更改
-    let output = Command::new("git")
+    // 检查未提交的更改；-z 输出原始路径（不加引号、不转义非 ASCII 字符）
+    let output = git()
         .args(["-C", path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?,
-               "status", "--porcelain=v1"])
+               "status", "--porcelain=v1", "-z"])
         .output()
-        .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+        .map_err(WorktreeError::git_spawn)?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
 
+/// 解析 `git status --porcelain=v1 -z` 的输出
+///
+/// 每项为 `XY <路径>`，以 NUL 分隔；重命名和复制的原路径紧随其后单独成项，
+/// 记录为 `原路径 -> 新路径`（与不带 -z 时的显示一致）。
+fn parse_status(output: &str) -> WorktreeStatusInfo {
     let mut modified = Vec::new();
     let mut staged = Vec::new();
     let mut untracked = Vec::new();
+    let mut conflicted = Vec::new();
 
-    for line in stdout.lines() {
-        if line.is_empty() {
-            continue;
-        }
-
+    let mut entries = output.split('\0');
+    while let Some(entry) = entries.next() {
         // 使用字符迭代器安全地获取前 3 个字符（XY + 空格）
-        let mut chars = line.chars();
+        let mut chars = entry.chars();
 
         let x_status = chars.next().unwrap_or(' ');
         let y_status = chars.next().unwrap_or(' ');
         let space = chars.next().unwrap_or(' ');
 
-        // 跳过不是标准格式行（少于 3 个字符或第 3 个字符不是空格）
-        if space != ' ' {
+        // 跳过不是标准格式的项（少于 3 个字符或第 3 个字符不是空格）
+        if entry.len() < 4 || space != ' ' {
             continue;
         }
 
-        let file_path = chars.as_str().to_string();
+        let mut file_path = chars.as_str().to_string();
+        if matches!(x_status, 'R' | 'C') {
+            if let Some(origin) = entries.next() {
+                file_path = format!("{} -> {}", origin, file_path);
+            }
+        }
+
+        // 未合并的条目（DD、AU、UD、UA、DU、AA、UU）单独列为冲突
+        if matches!((x_status, y_status), ('D', 'D') | ('A', 'A') | ('U', _) | (_, 'U')) {
+            conflicted.push(file_path);
+            continue;
+        }
 
         // 第一个字符：暂存区状态
         // 第二个字符：工作区状态
@@ -386,11 +818,36 @@ pub fn get_worktree_status(path: &Path) -> Result<WorktreeStatusInfo> {
         }
     }
 
-    Ok(WorktreeStatusInfo {
+    WorktreeStatusInfo {
         modified,
         staged,
         untracked,
-    })
+        conflicted,
+    }
+}
+
+/// 获取 worktree 相对上游分支领先/落后的提交数
+///
+/// 没有上游分支时返回 `None`
+pub fn get_ahead_behind(path: &Path) -> Result<Option<(usize, usize)>> {
+    let output = git()
+        .args(["-C", path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?,
+               "rev-list", "--left-right", "--count", "HEAD...@{u}"])
+        .output()
+        .map_err(WorktreeError::git_spawn)?;
+
+    if !output.status.success() {
+        // 没有配置上游分支
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut counts = stdout.split_whitespace().map(|n| n.parse::<usize>());
+
+    match (counts.next(), counts.next()) {
+        (Some(Ok(ahead)), Some(Ok(behind))) => Ok(Some((ahead, behind))),
+        _ => Ok(None),
+    }
 }
 
 /// Worktree 状态详细信息
@@ -399,6 +856,54 @@ pub struct WorktreeStatusInfo {
     pub modified: Vec<String>,
     pub staged: Vec<String>,
     pub untracked: Vec<String>,
+    /// 未解决冲突的文件
+    pub conflicted: Vec<String>,
+}
+
+impl WorktreeStatusInfo {
+    /// 是否没有任何未提交的更改
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.staged.is_empty() && self.untracked.is_empty() && self.conflicted.is_empty()
+    }
+}
+
+/// worktree 私有的 git 目录：主 worktree 为 `.git` 目录，其余为 `.git` 文件中 `gitdir:` 指向的目录
+fn worktree_git_dir(path: &Path) -> Option<PathBuf> {
+    let dot_git = path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let gitdir = content.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim();
+    Some(path.join(gitdir))
+}
+
+/// 读取 worktree 中进行中的操作（合并、变基、拣选、二分查找）
+pub fn detect_operation(path: &Path) -> Option<GitOperation> {
+    let git_dir = worktree_git_dir(path)?;
+    let read_number = |file: &str| -> Option<usize> { std::fs::read_to_string(git_dir.join(file)).ok()?.trim().parse().ok() };
+    let operation = |kind| Some(GitOperation { kind, step: None, total: None });
+
+    // 交互式或 merge 后端的变基使用 rebase-merge，apply 后端使用 rebase-apply
+    for (dir, step, total) in [("rebase-merge", "msgnum", "end"), ("rebase-apply", "next", "last")] {
+        if git_dir.join(dir).is_dir() {
+            return Some(GitOperation {
+                kind: OperationKind::Rebase,
+                step: read_number(&format!("{}/{}", dir, step)),
+                total: read_number(&format!("{}/{}", dir, total)),
+            });
+        }
+    }
+    if git_dir.join("MERGE_HEAD").is_file() {
+        return operation(OperationKind::Merge);
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        return operation(OperationKind::CherryPick);
+    }
+    if git_dir.join("BISECT_LOG").is_file() {
+        return operation(OperationKind::Bisect);
+    }
+    None
 }
 
 /// Worktree 构建数据结构
@@ -406,7 +911,12 @@ struct WorktreeData {
     path: String,
     branch: String,
     head_commit: Option<String>,
+    is_bare: bool,
     is_detached: bool,
+    is_locked: bool,
+    lock_reason: Option<String>,
+    is_prunable: bool,
+    prune_reason: Option<String>,
 }
 
 impl WorktreeData {
@@ -415,42 +925,62 @@ impl WorktreeData {
             path,
             branch: "HEAD".to_string(),
             head_commit: None,
+            is_bare: false,
             is_detached: false,
+            is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
+            prune_reason: None,
         }
     }
 
     /// 转换为 Worktree 结构体
-    fn to_worktree(self) -> Result<Worktree> {
+    fn into_worktree(self) -> Result<Worktree> {
         // 检查是否为当前 worktree
         let current_path = std::env::current_dir()
-            .map_err(|e| WorktreeError::IoError(e))?;
+            .map_err(WorktreeError::IoError)?;
         let is_current = current_path.starts_with(Path::new(&self.path));
 
         // 从路径推断 worktree 名称
         let name = self.derive_worktree_name();
 
-        // 检查是否为裸仓库
-        let is_bare = is_bare_repository().unwrap_or(false);
-
         // 获取上游分支
         let upstream_branch = self.get_upstream_for_worktree(&name)?;
 
-        Ok(Worktree::new(
+        let mut worktree = Worktree::new(
             name,
             self.branch,
             self.path,
             is_current,
-            is_bare,
+            self.is_bare,
             self.is_detached,
             self.head_commit,
             upstream_branch,
-        ))
+        );
+        worktree.is_locked = self.is_locked;
+        worktree.lock_reason = self.lock_reason;
+        worktree.is_prunable = self.is_prunable;
+        worktree.prune_reason = self.prune_reason;
+        if !worktree.is_bare && !worktree.is_prunable {
+            worktree.operation = detect_operation(Path::new(&worktree.path));
+        }
+
+        Ok(worktree)
     }
 
     /// 从路径推断 worktree 名称（基于目录名，不是分支名）
     fn derive_worktree_name(&self) -> String {
         let path = Path::new(&self.path);
 
+        // `<仓库>/.bare` 布局中的裸仓库使用仓库目录名
+        if self.is_bare {
+            if let Some(root) = path.parent().filter(|root| is_bare_layout(root)) {
+                if let Some(name) = root.file_name() {
+                    return name.to_string_lossy().to_string();
+                }
+            }
+        }
+
         // 检查是否在 .worktrees 目录中
         if let Some(parent) = path.parent() {
             if let Some(dir_name) = parent.file_name() {
@@ -485,7 +1015,7 @@ impl WorktreeData {
         // 如果不是当前 worktree，暂时返回 None
         // TODO: 可以通过切换到该 worktree 并运行 git rev-parse @{u} 来获取
         let current_path = std::env::current_dir()
-            .map_err(|e| WorktreeError::IoError(e))?;
+            .map_err(WorktreeError::IoError)?;
         let is_current = current_path.starts_with(Path::new(&self.path));
 
         if is_current {
@@ -501,27 +1031,6 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
-    #[test]
-    fn test_get_repository_root() {
-        // 创建临时目录
-        let temp_dir = TempDir::new().unwrap();
-        let repo_path = temp_dir.path();
-
-        // 初始化 Git 仓库
-        Command::new("git")
-            .args(["init"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-
-        // 更改当前目录到临时目录
-        std::env::set_current_dir(repo_path).unwrap();
-
-        // 获取仓库根目录
-        let root = get_repository_root();
-        assert!(root.is_ok());
-    }
-
     #[test]
     fn test_is_inside_repository() {
         // 创建临时目录
@@ -596,6 +1105,131 @@ mod tests {
         assert_eq!(branch, "main");
     }
 
+    #[test]
+    fn test_parse_worktree_list_entries() {
+        let output = "worktree /code/project/.bare\nbare\n\n\
+                      worktree /code/project/main\nHEAD abc123\nbranch refs/heads/main\n\n\
+                      worktree /code/project/feat-x\nHEAD def456\nbranch refs/heads/feat/x\nlocked on usb drive\n\n\
+                      worktree /code/project/gone\nHEAD 789abc\ndetached\nlocked\nprunable gitdir file points to non-existent location\n";
+        let entries = parse_worktree_list(output);
+        assert_eq!(entries.len(), 4);
+
+        assert!(entries[0].is_bare);
+        assert!(entries[0].head_commit.is_none());
+        assert!(!entries[1].is_bare);
+        assert_eq!(entries[1].branch, "main");
+
+        assert!(entries[2].is_locked);
+        assert_eq!(entries[2].lock_reason.as_deref(), Some("on usb drive"));
+        assert_eq!(entries[2].branch, "feat/x");
+
+        assert!(entries[3].is_detached && entries[3].is_locked && entries[3].is_prunable);
+        assert!(entries[3].lock_reason.is_none());
+        assert_eq!(entries[3].prune_reason.as_deref(), Some("gitdir file points to non-existent location"));
+    }
+
+    #[test]
+    fn test_parse_status_keeps_raw_paths() {
+        let status = parse_status("M  caf\u{e9}.txt\0 M \u{65e5}\u{672c}/a b.txt\0R  new.txt\0old.txt\0?? \"quoted\".txt\0UU both.txt\0");
+        assert_eq!(status.staged, vec!["caf\u{e9}.txt", "old.txt -> new.txt"]);
+        assert_eq!(status.modified, vec!["\u{65e5}\u{672c}/a b.txt"]);
+        assert_eq!(status.untracked, vec!["\"quoted\".txt"]);
+        assert_eq!(status.conflicted, vec!["both.txt"]);
+    }
+
+    #[test]
+    fn test_in_repo_runs_git_in_root_and_restores_after_panic() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        assert!(Command::new("git").args(["init", "--quiet"]).current_dir(&repo).status().unwrap().success());
+
+        let common_dir = in_repo(&repo, get_git_common_dir).unwrap();
+        assert_eq!(common_dir.canonicalize().unwrap(), repo.join(".git").canonicalize().unwrap());
+
+        // 嵌套调用结束后恢复外层的仓库目录
+        let nested = in_repo(Path::new("/"), || in_repo(&repo, repo_dir).map(|inner| (inner, repo_dir().unwrap())));
+        assert_eq!(nested.unwrap(), (repo.clone(), PathBuf::from("/")));
+
+        let panicked = std::panic::catch_unwind(|| in_repo::<()>(&repo, || panic!("inside in_repo")));
+        assert!(panicked.is_err());
+        assert!(REPO_DIR.with(|dir| dir.borrow().is_none()));
+    }
+
+    #[test]
+    fn test_work_excludes_block() {
+        let user = "# user\n*.swp\n";
+        let patterns = vec!["/.env.work".to_string(), "/.npmrc".to_string()];
+        let content = with_work_excludes(user, &patterns);
+        assert_eq!(work_excludes(&content), patterns);
+        assert!(content.starts_with(user));
+
+        // 替换区块时保留区块前后用户自己的条目
+        let content = format!("{}/later\n", content);
+        let content = with_work_excludes(&content, &patterns[..1]);
+        assert_eq!(work_excludes(&content), vec!["/.env.work"]);
+        assert!(content.contains("/later\n") && content.contains("*.swp\n"));
+
+        // 没有条目时去掉整个区块
+        assert_eq!(with_work_excludes(&content, &[]), format!("{}/later\n", user));
+        assert!(work_excludes(user).is_empty());
+    }
+
+    #[test]
+    fn test_detect_operation_and_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = &temp_dir.path().join("repo");
+        std::fs::create_dir(repo).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com", "-c", "core.editor=true"])
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap();
+            output.status.success()
+        };
+        let commit = |file: &str, content: &str| {
+            std::fs::write(repo.join(file), content).unwrap();
+            assert!(git(&["add", file]));
+            assert!(git(&["commit", "--quiet", "-m", file]));
+        };
+
+        assert!(git(&["init", "--quiet", "-b", "main"]));
+        commit("a.txt", "base\n");
+        assert!(git(&["checkout", "--quiet", "-b", "topic"]));
+        commit("a.txt", "topic\n");
+        commit("b.txt", "topic\n");
+        assert!(git(&["checkout", "--quiet", "main"]));
+        commit("a.txt", "main\n");
+        assert_eq!(detect_operation(repo), None);
+
+        // 合并冲突：UU 条目列为冲突文件
+        assert!(!git(&["merge", "--quiet", "topic"]));
+        let operation = detect_operation(repo).unwrap();
+        assert_eq!(operation.kind, OperationKind::Merge);
+        assert_eq!(operation.marker(), "MERGING");
+        let status = get_worktree_status(repo).unwrap();
+        assert_eq!(status.conflicted, vec!["a.txt"]);
+        assert!(status.modified.is_empty() && !status.is_clean());
+        assert!(git(&["merge", "--abort"]));
+
+        // 变基在第一步冲突
+        assert!(git(&["checkout", "--quiet", "topic"]));
+        assert!(!git(&["rebase", "--merge", "main"]));
+        let operation = detect_operation(repo).unwrap();
+        assert_eq!((operation.kind, operation.step, operation.total), (OperationKind::Rebase, Some(1), Some(2)));
+        assert_eq!(operation.marker(), "REBASING 1/2");
+        assert!(git(&["rebase", "--abort"]));
+
+        // 其他 worktree 的状态在各自私有的 git 目录中
+        let linked = temp_dir.path().join("linked");
+        assert!(git(&["worktree", "add", "--quiet", linked.to_str().unwrap(), "main"]));
+        assert!(Command::new("git").args(["bisect", "start"]).current_dir(&linked).output().unwrap().status.success());
+        assert_eq!(detect_operation(&linked).unwrap().kind, OperationKind::Bisect);
+        assert_eq!(detect_operation(repo), None);
+    }
+
     // T037: 单元测试 - branch_to_dirname()
     #[test]
     fn test_branch_to_dirname_simple_branch() {
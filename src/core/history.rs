@@ -0,0 +1,216 @@
+// worktree 切换历史（MRU），保存在 `<git common dir>/work/history.json`
+//
+// `work switch` 和 `work ui` 每次切换时把离开的和进入的 worktree 移到列表最前面，
+// `work switch -` 回到上一个 worktree；交互式选择按 frecency（访问次数按时间衰减加权）排序。
+
+use crate::core::git_ops;
+use crate::core::metadata;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const HISTORY_FILE: &str = "history.json";
+/// 最多保留的条目数
+const MAX_ENTRIES: usize = 200;
+
+/// 一个 worktree 的访问记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Visit {
+    pub path: String,
+    pub dirname: String,
+    /// 切换到该 worktree 的次数
+    pub visits: u32,
+    pub last_visited: DateTime<Utc>,
+}
+
+impl Visit {
+    /// 访问次数按距离上次访问的时间加权，越近权重越高
+    pub fn frecency(&self, now: DateTime<Utc>) -> f64 {
+        let age = now - self.last_visited;
+        let weight = if age < Duration::hours(1) {
+            4.0
+        } else if age < Duration::days(1) {
+            2.0
+        } else if age < Duration::weeks(1) {
+            0.5
+        } else {
+            0.25
+        };
+        f64::from(self.visits.max(1)) * weight
+    }
+}
+
+/// history.json 的内容，最近访问的在前
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    pub entries: Vec<Visit>,
+}
+
+impl History {
+    /// 读取当前仓库的切换历史，文件不存在时返回空
+    pub fn load() -> Result<Self> {
+        Self::load_from(&git_ops::get_git_common_dir()?.join("work").join(HISTORY_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| WorktreeError::InvalidPath(format!("{} is corrupted: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&metadata::data_dir()?.join(HISTORY_FILE))
+    }
+
+    /// 先写临时文件再重命名，避免中断时留下半个文件
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content + "\n")?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 把 worktree 移到最前面；count 为真时计为一次访问
+    pub fn touch(&mut self, path: &str, dirname: &str, now: DateTime<Utc>, count: bool) {
+        let mut visit = match self.entries.iter().position(|v| v.path == path) {
+            Some(index) => self.entries.remove(index),
+            None => Visit { path: path.to_string(), dirname: dirname.to_string(), visits: 0, last_visited: now },
+        };
+        visit.dirname = dirname.to_string();
+        visit.last_visited = now;
+        if count {
+            visit.visits += 1;
+        }
+        self.entries.insert(0, visit);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    /// 记录一次从 from 到 to 的切换
+    pub fn record_switch(&mut self, from: Option<&Worktree>, to: &Worktree, now: DateTime<Utc>) {
+        if let Some(from) = from.filter(|from| from.path != to.path) {
+            self.touch(&from.path, &from.dirname, now, false);
+        }
+        self.touch(&to.path, &to.dirname, now, true);
+    }
+
+    /// 上一个 worktree：历史中最近的、仍然存在且不是当前 worktree 的条目
+    pub fn previous<'a>(&self, worktrees: &'a [Worktree]) -> Option<&'a Worktree> {
+        self.entries
+            .iter()
+            .filter_map(|visit| worktrees.iter().find(|wt| wt.path == visit.path))
+            .find(|wt| !wt.is_current)
+    }
+
+    /// 仍然存在的 worktree 的访问记录，最近的在前
+    pub fn recent(&self, worktrees: &[Worktree]) -> Vec<Visit> {
+        self.entries
+            .iter()
+            .filter(|visit| worktrees.iter().any(|wt| wt.path == visit.path))
+            .cloned()
+            .collect()
+    }
+
+    /// 按 frecency 从高到低排序，没有历史的 worktree 保持原顺序排在最后
+    pub fn sort_by_frecency(&self, worktrees: &mut [&Worktree], now: DateTime<Utc>) {
+        let score = |wt: &Worktree| {
+            self.entries
+                .iter()
+                .find(|visit| visit.path == wt.path)
+                .map_or(0.0, |visit| visit.frecency(now))
+        };
+        worktrees.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    }
+}
+
+/// 记录一次切换，失败只记录日志，不影响切换本身
+pub fn record_switch(worktrees: &[Worktree], to: &Worktree) {
+    let result = History::load().and_then(|mut history| {
+        history.record_switch(worktrees.iter().find(|wt| wt.is_current), to, Utc::now());
+        history.save()
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to record switch history: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn worktree(dirname: &str, is_current: bool) -> Worktree {
+        Worktree::new(dirname.to_string(), dirname.to_string(), format!("/w/{}", dirname), is_current, false, false, None, None)
+    }
+
+    #[test]
+    fn test_previous_toggles_between_worktrees() {
+        let now = Utc::now();
+        let main = worktree("main", true);
+        let feat = worktree("feat", false);
+        let mut history = History::default();
+
+        // 从 main 切换到 feat 后，在 feat 中 `switch -` 回到 main
+        history.record_switch(Some(&main), &feat, now);
+        let in_feat = [worktree("main", false), worktree("feat", true)];
+        assert_eq!(history.previous(&in_feat).unwrap().dirname, "main");
+
+        history.record_switch(Some(&in_feat[1]), &in_feat[0], now);
+        let in_main = [worktree("main", true), worktree("feat", false)];
+        assert_eq!(history.previous(&in_main).unwrap().dirname, "feat");
+
+        // 已删除的 worktree 被跳过
+        assert!(history.previous(&[worktree("main", true)]).is_none());
+    }
+
+    #[test]
+    fn test_touch_counts_visits_and_caps_entries() {
+        let now = Utc::now();
+        let mut history = History::default();
+        history.touch("/w/a", "a", now, true);
+        history.touch("/w/b", "b", now, false);
+        history.touch("/w/a", "a", now, true);
+        assert_eq!(history.entries[0].visits, 2);
+        assert_eq!(history.entries[1].visits, 0);
+
+        for i in 0..MAX_ENTRIES + 10 {
+            history.touch(&format!("/w/{}", i), "x", now, true);
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+    }
+
+    #[test]
+    fn test_sort_by_frecency() {
+        let now = Utc::now();
+        let history = History {
+            entries: vec![
+                Visit { path: "/w/recent".into(), dirname: "recent".into(), visits: 1, last_visited: now },
+                Visit { path: "/w/old".into(), dirname: "old".into(), visits: 5, last_visited: now - Duration::days(30) },
+                Visit { path: "/w/often".into(), dirname: "often".into(), visits: 5, last_visited: now - Duration::hours(3) },
+            ],
+        };
+        let (new, old, often, recent) = (worktree("new", false), worktree("old", false), worktree("often", false), worktree("recent", false));
+        let mut candidates = vec![&new, &old, &often, &recent];
+        history.sort_by_frecency(&mut candidates, now);
+        let names: Vec<&str> = candidates.iter().map(|wt| wt.dirname.as_str()).collect();
+        assert_eq!(names, ["often", "recent", "old", "new"]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(HISTORY_FILE);
+        assert!(History::load_from(&file).unwrap().entries.is_empty());
+
+        let mut history = History::default();
+        history.touch("/w/a", "a", Utc::now(), true);
+        history.save_to(&file).unwrap();
+        assert_eq!(History::load_from(&file).unwrap().entries, history.entries);
+    }
+}
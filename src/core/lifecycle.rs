@@ -0,0 +1,189 @@
+// worktree 的创建和删除流程，命令行、`work ui` 和多仓库工作区共用
+//
+// 创建：检查目录名和冲突 → 执行 git → 记录操作日志 → 按配置设置共享缓存和环境文件。
+// 附带设置的失败不影响创建结果，连同结果一起返回，由调用方决定如何提示。
+// 删除：检查能否删除 → 放入回收站并删除 → 记录操作日志。交互确认等由调用方在两步之间完成。
+
+use crate::core::cache::{self, CacheAction};
+use crate::core::env::{self, EnvSetup, LoaderSetup};
+use crate::core::git_ops;
+use crate::core::oplog;
+use crate::core::trash::{self, TrashEntry};
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use std::path::{Path, PathBuf};
+
+/// 新 worktree 检出的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source<'a> {
+    /// 新建与 worktree 同名的分支，基于 upstream 或当前 HEAD
+    NewBranch { upstream: Option<&'a str> },
+    /// 检出已有分支
+    Branch(&'a str),
+    /// 以分离 HEAD 检出修订
+    Detached(&'a str),
+}
+
+impl<'a> Source<'a> {
+    /// 分支已存在时检出它，否则新建（基于 upstream 或当前 HEAD）
+    pub fn for_branch(branch: &'a str, upstream: Option<&'a str>) -> Self {
+        if git_ops::branch_exists(branch) {
+            Source::Branch(branch)
+        } else {
+            Source::NewBranch { upstream }
+        }
+    }
+}
+
+/// 创建后的附带设置，各项失败互不影响
+#[derive(Debug)]
+pub struct Setup {
+    /// 共享构建缓存（`work.cache.*`）
+    pub cache: Result<Vec<CacheAction>>,
+    /// 端口和 `.env.work`（`work.env.*`）
+    pub env: Result<Option<EnvSetup>>,
+    /// direnv / mise 文件（`work.env.loader`）
+    pub loader: Result<Option<LoaderSetup>>,
+}
+
+impl Setup {
+    /// 失败的设置项
+    pub fn errors(&self) -> Vec<&WorktreeError> {
+        [self.cache.as_ref().err(), self.env.as_ref().err(), self.loader.as_ref().err()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+/// 创建结果
+#[derive(Debug)]
+pub struct Created {
+    pub dirname: String,
+    pub path: String,
+    pub setup: Setup,
+}
+
+/// 检查名称能否用于新 worktree，返回目录名
+pub fn check_name(worktrees: &[Worktree], name: &str) -> Result<String> {
+    let dirname = git_ops::branch_to_dirname(name);
+    git_ops::validate_dirname(&dirname)?;
+    if worktrees.iter().any(|wt| wt.dirname == dirname) {
+        return Err(WorktreeError::AlreadyExists(dirname));
+    }
+    Ok(dirname)
+}
+
+/// 创建名为 name 的 worktree（目录名由 name 转换得到），path 为空时使用默认位置
+pub fn create(name: &str, source: Source, path: Option<&str>) -> Result<Created> {
+    let worktrees = git_ops::list_worktrees()?;
+    let dirname = check_name(&worktrees, name)?;
+    let path = match path {
+        Some(path) => path.to_string(),
+        None => git_ops::default_worktree_path(&dirname)?,
+    };
+
+    let (result, operation) = match source {
+        Source::NewBranch { upstream } => (
+            git_ops::create_worktree_with_new_branch(name, &path, upstream),
+            oplog::Operation::create(&dirname, &path, Some(name), true),
+        ),
+        Source::Branch(branch) => {
+            if !git_ops::branch_exists(branch) {
+                return Err(WorktreeError::BranchNotFound(branch.to_string()));
+            }
+            (
+                git_ops::create_worktree(branch, &path),
+                oplog::Operation::create(&dirname, &path, Some(branch), false),
+            )
+        }
+        Source::Detached(rev) => (
+            git_ops::create_detached_worktree(rev, &path),
+            oplog::Operation::create(&dirname, &path, None, false),
+        ),
+    };
+    oplog::record_outcome(operation, &result);
+    result?;
+
+    // 缓存路径模板中的分支在分离 HEAD 时使用修订名，环境文件中的分支则留空
+    let (cache_branch, env_branch) = match source {
+        Source::NewBranch { .. } => (name, name),
+        Source::Branch(branch) => (branch, branch),
+        Source::Detached(rev) => (rev, ""),
+    };
+    let setup = setup(&worktrees, Path::new(&path), &dirname, cache_branch, env_branch);
+
+    Ok(Created { dirname, path, setup })
+}
+
+/// 为不经 [`create`] 创建的 worktree（如 `work clone` 检出的第一个 worktree）补做附带设置
+pub fn setup_existing(worktree_path: &Path, dirname: &str, branch: &str) -> Result<Setup> {
+    let worktrees = git_ops::list_worktrees()?;
+    Ok(setup(&worktrees, worktree_path, dirname, branch, branch))
+}
+
+fn setup(worktrees: &[Worktree], worktree_path: &Path, dirname: &str, cache_branch: &str, env_branch: &str) -> Setup {
+    Setup {
+        cache: setup_cache(worktrees, worktree_path, cache_branch, dirname),
+        env: env::setup(worktree_path, dirname, env_branch),
+        loader: env::setup_loader(worktree_path, dirname, env_branch),
+    }
+}
+
+/// 按 work.cache.* 设置共享构建缓存
+fn setup_cache(worktrees: &[Worktree], worktree_path: &Path, branch: &str, dirname: &str) -> Result<Vec<CacheAction>> {
+    let config = cache::CacheConfig::from_config()?;
+    if !config.is_enabled() {
+        return Ok(Vec::new());
+    }
+
+    // 从当前 worktree 复制构建目录，不在 worktree 中时使用主 worktree
+    let seed_from = match worktrees.iter().find(|wt| wt.is_current && !wt.is_bare) {
+        Some(current) => PathBuf::from(&current.path),
+        None => git_ops::get_main_repository_root()?,
+    };
+    cache::apply(&config, worktree_path, branch, dirname, Some(&seed_from))
+}
+
+/// 检查 worktree 能否删除：必须已检出、不是当前所在的 worktree，且没有进行中的操作（force 时忽略）
+pub fn ensure_deletable(worktree: &Worktree, force: bool) -> Result<()> {
+    worktree.ensure_checkout()?;
+    if worktree.is_current {
+        return Err(WorktreeError::CannotDeleteCurrent(worktree.dirname.clone()));
+    }
+    worktree.ensure_idle(force)
+}
+
+/// 删除 worktree 并记录操作日志，use_trash 为 true 时先放入回收站
+pub fn delete(worktree: &Worktree, force: bool, use_trash: bool) -> Result<Option<TrashEntry>> {
+    let result = trash::delete(worktree, force, use_trash);
+    let trash_id = result.as_ref().ok().and_then(|entry| entry.as_ref()).map(|entry| entry.id.as_str());
+    oplog::record_outcome(oplog::Operation::delete(worktree, trash_id), &result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worktree(dirname: &str) -> Worktree {
+        Worktree::new(
+            dirname.to_string(),
+            dirname.to_string(),
+            format!("/repo.worktrees/{}", dirname),
+            false,
+            false,
+            false,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_check_name_uses_converted_dirname() {
+        let worktrees = vec![worktree("feat-x")];
+        assert_eq!(check_name(&worktrees, "fix/y").unwrap(), "fix-y");
+        assert!(matches!(check_name(&worktrees, "feat/x"), Err(WorktreeError::AlreadyExists(name)) if name == "feat-x"));
+        assert!(check_name(&worktrees, "..").is_err());
+    }
+}
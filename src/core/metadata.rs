@@ -0,0 +1,158 @@
+// work 自己的每仓库数据，保存在 `<git common dir>/work/` 下，所有 worktree 共享
+//
+// metadata.json 记录 git 本身不保存的 worktree 属性（如临时 worktree 的过期时间），
+// 以 worktree 的绝对路径为键。
+
+use crate::core::git_ops;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const METADATA_FILE: &str = "metadata.json";
+
+/// work 的每仓库数据目录 `<git common dir>/work`，不存在时创建
+pub fn data_dir() -> Result<PathBuf> {
+    let dir = git_ops::get_git_common_dir()?.join("work");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 单个 worktree 的附加属性
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorktreeMeta {
+    /// 是否为临时 worktree（`work scratch` 创建）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub temporary: bool,
+    /// 过期时间，过期后可由 `work clean --expired` 删除
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 创建时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// 分配的端口块的起始端口（见 `work.env.ports`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// 别名（`work alias set`），可代替目录名或分支名使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// 备注（在 `work ui` 中按 n 编辑）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// metadata.json 的内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default)]
+    pub worktrees: BTreeMap<String, WorktreeMeta>,
+}
+
+impl Metadata {
+    /// 读取当前仓库的元数据，文件不存在时返回空数据
+    pub fn load() -> Result<Self> {
+        // 只读时不创建数据目录
+        Self::load_from(&git_ops::get_git_common_dir()?.join("work").join(METADATA_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| WorktreeError::InvalidPath(format!("{} is corrupted: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Metadata::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 写回当前仓库的元数据
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&data_dir()?.join(METADATA_FILE))
+    }
+
+    /// 先写临时文件再重命名，避免中断时留下半个文件
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content + "\n")?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 把元数据中的属性填入 worktree 列表
+    pub fn apply(&self, worktrees: &mut [Worktree]) {
+        for wt in worktrees {
+            if let Some(meta) = self.worktrees.get(&wt.path) {
+                wt.is_temporary = meta.temporary;
+                wt.expires_at = meta.expires_at;
+                wt.alias = meta.alias.clone();
+                wt.note = meta.note.clone();
+            }
+        }
+    }
+
+    /// 设置或清除（note 为空时）worktree 的备注，没有其他属性时删除整个条目
+    pub fn set_note(&mut self, path: &str, note: Option<&str>) {
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+        let meta = self.worktrees.entry(path.to_string()).or_default();
+        meta.note = note.map(str::to_string);
+        if *meta == WorktreeMeta::default() {
+            self.worktrees.remove(path);
+        }
+    }
+}
+
+/// 读取、修改并写回当前仓库的元数据
+pub fn update(f: impl FnOnce(&mut Metadata)) -> Result<()> {
+    let mut metadata = Metadata::load()?;
+    f(&mut metadata);
+    metadata.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roundtrip_and_apply() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(METADATA_FILE);
+        assert!(Metadata::load_from(&file).unwrap().worktrees.is_empty());
+
+        let expires = Utc::now();
+        let mut metadata = Metadata::default();
+        metadata.worktrees.insert(
+            "/src/repo.worktrees/scratch-1".to_string(),
+            WorktreeMeta { temporary: true, expires_at: Some(expires), ..Default::default() },
+        );
+        metadata.save_to(&file).unwrap();
+
+        let loaded = Metadata::load_from(&file).unwrap();
+        let mut worktrees = vec![
+            Worktree::new("repo".into(), "main".into(), "/src/repo".into(), false, false, false, None, None),
+            Worktree::new("scratch-1".into(), "HEAD".into(), "/src/repo.worktrees/scratch-1".into(), false, false, true, None, None),
+        ];
+        loaded.apply(&mut worktrees);
+
+        assert!(!worktrees[0].is_temporary);
+        assert!(worktrees[1].is_temporary);
+        assert_eq!(worktrees[1].expires_at, Some(expires));
+    }
+
+    #[test]
+    fn test_set_note_and_clear() {
+        let mut metadata = Metadata::default();
+        metadata.set_note("/w/a", Some("  waiting for review "));
+        assert_eq!(metadata.worktrees["/w/a"].note.as_deref(), Some("waiting for review"));
+
+        metadata.set_note("/w/a", Some(""));
+        assert!(metadata.worktrees.is_empty());
+
+        metadata.worktrees.insert("/w/b".to_string(), WorktreeMeta { temporary: true, ..Default::default() });
+        metadata.set_note("/w/b", Some("x"));
+        metadata.set_note("/w/b", None);
+        assert!(metadata.worktrees["/w/b"].temporary);
+    }
+}
@@ -1,3 +1,23 @@
+pub mod alias;
+pub mod cache;
+pub mod carry;
+pub mod clone;
+pub mod config;
+pub mod du;
+pub mod editor;
+pub mod env;
 pub mod git_ops;
+pub mod history;
+pub mod lifecycle;
+pub mod metadata;
+pub mod multi_repo;
+pub mod oplog;
+pub mod promote;
+pub mod registry;
 pub mod repository;
+pub mod scratch;
+pub mod session;
+pub mod snapshot;
+pub mod trash;
+pub mod workspace;
 pub mod worktree;
@@ -0,0 +1,222 @@
+// 多仓库工作区：一个功能跨多个仓库时，在每个仓库中同时创建、列出、切换和删除同名的 worktree。
+//
+// 工作区文件 `work.repos` 每行一个仓库路径（相对文件所在目录，支持 `~`），`#` 开头为注释。
+// 命令从当前目录向上查找该文件，也可以用 `--file` 指定。
+//
+// 每个仓库通过 `Repository::from_path` 打开，然后用 `git_ops::in_repo` 在该仓库中执行与单仓库相同的
+// 创建和删除流程（core::lifecycle），git 命令、配置、元数据、回收站和操作日志都以该仓库为准。
+
+use crate::core::git_ops;
+use crate::core::lifecycle::{self, Created, Source};
+use crate::core::repository::Repository;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 工作区文件名
+pub const WORKSPACE_FILE: &str = "work.repos";
+
+/// 工作区中的一个仓库
+#[derive(Debug, Clone)]
+pub struct RepoEntry {
+    /// 仓库名（主目录的目录名）
+    pub name: String,
+    pub repository: Repository,
+}
+
+/// 多仓库工作区
+#[derive(Debug, Clone)]
+pub struct MultiRepo {
+    pub repos: Vec<RepoEntry>,
+}
+
+/// 某个仓库中的一个 worktree
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoWorktree {
+    pub repo: String,
+    #[serde(flatten)]
+    pub worktree: Worktree,
+}
+
+/// 在一个仓库中执行操作的结果
+#[derive(Debug)]
+pub enum RepoOutcome {
+    /// 创建成功，附带附带设置的结果
+    Created(Created),
+    /// 成功，附带 worktree 路径
+    Done(String),
+    /// 跳过（如 worktree 已存在或不存在），附带原因
+    Skipped(String),
+    Failed(WorktreeError),
+}
+
+/// 解析工作区文件内容，返回仓库路径（相对路径基于 base 展开）
+pub fn parse(content: &str, base: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match (line.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+            _ => base.join(line),
+        })
+        .collect()
+}
+
+/// 从 start 向上查找工作区文件
+pub fn find_file(start: &Path) -> Option<PathBuf> {
+    start.ancestors().map(|dir| dir.join(WORKSPACE_FILE)).find(|file| file.is_file())
+}
+
+impl MultiRepo {
+    /// 读取工作区：file 为空时从当前目录向上查找
+    pub fn load(file: Option<&Path>) -> Result<Self> {
+        let file = match file {
+            Some(file) => file.to_path_buf(),
+            None => find_file(&std::env::current_dir()?).ok_or_else(|| {
+                WorktreeError::NotFound(tf("error.detail.workspace_not_found", &[("file", WORKSPACE_FILE)]))
+            })?,
+        };
+        let content = std::fs::read_to_string(&file)
+            .map_err(|e| WorktreeError::InvalidPath(format!("{}: {}", file.display(), e)))?;
+        let base = file.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let mut repos = Vec::new();
+        for path in parse(&content, &base) {
+            let repository = Repository::from_path(&path)?;
+            let name = repository
+                .root_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("repo")
+                .to_string();
+            repos.push(RepoEntry { name, repository });
+        }
+
+        if repos.is_empty() {
+            return Err(WorktreeError::InvalidArgument(tf(
+                "error.detail.workspace_empty",
+                &[("file", &file.display().to_string())],
+            )));
+        }
+        Ok(MultiRepo { repos })
+    }
+
+    /// 所有仓库的 worktree；is_current 以调用时的当前目录为准
+    pub fn list(&self) -> Result<Vec<RepoWorktree>> {
+        let mut result = Vec::new();
+        for repo in &self.repos {
+            for worktree in git_ops::in_repo(&repo.repository.root_path, git_ops::list_worktrees)? {
+                result.push(RepoWorktree { repo: repo.name.clone(), worktree });
+            }
+        }
+        Ok(result)
+    }
+
+    /// 在每个仓库中创建同名分支和 worktree；分支已存在时直接检出，新分支基于 base（默认为仓库的 HEAD）
+    pub fn create(&self, branch: &str, base: Option<&str>) -> Vec<(String, RepoOutcome)> {
+        self.each(|| match lifecycle::create(branch, Source::for_branch(branch, base), None) {
+            Ok(created) => Ok(RepoOutcome::Created(created)),
+            Err(WorktreeError::AlreadyExists(dirname)) => {
+                Ok(RepoOutcome::Skipped(tf("error.detail.ws_exists", &[("name", &dirname)])))
+            }
+            Err(e) => Err(e),
+        })
+    }
+
+    /// 在每个仓库中删除名为 name 的 worktree（放入各自的回收站）
+    pub fn delete(&self, name: &str, force: bool) -> Vec<(String, RepoOutcome)> {
+        self.each(|| {
+            let worktrees = git_ops::list_worktrees()?;
+            let Ok(worktree) = Worktree::resolve(&worktrees, name) else {
+                return Ok(RepoOutcome::Skipped(tf("error.detail.ws_missing", &[("name", name)])));
+            };
+            lifecycle::ensure_deletable(worktree, force)?;
+            if !force && worktree.has_uncommitted_changes() {
+                return Err(WorktreeError::UncommittedChanges(worktree.dirname.clone()));
+            }
+
+            lifecycle::delete(worktree, force, true)?;
+            Ok(RepoOutcome::Done(worktree.path.clone()))
+        })
+    }
+
+    /// 名为 name 的 worktree 在各仓库中的路径（跳过裸仓库）
+    pub fn find(&self, name: &str) -> Result<Vec<RepoWorktree>> {
+        let worktrees = self.list()?;
+        let found: Vec<RepoWorktree> = self
+            .repos
+            .iter()
+            .filter_map(|repo| {
+                let in_repo: Vec<Worktree> = worktrees
+                    .iter()
+                    .filter(|wt| wt.repo == repo.name)
+                    .map(|wt| wt.worktree.clone())
+                    .collect();
+                Worktree::resolve(&in_repo, name)
+                    .ok()
+                    .filter(|wt| !wt.is_bare)
+                    .map(|wt| RepoWorktree { repo: repo.name.clone(), worktree: wt.clone() })
+            })
+            .collect();
+
+        if found.is_empty() {
+            return Err(WorktreeError::NotFound(name.to_string()));
+        }
+        Ok(found)
+    }
+
+    /// 当前目录所在的仓库
+    pub fn current_repo(&self) -> Option<&RepoEntry> {
+        let cwd = std::env::current_dir().ok()?;
+        let worktrees = self.list().ok()?;
+        worktrees
+            .iter()
+            .find(|wt| cwd.starts_with(&wt.worktree.path))
+            .and_then(|wt| self.repos.iter().find(|repo| repo.name == wt.repo))
+    }
+
+    fn each(&self, f: impl Fn() -> Result<RepoOutcome>) -> Vec<(String, RepoOutcome)> {
+        self.repos
+            .iter()
+            .map(|repo| {
+                let outcome = git_ops::in_repo(&repo.repository.root_path, &f).unwrap_or_else(RepoOutcome::Failed);
+                (repo.name.clone(), outcome)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_workspace_file() {
+        let base = Path::new("/code/product");
+        let repos = parse("# product repos\napi\n\n  web  \n../shared/infra\n/abs/repo\n", base);
+        assert_eq!(
+            repos,
+            vec![
+                PathBuf::from("/code/product/api"),
+                PathBuf::from("/code/product/web"),
+                PathBuf::from("/code/product/../shared/infra"),
+                PathBuf::from("/abs/repo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_file_searches_parents() {
+        let temp = TempDir::new().unwrap();
+        let nested = temp.path().join("api/src/bin");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_file(&nested), None);
+
+        std::fs::write(temp.path().join(WORKSPACE_FILE), "api\n").unwrap();
+        assert_eq!(find_file(&nested), Some(temp.path().join(WORKSPACE_FILE)));
+    }
+}
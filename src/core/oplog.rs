@@ -0,0 +1,340 @@
+// 操作日志：每个修改 worktree 的操作追加一行 JSON 到 `<git common dir>/work/oplog.jsonl`，
+// 记录时间、用户、命令行参数、涉及的路径、HEAD 前后的提交以及结果，可用 `work log` 查看。
+//
+// `work undo` 从日志末尾查找最近一个尚未撤销的 create / delete；撤销本身也记录为一条 undo 操作，
+// 因此连续执行 undo 会依次撤销更早的操作。
+
+use crate::core::metadata;
+use crate::core::worktree::Worktree;
+use crate::core::git_ops;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+const OPLOG_FILE: &str = "oplog.jsonl";
+
+/// 操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpKind {
+    Create,
+    Delete,
+    Prune,
+    Promote,
+    Carry,
+    Lock,
+    Unlock,
+    Sync,
+    /// 删除被忽略的内容（`work du --clean-ignored`）
+    Purge,
+    Undo,
+}
+
+impl OpKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpKind::Create => "create",
+            OpKind::Delete => "delete",
+            OpKind::Prune => "prune",
+            OpKind::Promote => "promote",
+            OpKind::Carry => "carry",
+            OpKind::Lock => "lock",
+            OpKind::Unlock => "unlock",
+            OpKind::Sync => "sync",
+            OpKind::Purge => "purge",
+            OpKind::Undo => "undo",
+        }
+    }
+}
+
+/// 操作结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpResult {
+    #[default]
+    Ok,
+    Failed,
+}
+
+/// 日志中的一条操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub id: String,
+    pub time: DateTime<Utc>,
+    pub kind: OpKind,
+    /// 执行操作的系统用户
+    #[serde(default)]
+    pub user: String,
+    /// `work` 之后的命令行参数
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub dirname: String,
+    pub path: String,
+    /// 操作涉及的另一个 worktree 路径（carry 的目标、promote 创建的新 worktree）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// 操作前 HEAD 的提交
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_before: Option<String>,
+    /// 操作后 HEAD 的提交
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_after: Option<String>,
+    #[serde(default)]
+    pub result: OpResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// create 时新建了分支，撤销时一并删除
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub created_branch: bool,
+    /// delete 时保存到回收站的条目
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>,
+    /// undo 撤销的操作 id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<String>,
+}
+
+impl Operation {
+    pub fn new(kind: OpKind, dirname: &str, path: &str) -> Self {
+        let time = Utc::now();
+        Operation {
+            // 时间戳加进程号，同一仓库的多个进程同时写入也不会重复
+            id: format!("{}-{}", time.format("%Y%m%d%H%M%S%6f"), std::process::id()),
+            time,
+            kind,
+            user: current_user(),
+            args: std::env::args().skip(1).collect(),
+            dirname: dirname.to_string(),
+            path: path.to_string(),
+            target: None,
+            branch: None,
+            head_before: None,
+            head_after: None,
+            result: OpResult::Ok,
+            error: None,
+            created_branch: false,
+            trash_id: None,
+            undoes: None,
+        }
+    }
+
+    /// 创建 worktree 的操作
+    pub fn create(dirname: &str, path: &str, branch: Option<&str>, created_branch: bool) -> Self {
+        let mut op = Operation::new(OpKind::Create, dirname, path);
+        op.branch = branch.map(str::to_string);
+        op.created_branch = created_branch;
+        op.head_after = head_of(Path::new(path));
+        op
+    }
+
+    /// 删除 worktree 的操作
+    pub fn delete(worktree: &Worktree, trash_id: Option<&str>) -> Self {
+        let mut op = Operation::for_worktree(OpKind::Delete, worktree);
+        op.trash_id = trash_id.map(str::to_string);
+        op
+    }
+
+    /// 针对已有 worktree 的操作，记录操作前的 HEAD
+    pub fn for_worktree(kind: OpKind, worktree: &Worktree) -> Self {
+        let mut op = Operation::new(kind, &worktree.dirname, &worktree.path);
+        op.branch = (!worktree.is_detached).then(|| worktree.branch_name.clone());
+        op.head_before = worktree.head_commit.clone();
+        op
+    }
+
+    /// 记录操作后 path 的 HEAD
+    pub fn with_head_after(mut self, path: &Path) -> Self {
+        self.head_after = head_of(path);
+        self
+    }
+
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// 标记为失败
+    pub fn failed(mut self, error: &WorktreeError) -> Self {
+        self.result = OpResult::Failed;
+        self.error = Some(error.to_string());
+        self
+    }
+
+    /// 撤销 target 的操作
+    pub fn undo(target: &Operation) -> Self {
+        let mut op = Operation::new(OpKind::Undo, &target.dirname, &target.path);
+        op.branch = target.branch.clone();
+        op.undoes = Some(target.id.clone());
+        op
+    }
+}
+
+/// 追加一条操作到当前仓库的日志
+pub fn append(op: &Operation) -> Result<()> {
+    append_to(&metadata::data_dir()?.join(OPLOG_FILE), op)
+}
+
+/// 以追加模式写入单独一行，多个进程同时写入时各行保持完整
+pub fn append_to(path: &Path, op: &Operation) -> Result<()> {
+    let mut line = serde_json::to_string(op).map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// 记录操作：worktree 已经修改完成，写日志失败只给出警告
+pub fn record(op: &Operation) {
+    if let Err(e) = append(op) {
+        log::warn!("Failed to write operation log: {}", e);
+    }
+}
+
+/// 按结果记录操作：失败时附带错误信息
+pub fn record_outcome<T>(op: Operation, result: &Result<T>) {
+    match result {
+        Ok(_) => record(&op),
+        Err(e) => record(&op.failed(e)),
+    }
+}
+
+/// 系统用户名，环境变量都未设置时（如某些 CI、容器）退回到 git 的 user.email
+fn current_user() -> String {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|user| !user.is_empty())
+        .or_else(|| {
+            git_ops::run_git_bytes_in(Path::new("."), &["config", "user.email"])
+                .ok()
+                .map(|out| String::from_utf8_lossy(&out).trim().to_string())
+        })
+        .unwrap_or_default()
+}
+
+fn head_of(path: &Path) -> Option<String> {
+    git_ops::run_git_bytes_in(path, &["rev-parse", "--verify", "--quiet", "HEAD"])
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out).trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// 解析 `--since`：相对时长（如 `12h`、`7d`）、日期（`2025-01-01`）或 RFC 3339 时间
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(duration) = crate::core::scratch::parse_ttl(value) {
+        return Ok(now - duration);
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|time| time.and_local_timezone(chrono::Local).earliest())
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| WorktreeError::InvalidArgument(tf("error.detail.invalid_since", &[("value", value)])))
+}
+
+/// 读取当前仓库的全部操作（按时间顺序），无法解析的行会被跳过
+pub fn read_all() -> Result<Vec<Operation>> {
+    // 只读时不创建数据目录
+    read_from(&git_ops::get_git_common_dir()?.join("work").join(OPLOG_FILE))
+}
+
+pub fn read_from(path: &Path) -> Result<Vec<Operation>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// 最近一个可以撤销的操作（成功且未被撤销的 create 或 delete）
+pub fn last_undoable(ops: &[Operation]) -> Option<&Operation> {
+    let undone: HashSet<&str> = ops
+        .iter()
+        .filter(|op| op.result == OpResult::Ok)
+        .filter_map(|op| op.undoes.as_deref())
+        .collect();
+
+    ops.iter()
+        .rev()
+        .filter(|op| matches!(op.kind, OpKind::Create | OpKind::Delete) && op.result == OpResult::Ok)
+        .find(|op| !undone.contains(op.id.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn op(kind: OpKind, id: &str) -> Operation {
+        let mut op = Operation::new(kind, id, &format!("/w/{}", id));
+        op.id = id.to_string();
+        op
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(OPLOG_FILE);
+        assert!(read_from(&file).unwrap().is_empty());
+
+        append_to(&file, &Operation::create("feat", "/w/feat", Some("feat"), true)).unwrap();
+        std::fs::OpenOptions::new().append(true).open(&file).unwrap().write_all(b"not json\n").unwrap();
+        append_to(&file, &op(OpKind::Delete, "old")).unwrap();
+
+        let ops = read_from(&file).unwrap();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].kind, OpKind::Create);
+        assert!(ops[0].created_branch);
+        assert_eq!(ops[1].dirname, "old");
+    }
+
+    #[test]
+    fn test_last_undoable_skips_undone() {
+        let a = op(OpKind::Create, "a");
+        let b = op(OpKind::Delete, "b");
+        let undo_b = Operation::undo(&b);
+        let mut ops = vec![a.clone(), b.clone()];
+        assert_eq!(last_undoable(&ops).unwrap().id, "b");
+
+        ops.push(undo_b);
+        assert_eq!(last_undoable(&ops).unwrap().id, "a");
+
+        ops.push(Operation::undo(&a));
+        assert!(last_undoable(&ops).is_none());
+    }
+
+    #[test]
+    fn test_last_undoable_ignores_failed_and_other_kinds() {
+        let a = op(OpKind::Create, "a");
+        let failed = op(OpKind::Delete, "b").failed(&WorktreeError::UncommittedChanges("b".to_string()));
+        let ops = vec![a, failed, op(OpKind::Lock, "c")];
+        assert_eq!(last_undoable(&ops).unwrap().id, "a");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("12h", now).unwrap(), now - chrono::Duration::hours(12));
+        assert_eq!(
+            parse_since("2025-01-01T08:00:00Z", now).unwrap(),
+            DateTime::parse_from_rfc3339("2025-01-01T08:00:00Z").unwrap()
+        );
+        assert!(parse_since("2025-01-01", now).unwrap() < now);
+        assert!(parse_since("yesterday", now).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+// work promote：把当前 checkout 的分支及其未提交的更改移到一个新的 worktree，
+// 当前 checkout 切回默认分支
+//
+// 等价于手动执行 stash、switch、worktree add 和 stash pop；
+// 任何一步失败都会尽量回滚到原来的状态。
+
+use crate::core::git_ops;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use std::path::Path;
+
+/// 执行 promote，返回是否转移了未提交的更改
+pub fn promote(current: &Worktree, default_branch: &str, target_path: &str) -> Result<bool> {
+    if current.is_detached {
+        return Err(WorktreeError::InvalidArgument(t("error.detail.promote_detached")));
+    }
+    let branch = current.branch_name.as_str();
+    if branch == default_branch {
+        return Err(WorktreeError::InvalidArgument(tf("error.detail.promote_default_branch", &[("branch", branch)])));
+    }
+
+    let current_path = Path::new(&current.path);
+    let dirty = !git_ops::get_worktree_status(current_path)?.is_clean();
+
+    let stash_message = format!("work promote {}", branch);
+    let stash = dirty.then_some(stash_message.as_str());
+    if let Some(message) = stash {
+        git_ops::stash_push(current_path, message)?;
+    }
+
+    // 切回默认分支，失败时恢复更改
+    if let Err(e) = git_ops::switch_branch(current_path, default_branch) {
+        return Err(rollback(current_path, e, None, stash));
+    }
+
+    // 分支已不再被检出，可以为它创建 worktree；失败时回到原分支
+    if let Err(e) = git_ops::add_worktree_in(current_path, branch, target_path) {
+        return Err(rollback(current_path, e, Some(branch), stash));
+    }
+
+    if dirty {
+        git_ops::stash_pop(Path::new(target_path)).map_err(|e| {
+            WorktreeError::GitError(tf("error.detail.promote_restore_failed", &[("error", &e.to_string())]))
+        })?;
+    }
+
+    Ok(dirty)
+}
+
+/// 回滚失败的 promote：切回原分支（switch_back）并恢复暂存的更改（stash 为 stash 的说明）
+///
+/// 回滚全部成功时返回原来的错误；否则把原错误、回滚失败的原因和更改所在的 stash 一起报告。
+fn rollback(path: &Path, error: WorktreeError, switch_back: Option<&str>, stash: Option<&str>) -> WorktreeError {
+    let mut failures = Vec::new();
+
+    if let Some(branch) = switch_back {
+        if let Err(e) = git_ops::switch_branch(path, branch) {
+            failures.push(tf("error.detail.rollback_switch_failed", &[("branch", branch), ("error", &e.to_string())]));
+        }
+    }
+
+    if let Some(message) = stash {
+        // 没能切回原分支时不恢复更改，以免应用到错误的分支上
+        if failures.is_empty() {
+            if let Err(e) = git_ops::stash_pop(path) {
+                failures.push(tf("error.detail.rollback_stash_failed", &[("error", &e.to_string())]));
+            }
+        }
+        if !failures.is_empty() {
+            failures.push(tf(
+                "error.detail.rollback_stash_kept",
+                &[("stash", message), ("path", &path.display().to_string())],
+            ));
+        }
+    }
+
+    if failures.is_empty() {
+        error
+    } else {
+        WorktreeError::GitError(tf(
+            "error.detail.rollback_incomplete",
+            &[("error", &error.to_string()), ("failures", &failures.join("; "))],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn setup() -> (TempDir, Worktree) {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test User"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("file.txt"), "one\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+        git(&repo, &["switch", "-q", "-c", "feature/x"]);
+
+        let path = repo.to_string_lossy().to_string();
+        let current = Worktree::new("repo".to_string(), "feature/x".to_string(), path, true, false, false, None, None);
+        (temp, current)
+    }
+
+    #[test]
+    fn test_promote_moves_branch_and_changes() {
+        let (temp, current) = setup();
+        let repo = Path::new(&current.path);
+        std::fs::write(repo.join("file.txt"), "two\n").unwrap();
+        git(repo, &["add", "file.txt"]);
+        std::fs::write(repo.join("new.txt"), "new\n").unwrap();
+
+        let target = temp.path().join("feature-x");
+        assert!(promote(&current, "main", target.to_str().unwrap()).unwrap());
+
+        assert_eq!(git(repo, &["branch", "--show-current"]), "main");
+        assert_eq!(git(repo, &["status", "--porcelain"]), "");
+
+        assert_eq!(git(&target, &["branch", "--show-current"]), "feature/x");
+        assert_eq!(git(&target, &["status", "--porcelain"]), "M  file.txt\n?? new.txt");
+        assert_eq!(git(repo, &["stash", "list"]), "");
+    }
+
+    #[test]
+    fn test_promote_rolls_back_on_failure() {
+        let (temp, current) = setup();
+        let repo = Path::new(&current.path);
+        std::fs::write(repo.join("file.txt"), "two\n").unwrap();
+
+        // 目标路径已存在且非空，worktree add 失败
+        let target = temp.path().join("occupied");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("x"), "x").unwrap();
+
+        assert!(promote(&current, "main", target.to_str().unwrap()).is_err());
+        assert_eq!(git(repo, &["branch", "--show-current"]), "feature/x");
+        assert_eq!(git(repo, &["status", "--porcelain"]), "M file.txt");
+    }
+
+    #[test]
+    fn test_rollback_failure_reports_both_errors_and_stash() {
+        let (_temp, current) = setup();
+        let repo = Path::new(&current.path);
+        std::fs::write(repo.join("file.txt"), "two\n").unwrap();
+        git_ops::stash_push(repo, "work promote feature/x").unwrap();
+
+        let original = WorktreeError::GitError("worktree add failed".to_string());
+        let message = rollback(repo, original, Some("no-such-branch"), Some("work promote feature/x")).to_string();
+        assert!(message.contains("worktree add failed"), "{}", message);
+        assert!(message.contains("no-such-branch"), "{}", message);
+        assert!(message.contains("work promote feature/x"), "{}", message);
+
+        // 更改仍保存在 stash 中
+        assert!(git(repo, &["stash", "list"]).contains("work promote feature/x"));
+    }
+
+    #[test]
+    fn test_promote_rejects_default_branch() {
+        let (_temp, mut current) = setup();
+        current.branch_name = "main".to_string();
+        assert!(matches!(promote(&current, "main", "/unused"), Err(WorktreeError::InvalidArgument(_))));
+    }
+}
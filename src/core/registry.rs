@@ -0,0 +1,328 @@
+// 全局仓库索引：记录 work 用过的所有仓库，用于 `work ls --all` 和 `work switch --global`
+//
+// 索引保存在 `$WORK_DATA_DIR/repos.json`（默认为 `$XDG_DATA_HOME/work` 或 `~/.local/share/work`），
+// 在仓库中执行任意 work 命令时自动登记，也可以用 `work scan <目录>` 批量发现。
+// 仓库被移动或删除后条目保留并在列表中标记为缺失，`work scan --prune` 清除这些条目。
+
+use crate::core::git_ops;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const REGISTRY_FILE: &str = "repos.json";
+/// 扫描时跳过的目录（除隐藏目录外）
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+/// 同一仓库两次登记之间的最短间隔，避免每条命令都写文件
+const TOUCH_INTERVAL_HOURS: i64 = 1;
+
+/// 索引中的一个仓库
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredRepo {
+    /// 主仓库目录
+    pub path: PathBuf,
+    /// 仓库名（主目录的目录名）
+    pub name: String,
+    pub last_used: DateTime<Utc>,
+}
+
+/// 全局列表中的一个 worktree
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalWorktree {
+    pub repo: String,
+    pub repo_path: PathBuf,
+    #[serde(flatten)]
+    pub worktree: Worktree,
+    /// 是否有未提交的更改
+    pub dirty: bool,
+    /// HEAD 提交的时间
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+impl GlobalWorktree {
+    /// `仓库名/目录名`，用于跨仓库唯一地指定 worktree
+    pub fn qualified_name(&self) -> String {
+        format!("{}/{}", self.repo, self.worktree.dirname)
+    }
+}
+
+/// repos.json 的内容，最近使用的在前
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default)]
+    pub repos: Vec<RegisteredRepo>,
+}
+
+/// 索引文件路径
+pub fn registry_file() -> Result<PathBuf> {
+    let dir = match (std::env::var_os("WORK_DATA_DIR"), std::env::var_os("XDG_DATA_HOME"), std::env::var_os("HOME")) {
+        (Some(dir), _, _) => PathBuf::from(dir),
+        (None, Some(data), _) => PathBuf::from(data).join("work"),
+        (None, None, Some(home)) => PathBuf::from(home).join(".local/share/work"),
+        (None, None, None) => {
+            return Err(WorktreeError::InvalidPath("Cannot determine data directory (HOME is not set)".to_string()))
+        }
+    };
+    Ok(dir.join(REGISTRY_FILE))
+}
+
+impl Registry {
+    pub fn load() -> Result<Self> {
+        Self::load_from(&registry_file()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| WorktreeError::InvalidPath(format!("{} is corrupted: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&registry_file()?)
+    }
+
+    /// 先写临时文件再重命名，避免中断时留下半个文件
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content + "\n")?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 登记仓库并移到最前面；已登记且最近登记过时不做改动，返回是否有改动
+    pub fn register(&mut self, path: &Path, now: DateTime<Utc>) -> bool {
+        let index = self.repos.iter().position(|repo| repo.path == path);
+        if let Some(index) = index {
+            if index == 0 && now - self.repos[0].last_used < Duration::hours(TOUCH_INTERVAL_HOURS) {
+                return false;
+            }
+        }
+
+        let mut repo = match index {
+            Some(index) => self.repos.remove(index),
+            None => RegisteredRepo { path: path.to_path_buf(), name: repo_name(path), last_used: now },
+        };
+        repo.last_used = now;
+        self.repos.insert(0, repo);
+        true
+    }
+
+    /// 添加尚未登记的仓库（排在最后，不影响已有条目的顺序），返回是否为新仓库
+    pub fn add(&mut self, path: &Path, now: DateTime<Utc>) -> bool {
+        if self.repos.iter().any(|repo| repo.path == path) {
+            return false;
+        }
+        self.repos.push(RegisteredRepo { path: path.to_path_buf(), name: repo_name(path), last_used: now });
+        true
+    }
+
+    /// 移除已不存在的仓库，返回被移除的条目
+    pub fn prune(&mut self) -> Vec<RegisteredRepo> {
+        let (kept, removed) = std::mem::take(&mut self.repos).into_iter().partition(|repo| is_repository(&repo.path));
+        self.repos = kept;
+        removed
+    }
+
+    /// 所有仓库的 worktree 及其状态，同时返回已不存在的仓库；is_current 以调用时的当前目录为准
+    pub fn list_all(&self) -> Result<(Vec<GlobalWorktree>, Vec<&RegisteredRepo>)> {
+        let mut worktrees = Vec::new();
+        let mut missing = Vec::new();
+
+        for repo in &self.repos {
+            if !is_repository(&repo.path) {
+                missing.push(repo);
+                continue;
+            }
+            let Ok(list) = git_ops::in_repo(&repo.path, git_ops::list_worktrees) else {
+                missing.push(repo);
+                continue;
+            };
+            for worktree in list {
+                let path = Path::new(&worktree.path);
+                worktrees.push(GlobalWorktree {
+                    repo: repo.name.clone(),
+                    repo_path: repo.path.clone(),
+                    dirty: !worktree.is_bare && git_ops::has_uncommitted_changes(path).unwrap_or(false),
+                    committed_at: git_ops::head_commit_time(path),
+                    worktree,
+                });
+            }
+        }
+
+        Ok((worktrees, missing))
+    }
+}
+
+/// 在全局列表中查找 worktree：`<仓库>/<名称>` 只在该仓库中查找，否则在每个仓库中按目录名、别名和分支名查找（跳过裸仓库）
+pub fn find<'a>(worktrees: &'a [GlobalWorktree], name: &str) -> Vec<&'a GlobalWorktree> {
+    let mut repos: Vec<&Path> = worktrees.iter().map(|wt| wt.repo_path.as_path()).collect();
+    repos.dedup();
+
+    repos
+        .into_iter()
+        .filter_map(|repo| {
+            let in_repo: Vec<&GlobalWorktree> = worktrees.iter().filter(|wt| wt.repo_path == repo).collect();
+            let local: Vec<Worktree> = in_repo.iter().map(|wt| wt.worktree.clone()).collect();
+            let qualified = name
+                .strip_prefix(&in_repo[0].repo)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .and_then(|rest| Worktree::resolve(&local, rest).ok());
+            let resolved = qualified.or_else(|| Worktree::resolve(&local, name).ok())?;
+            in_repo.into_iter().find(|wt| wt.worktree.path == resolved.path && !wt.worktree.is_bare)
+        })
+        .collect()
+}
+
+/// 登记当前目录所在的仓库，失败只记录日志
+pub fn touch_current() {
+    let Ok(root) = git_ops::get_main_repository_root() else {
+        return;
+    };
+    let result = Registry::load().and_then(|mut registry| {
+        if registry.register(&root, Utc::now()) {
+            registry.save()?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to update repository registry: {}", e);
+    }
+}
+
+/// 在 dir 下查找 Git 仓库（含 `.git` 目录的目录或 `.bare` 布局的仓库目录），最多向下 depth 层；不进入仓库、隐藏目录和构建目录
+pub fn scan(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    scan_into(dir, depth, &mut found);
+    found.sort();
+    found
+}
+
+fn scan_into(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    if dir.join(".git").is_dir() || git_ops::is_bare_layout(dir) {
+        found.push(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        // 不跟随符号链接，避免循环
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            scan_into(&entry.path(), depth - 1, found);
+        }
+    }
+}
+
+fn is_repository(path: &Path) -> bool {
+    path.is_dir() && git_ops::is_inside_repository(path)
+}
+
+fn repo_name(path: &Path) -> String {
+    path.file_name().and_then(|n| n.to_str()).unwrap_or("repo").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_register_moves_to_front_and_throttles() {
+        let now = Utc::now();
+        let mut registry = Registry::default();
+        assert!(registry.register(Path::new("/code/api"), now));
+        assert!(registry.register(Path::new("/code/web"), now));
+        assert_eq!(registry.repos[0].name, "web");
+
+        // 已在最前面且刚登记过：不改动
+        assert!(!registry.register(Path::new("/code/web"), now + Duration::minutes(5)));
+        // 不在最前面：移到最前面
+        assert!(registry.register(Path::new("/code/api"), now + Duration::minutes(5)));
+        assert_eq!(registry.repos[0].name, "api");
+        assert_eq!(registry.repos.len(), 2);
+
+        // 扫描发现的仓库排在最后，已登记的不变
+        assert!(registry.add(Path::new("/code/infra"), now));
+        assert!(!registry.add(Path::new("/code/api"), now));
+        let names: Vec<&str> = registry.repos.iter().map(|repo| repo.name.as_str()).collect();
+        assert_eq!(names, ["api", "web", "infra"]);
+    }
+
+    fn global(repo: &str, dirname: &str, branch: &str) -> GlobalWorktree {
+        GlobalWorktree {
+            repo: repo.to_string(),
+            repo_path: PathBuf::from(format!("/code/{}", repo)),
+            worktree: Worktree::new(dirname.to_string(), branch.to_string(), format!("/code/{}.worktrees/{}", repo, dirname), false, false, false, None, None),
+            dirty: false,
+            committed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_find_across_repositories() {
+        let worktrees = vec![
+            global("api", "main", "main"),
+            global("api", "feat-x", "feat/x"),
+            global("web", "main", "main"),
+            global("web", "feat-x", "feat/x"),
+        ];
+        let names = |name: &str| -> Vec<String> { find(&worktrees, name).iter().map(|wt| wt.qualified_name()).collect() };
+
+        assert_eq!(names("feat-x"), ["api/feat-x", "web/feat-x"]);
+        assert_eq!(names("feat/x"), ["api/feat-x", "web/feat-x"]);
+        assert_eq!(names("web/feat-x"), ["web/feat-x"]);
+        assert_eq!(names("api/feat/x"), ["api/feat-x"]);
+        assert!(names("missing").is_empty());
+    }
+
+    #[test]
+    fn test_scan_finds_repositories() {
+        let temp = TempDir::new().unwrap();
+        for dir in ["a/.git", "b/nested/.git", "b/nested/inner/.git", "node_modules/pkg/.git", ".hidden/.git", "deep/1/2/3/.git"] {
+            std::fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        // 链接 worktree 的 .git 是文件，不算仓库
+        std::fs::create_dir_all(temp.path().join("a.worktrees/feat")).unwrap();
+        std::fs::write(temp.path().join("a.worktrees/feat/.git"), "gitdir: ../../a/.git/worktrees/feat\n").unwrap();
+
+        // `.bare` 布局的仓库目录
+        std::fs::create_dir_all(temp.path().join("c/.bare")).unwrap();
+        std::fs::write(temp.path().join("c/.git"), "gitdir: ./.bare\n").unwrap();
+
+        let root = temp.path().canonicalize().unwrap();
+        assert_eq!(scan(temp.path(), 3), vec![root.join("a"), root.join("b/nested"), root.join("c")]);
+        assert_eq!(scan(temp.path(), 4).len(), 4);
+    }
+
+    #[test]
+    fn test_prune_and_save_load() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("data").join(REGISTRY_FILE);
+        assert!(Registry::load_from(&file).unwrap().repos.is_empty());
+
+        let mut registry = Registry::default();
+        registry.register(Path::new("/nonexistent/moved-repo"), Utc::now());
+        registry.save_to(&file).unwrap();
+        assert_eq!(Registry::load_from(&file).unwrap().repos, registry.repos);
+
+        let removed = registry.prune();
+        assert_eq!(removed[0].name, "moved-repo");
+        assert!(registry.repos.is_empty());
+    }
+}
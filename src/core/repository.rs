@@ -9,12 +9,16 @@ pub struct Repository {
     /// 仓库根目录的绝对路径（`.git` 目录所在位置）
     pub root_path: PathBuf,
     /// 是否为裸仓库
+    #[allow(dead_code)]
     pub is_bare: bool,
     /// 关联的 worktree 数量
+    #[allow(dead_code)]
     pub worktree_count: usize,
     /// 默认分支名
+    #[allow(dead_code)]
     pub default_branch: String,
     /// 当前 worktree 名称（基于 shell cwd）
+    #[allow(dead_code)]
     pub current_worktree: Option<String>,
 }
 
@@ -22,22 +26,32 @@ impl Repository {
     /// 从路径打开 Git 仓库并返回 Repository 信息
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
+        let path_str = path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?;
+        let git = |args: &[&str]| -> Result<Option<String>> {
+            let output = Command::new("git")
+                .args(["-C", path_str])
+                .args(args)
+                .output()
+                .map_err(WorktreeError::git_spawn)?;
+            Ok(output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+        };
+
+        // 尝试获取仓库根目录；裸仓库没有工作目录，`<仓库>/.bare` 布局中为仓库目录，否则为裸仓库本身
+        let root_path = match git(&["rev-parse", "--show-toplevel"])? {
+            Some(toplevel) => PathBuf::from(toplevel),
+            None if git(&["rev-parse", "--is-bare-repository"])?.as_deref() == Some("true") => {
+                if git_ops::is_bare_layout(path) {
+                    path.canonicalize()?
+                } else {
+                    let git_dir = git(&["rev-parse", "--absolute-git-dir"])?.unwrap_or_else(|| path_str.to_string());
+                    PathBuf::from(git_dir)
+                }
+            }
+            None => return Err(WorktreeError::NotGitRepository(path.to_path_buf())),
+        };
 
-        // 尝试获取仓库根目录
-        let output = Command::new("git")
-            .args(["-C", path.to_str().ok_or_else(|| WorktreeError::InvalidPath(path.to_string_lossy().to_string()))?,
-                   "rev-parse", "--show-toplevel"])
-            .output()
-            .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
-
-        if !output.status.success() {
-            return Err(WorktreeError::NotGitRepository(path.to_path_buf()));
-        }
-
-        let root_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
-
-        // 检查是否为裸仓库
-        let is_bare = git_ops::is_bare_repository().unwrap_or(false);
+        // 检查是否为裸仓库（`<仓库>/.bare` 布局的 worktree 中也为真）
+        let is_bare = git(&["config", "--bool", "core.bare"])?.as_deref() == Some("true");
 
         // 获取 worktree 数量
         let worktree_count = Self::count_worktrees(&root_path)?;
@@ -63,7 +77,7 @@ impl Repository {
             .args(["-C", root_path.to_str().ok_or_else(|| WorktreeError::InvalidPath(root_path.to_string_lossy().to_string()))?,
                    "worktree", "list"])
             .output()
-            .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+            .map_err(WorktreeError::git_spawn)?;
 
         if !output.status.success() {
             return Ok(0);
@@ -75,27 +89,28 @@ impl Repository {
     }
 
     /// 检测仓库的默认分支名
-    fn detect_default_branch(root_path: &Path) -> Result<String> {
+    ///
+    /// 依次尝试远程的 `origin/HEAD`、常见的默认分支名和当前 HEAD 所在分支
+    pub fn detect_default_branch(root_path: &Path) -> Result<String> {
         let root_path_str = root_path.to_str().ok_or_else(|| WorktreeError::InvalidPath(root_path.to_string_lossy().to_string()))?;
 
-        // 尝试获取 HEAD 引用
-        let output = Command::new("git")
-            .args(["-C", root_path_str, "symbolic-ref", "--short", "HEAD"])
-            .output();
-
-        match output {
-            Ok(out) if out.status.success() => {
-                let branch = String::from_utf8_lossy(&out.stdout).trim().to_string();
-                if !branch.is_empty() {
-                    return Ok(branch);
-                }
-            }
-            _ => {
-                // HEAD 可能是分离的或仓库为空
+        let symbolic_ref = |reference: &str| -> Option<String> {
+            let out = Command::new("git")
+                .args(["-C", root_path_str, "symbolic-ref", "--quiet", "--short", reference])
+                .output()
+                .ok()?;
+            let branch = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            (out.status.success() && !branch.is_empty()).then_some(branch)
+        };
+
+        // 克隆的仓库中 origin/HEAD 指向远程的默认分支
+        if let Some(remote_head) = symbolic_ref("refs/remotes/origin/HEAD") {
+            if let Some(branch) = remote_head.strip_prefix("origin/") {
+                return Ok(branch.to_string());
             }
         }
 
-        // 如果 HEAD 是分离的，尝试查找常见的默认分支名
+        // 查找常见的默认分支名
         let common_defaults = ["main", "master", "develop"];
         for branch_name in common_defaults {
             // 检查分支是否存在
@@ -108,7 +123,8 @@ impl Repository {
             }
         }
 
-        Ok("main".to_string())
+        // 都不存在时使用当前分支（HEAD 可能是分离的或仓库为空）
+        Ok(symbolic_ref("HEAD").unwrap_or_else(|| "main".to_string()))
     }
 
     /// 检测当前 worktree（基于当前工作目录）
@@ -122,7 +138,7 @@ impl Repository {
         let output = Command::new("git")
             .args(["rev-parse", "--show-toplevel"])
             .output()
-            .map_err(|e| WorktreeError::GitError(format!("Failed to execute git: {}", e)))?;
+            .map_err(WorktreeError::git_spawn)?;
 
         if output.status.success() {
             let worktree_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
@@ -143,6 +159,7 @@ impl Repository {
     }
 
     /// 获取仓库信息（作为辅助函数，主要用于 CLI 显示）
+    #[allow(dead_code)]
     pub fn get_repository_info(&self) -> Self {
         self.clone()
     }
@@ -155,6 +172,7 @@ mod tests {
     use tempfile::TempDir;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_repository_from_path() {
         // 创建临时目录
         let temp_dir = TempDir::new().unwrap();
@@ -175,6 +193,28 @@ mod tests {
         assert_eq!(repo.worktree_count, 1); // main worktree
     }
 
+    #[test]
+    fn test_repository_from_bare_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        fs::create_dir(&origin).unwrap();
+        Command::new("git").args(["init", "--quiet"]).current_dir(&origin).output().unwrap();
+        Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "--allow-empty", "-m", "init"])
+            .current_dir(&origin)
+            .output()
+            .unwrap();
+
+        let cloned = crate::core::clone::clone(origin.to_str().unwrap(), &temp_dir.path().join("project")).unwrap();
+
+        // 仓库目录和其中的 worktree 都识别为裸仓库
+        let repository = Repository::from_path(&cloned.root).unwrap();
+        assert!(repository.is_bare);
+        assert_eq!(repository.root_path, cloned.root);
+        assert_eq!(repository.worktree_count, 2);
+        assert!(Repository::from_path(cloned.worktree_path.unwrap()).unwrap().is_bare);
+    }
+
     #[test]
     fn test_repository_from_invalid_path() {
         // 创建临时目录（不是 Git 仓库）
@@ -187,6 +227,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_repository_with_initial_commit() {
         // 创建临时目录
         let temp_dir = TempDir::new().unwrap();
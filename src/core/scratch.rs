@@ -0,0 +1,66 @@
+// 临时 worktree：在指定提交上创建分离 HEAD 的 worktree，过期后可批量清理
+
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+use chrono::Duration;
+
+/// 临时 worktree 的默认有效期
+pub const DEFAULT_TTL: &str = "7d";
+
+/// 解析有效期，如 `30m`、`12h`、`7d`、`2w`
+pub fn parse_ttl(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let invalid = || WorktreeError::InvalidArgument(tf("error.detail.invalid_ttl", &[("value", value)]));
+
+    let unit_index = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (number, unit) = value.split_at(unit_index);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+
+    let duration = match unit {
+        "m" => Duration::try_minutes(number),
+        "h" => Duration::try_hours(number),
+        "d" => Duration::try_days(number),
+        "w" => Duration::try_weeks(number),
+        _ => None,
+    };
+    duration.filter(|d| *d > Duration::zero()).ok_or_else(invalid)
+}
+
+/// 生成临时 worktree 的目录名：`scratch-<短 SHA>`，重名时追加序号
+pub fn scratch_name(short_sha: &str, existing: &[Worktree]) -> String {
+    let base = format!("scratch-{}", short_sha);
+    let taken = |name: &str| existing.iter().any(|wt| wt.dirname == name);
+
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|name| !taken(name))
+        .expect("unbounded sequence")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ttl() {
+        assert_eq!(parse_ttl("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_ttl("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_ttl("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_ttl("2w").unwrap(), Duration::weeks(2));
+        for invalid in ["", "7", "d", "0d", "7y", "-1d"] {
+            assert!(parse_ttl(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_scratch_name_avoids_existing() {
+        let wt = |name: &str| Worktree::new(name.into(), "HEAD".into(), format!("/w/{}", name), false, false, true, None, None);
+        assert_eq!(scratch_name("abc123", &[]), "scratch-abc123");
+        let existing = vec![wt("scratch-abc123"), wt("scratch-abc123-2")];
+        assert_eq!(scratch_name("abc123", &existing), "scratch-abc123-3");
+    }
+}
@@ -0,0 +1,270 @@
+// 终端复用器会话：每个 worktree 对应一个以 dirname 命名、以 worktree 路径为工作目录的会话
+//
+// 布局来自多值配置项 `work.session.window`，每个值描述一个窗口：
+// `名称: 命令1 | 命令2`，`|` 分隔同一窗口中的多个窗格，命令可以为空。
+
+use crate::core::config;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::tf;
+use std::process::{Command, Stdio};
+
+/// 支持的终端复用器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Zellij,
+}
+
+impl Multiplexer {
+    /// 解析复用器名称
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "tmux" => Some(Multiplexer::Tmux),
+            "zellij" => Some(Multiplexer::Zellij),
+            _ => None,
+        }
+    }
+
+    /// 配置项 `work.session.multiplexer` 指定的复用器，默认 tmux
+    pub fn from_config() -> Result<Self> {
+        match config::get("session.multiplexer") {
+            Some(value) => Multiplexer::parse(&value).ok_or_else(|| {
+                WorktreeError::InvalidArgument(tf("error.detail.unknown_multiplexer", &[("value", &value)]))
+            }),
+            None => Ok(Multiplexer::Tmux),
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => "tmux",
+            Multiplexer::Zellij => "zellij",
+        }
+    }
+}
+
+/// 布局中的一个窗口（zellij 中对应标签页）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+    pub name: String,
+    /// 每个窗格的启动命令，None 表示只打开 shell
+    pub panes: Vec<Option<String>>,
+}
+
+impl Window {
+    /// 解析 `名称: 命令1 | 命令2`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (name, commands) = match value.split_once(':') {
+            Some((name, commands)) => (name.trim(), commands),
+            None => (value.trim(), ""),
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        let panes = commands
+            .split('|')
+            .map(|c| Some(c.trim().to_string()).filter(|c| !c.is_empty()))
+            .collect();
+
+        Some(Window { name: name.to_string(), panes })
+    }
+}
+
+/// 从配置读取会话布局，无效的条目会被忽略
+pub fn layout_from_config() -> Vec<Window> {
+    config::get_all("session.window")
+        .iter()
+        .filter_map(|value| Window::parse(value))
+        .collect()
+}
+
+/// 会话名称：tmux 不允许名称中出现 `.` 和 `:`
+pub fn session_name(dirname: &str) -> String {
+    dirname.replace(['.', ':'], "_")
+}
+
+/// 会话是否已存在（复用器未安装时视为不存在）
+pub fn exists(mux: Multiplexer, name: &str) -> bool {
+    match mux {
+        Multiplexer::Tmux => Command::new("tmux")
+            .args(["has-session", "-t", &format!("={}", name)])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success()),
+        Multiplexer::Zellij => Command::new("zellij")
+            .args(["list-sessions", "--short", "--no-formatting"])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).lines().any(|l| l.trim() == name)),
+    }
+}
+
+/// 以分离状态创建 tmux 会话并按布局建立窗口和窗格
+pub fn create_tmux(name: &str, path: &str, layout: &[Window]) -> Result<()> {
+    let first_window = tmux_output(&["new-session", "-d", "-P", "-F", "#{window_id}", "-s", name, "-c", path])?;
+
+    for (index, window) in layout.iter().enumerate() {
+        let window_id = if index == 0 {
+            tmux_output(&["rename-window", "-t", &first_window, &window.name])?;
+            first_window.clone()
+        } else {
+            tmux_output(&["new-window", "-d", "-P", "-F", "#{window_id}", "-t", &format!("{}:", name), "-n", &window.name, "-c", path])?
+        };
+
+        for (pane_index, command) in window.panes.iter().enumerate() {
+            let pane = if pane_index == 0 {
+                window_id.clone()
+            } else {
+                tmux_output(&["split-window", "-d", "-P", "-F", "#{pane_id}", "-t", &window_id, "-c", path])?
+            };
+            if let Some(command) = command {
+                tmux_output(&["send-keys", "-t", &pane, command, "Enter"])?;
+            }
+        }
+
+        if window.panes.len() > 1 {
+            tmux_output(&["select-layout", "-t", &window_id, "tiled"])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 生成 zellij 的 KDL 布局
+pub fn zellij_layout(path: &str, layout: &[Window]) -> String {
+    let mut kdl = format!("layout {{\n    cwd {}\n", kdl_string(path));
+
+    for window in layout {
+        kdl.push_str(&format!("    tab name={} {{\n", kdl_string(&window.name)));
+        for command in &window.panes {
+            match command {
+                Some(command) => kdl.push_str(&format!(
+                    "        pane command=\"sh\" {{\n            args \"-c\" {}\n        }}\n",
+                    kdl_string(command)
+                )),
+                None => kdl.push_str("        pane\n"),
+            }
+        }
+        kdl.push_str("    }\n");
+    }
+
+    kdl.push_str("}\n");
+    kdl
+}
+
+fn kdl_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 连接到会话，不存在时创建；会阻塞直到用户离开会话
+pub fn attach_or_create(mux: Multiplexer, name: &str, path: &str, layout: &[Window]) -> Result<bool> {
+    let created = !exists(mux, name);
+
+    let status = match mux {
+        Multiplexer::Tmux => {
+            if created {
+                create_tmux(name, path, layout)?;
+            }
+            // 已在 tmux 中时切换客户端，避免嵌套会话
+            let verb = if std::env::var_os("TMUX").is_some() { "switch-client" } else { "attach-session" };
+            Command::new("tmux").args([verb, "-t", &format!("={}", name)]).status()
+        }
+        Multiplexer::Zellij if created && !layout.is_empty() => {
+            let layout_path = std::env::temp_dir().join(format!("work-{}.kdl", name));
+            std::fs::write(&layout_path, zellij_layout(path, layout))?;
+            let layout_arg = layout_path.to_string_lossy().to_string();
+            Command::new("zellij")
+                .args(["--session", name, "--layout", &layout_arg])
+                .current_dir(path)
+                .status()
+        }
+        Multiplexer::Zellij => Command::new("zellij")
+            .args(["attach", "--create", name])
+            .current_dir(path)
+            .status(),
+    };
+
+    match status {
+        Ok(status) if status.success() => Ok(created),
+        Ok(status) => Err(WorktreeError::SessionFailed(tf(
+            "error.detail.exited_with",
+            &[("program", mux.program()), ("status", &status.to_string())],
+        ))),
+        Err(e) => Err(spawn_error(mux, e)),
+    }
+}
+
+/// 结束会话
+pub fn kill(mux: Multiplexer, name: &str) -> Result<()> {
+    let output = match mux {
+        Multiplexer::Tmux => Command::new("tmux").args(["kill-session", "-t", &format!("={}", name)]).output(),
+        Multiplexer::Zellij => Command::new("zellij").args(["kill-session", name]).output(),
+    }
+    .map_err(|e| spawn_error(mux, e))?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::SessionFailed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+fn tmux_output(args: &[&str]) -> Result<String> {
+    let output = Command::new("tmux").args(args).output().map_err(|e| spawn_error(Multiplexer::Tmux, e))?;
+
+    if !output.status.success() {
+        return Err(WorktreeError::SessionFailed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn spawn_error(mux: Multiplexer, e: std::io::Error) -> WorktreeError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        WorktreeError::SessionFailed(tf("error.detail.program_not_found", &[("program", mux.program())]))
+    } else {
+        WorktreeError::SessionFailed(format!("{}: {}", mux.program(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(
+            Window::parse("dev: npm run dev | npm test"),
+            Some(Window {
+                name: "dev".to_string(),
+                panes: vec![Some("npm run dev".to_string()), Some("npm test".to_string())],
+            })
+        );
+        assert_eq!(Window::parse("shell"), Some(Window { name: "shell".to_string(), panes: vec![None] }));
+        assert_eq!(Window::parse("logs: | tail -f log").unwrap().panes, vec![None, Some("tail -f log".to_string())]);
+        assert_eq!(Window::parse(": vim"), None);
+    }
+
+    #[test]
+    fn test_session_name() {
+        assert_eq!(session_name("feat-x"), "feat-x");
+        assert_eq!(session_name("v1.2:rc"), "v1_2_rc");
+    }
+
+    #[test]
+    fn test_parse_multiplexer() {
+        assert_eq!(Multiplexer::parse("TMUX"), Some(Multiplexer::Tmux));
+        assert_eq!(Multiplexer::parse("zellij"), Some(Multiplexer::Zellij));
+        assert_eq!(Multiplexer::parse("screen"), None);
+    }
+
+    #[test]
+    fn test_zellij_layout() {
+        let layout = vec![Window::parse("dev: echo \"hi\" | ").unwrap()];
+        let kdl = zellij_layout("/src/repo", &layout);
+
+        assert!(kdl.contains("cwd \"/src/repo\""));
+        assert!(kdl.contains("tab name=\"dev\""));
+        assert!(kdl.contains("args \"-c\" \"echo \\\"hi\\\"\""));
+        assert!(kdl.contains("        pane\n"));
+    }
+}
@@ -0,0 +1,417 @@
+// worktree 快照：把 HEAD、暂存区、工作区更改和未跟踪文件保存为一个 tar+zstd 归档
+//
+// 归档内容：
+// - manifest.json   快照信息（见 Manifest）
+// - index.patch     暂存区相对 HEAD 的更改
+// - worktree.patch  工作区相对暂存区的更改
+// - head.bundle     不在任何远程分支上的提交（git bundle），全部已推送或仓库没有远程时省略
+// - untracked/      未跟踪文件（不含被忽略的文件）
+
+use crate::core::git_ops;
+use crate::core::metadata;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// 快照格式版本
+pub const SNAPSHOT_VERSION: u32 = 1;
+/// 快照文件扩展名
+pub const EXTENSION: &str = "tar.zst";
+
+const MANIFEST: &str = "manifest.json";
+const INDEX_PATCH: &str = "index.patch";
+const WORKTREE_PATCH: &str = "worktree.patch";
+const BUNDLE: &str = "head.bundle";
+const UNTRACKED_DIR: &str = "untracked";
+
+/// 快照信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub dirname: String,
+    /// 分离 HEAD 时为 None
+    pub branch: Option<String>,
+    /// HEAD 提交的完整 SHA
+    pub head: String,
+    pub created_at: DateTime<Utc>,
+    /// 快照中的未跟踪文件
+    #[serde(default)]
+    pub untracked: Vec<String>,
+}
+
+/// 恢复结果
+#[derive(Debug)]
+pub struct Restored {
+    pub manifest: Manifest,
+    /// 原分支已移动或已被其他 worktree 检出，因此以分离 HEAD 恢复
+    pub detached_fallback: bool,
+}
+
+/// 快照的默认保存目录 `<git common dir>/work/snapshots`
+pub fn snapshots_dir() -> Result<PathBuf> {
+    let dir = metadata::data_dir()?.join("snapshots");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 快照的默认文件路径：`<snapshots>/<dirname>-<时间>.tar.zst`
+pub fn default_archive_path(dirname: &str, now: DateTime<Utc>) -> Result<PathBuf> {
+    Ok(snapshots_dir()?.join(format!("{}-{}.{}", dirname, now.format("%Y%m%d-%H%M%S"), EXTENSION)))
+}
+
+/// 查找归档：优先按路径，其次在默认快照目录中按文件名查找
+pub fn resolve_archive(value: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(value);
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    let dir = metadata::data_dir()?.join("snapshots");
+    [value.to_string(), format!("{}.{}", value, EXTENSION)]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| WorktreeError::NotFound(value.to_string()))
+}
+
+/// 创建快照归档
+pub fn create(worktree: &Worktree, output: &Path) -> Result<Manifest> {
+    let wt_path = Path::new(&worktree.path);
+
+    let head = String::from_utf8_lossy(&git_ops::run_git_bytes_in(wt_path, &["rev-parse", "HEAD"])?)
+        .trim()
+        .to_string();
+    let index_patch = git_ops::run_git_bytes_in(wt_path, &["diff", "--cached", "--binary", "HEAD"])?;
+    let worktree_patch = git_ops::run_git_bytes_in(wt_path, &["diff", "--binary"])?;
+    let untracked: Vec<String> = git_ops::run_git_bytes_in(wt_path, &["ls-files", "--others", "--exclude-standard", "-z"])?
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect();
+
+    let manifest = Manifest {
+        version: SNAPSHOT_VERSION,
+        dirname: worktree.dirname.clone(),
+        branch: (!worktree.is_detached).then(|| worktree.branch_name.clone()),
+        head,
+        created_at: Utc::now(),
+        untracked,
+    };
+
+    let bundle = create_bundle(wt_path)?;
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let encoder = zstd::Encoder::new(File::create(output)?, 0)?.auto_finish();
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+    append_bytes(&mut builder, MANIFEST, &manifest_json)?;
+    append_bytes(&mut builder, INDEX_PATCH, &index_patch)?;
+    append_bytes(&mut builder, WORKTREE_PATCH, &worktree_patch)?;
+    if let Some(bundle) = &bundle {
+        append_bytes(&mut builder, BUNDLE, bundle)?;
+    }
+    for file in &manifest.untracked {
+        builder.append_path_with_name(wt_path.join(file), Path::new(UNTRACKED_DIR).join(file))?;
+    }
+    builder.into_inner()?;
+
+    Ok(manifest)
+}
+
+/// 打包不在任何远程分支上的提交，全部已推送时返回 None
+///
+/// 仓库没有远程时同样返回 None：此时提交只存在于本仓库中，而快照也只能在本仓库中恢复，
+/// 打包只会把整个历史复制一遍。
+fn create_bundle(wt_path: &Path) -> Result<Option<Vec<u8>>> {
+    if git_ops::run_git_bytes_in(wt_path, &["remote"])?.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+
+    // 先确认有需要打包的提交（git 拒绝创建空 bundle）
+    let count = git_ops::run_git_bytes_in(wt_path, &["rev-list", "--count", "HEAD", "--not", "--remotes"])?;
+    if String::from_utf8_lossy(&count).trim() == "0" {
+        return Ok(None);
+    }
+
+    let file = scratch_path("bundle");
+    let file_str = file.to_string_lossy().to_string();
+    let result = git_ops::run_git_bytes_in(wt_path, &["bundle", "create", "--quiet", &file_str, "HEAD", "--not", "--remotes"])
+        .and_then(|_| Ok(std::fs::read(&file)?));
+    let _ = std::fs::remove_file(&file);
+    result.map(Some)
+}
+
+fn append_bytes<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// 临时文件或目录路径（同一进程中可能多次调用）
+fn scratch_path(kind: &str) -> PathBuf {
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let sequence = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    std::env::temp_dir().join(format!("work-snapshot-{}-{}-{}", std::process::id(), sequence, kind))
+}
+
+/// 读取归档中的快照信息
+pub fn read_manifest(archive: &Path) -> Result<Manifest> {
+    let decoder = zstd::Decoder::new(File::open(archive)?)?;
+    let mut entries = tar::Archive::new(decoder);
+
+    for entry in entries.entries()? {
+        let entry = entry?;
+        if entry.path()?.as_ref() == Path::new(MANIFEST) {
+            return parse_manifest(entry);
+        }
+    }
+    Err(invalid_archive(archive, &t("error.detail.missing_manifest")))
+}
+
+fn parse_manifest(reader: impl std::io::Read) -> Result<Manifest> {
+    let manifest: Manifest = serde_json::from_reader(reader)
+        .map_err(|e| WorktreeError::InvalidArgument(tf("error.detail.invalid_manifest", &[("error", &e.to_string())])))?;
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(WorktreeError::InvalidArgument(tf(
+            "error.detail.unsupported_snapshot",
+            &[("version", &manifest.version.to_string()), ("supported", &SNAPSHOT_VERSION.to_string())],
+        )));
+    }
+    Ok(manifest)
+}
+
+fn invalid_archive(archive: &Path, reason: &str) -> WorktreeError {
+    WorktreeError::InvalidArgument(tf(
+        "error.detail.not_a_snapshot",
+        &[("path", &archive.display().to_string()), ("reason", reason)],
+    ))
+}
+
+/// 从归档恢复 worktree 到 target_path
+///
+/// repo_path 是仓库中任意一个 worktree 的路径，worktrees 用于判断分支是否已被检出。
+pub fn restore(archive: &Path, repo_path: &Path, target_path: &str, worktrees: &[Worktree]) -> Result<Restored> {
+    let extract_dir = scratch_path("extract");
+    let result = (|| {
+        tar::Archive::new(zstd::Decoder::new(File::open(archive)?)?).unpack(&extract_dir)?;
+        restore_from_dir(&extract_dir, archive, repo_path, target_path, worktrees)
+    })();
+    let _ = std::fs::remove_dir_all(&extract_dir);
+    result
+}
+
+fn restore_from_dir(dir: &Path, archive: &Path, repo_path: &Path, target_path: &str, worktrees: &[Worktree]) -> Result<Restored> {
+    let manifest_file = File::open(dir.join(MANIFEST)).map_err(|_| invalid_archive(archive, &t("error.detail.missing_manifest")))?;
+    let manifest = parse_manifest(manifest_file)?;
+    let head = manifest.head.as_str();
+
+    // 提交不在当前仓库中时从 bundle 导入
+    if !commit_exists(repo_path, head) {
+        let bundle = dir.join(BUNDLE);
+        if bundle.is_file() {
+            let bundle_str = bundle.to_string_lossy().to_string();
+            git_ops::run_git_bytes_in(repo_path, &["fetch", "--quiet", &bundle_str, "HEAD"])?;
+        }
+        if !commit_exists(repo_path, head) {
+            return Err(WorktreeError::RevisionNotFound(head.to_string()));
+        }
+    }
+
+    let mut detached_fallback = false;
+    let add_args: Vec<&str> = match manifest.branch.as_deref() {
+        Some(branch) if !git_ops::branch_exists_in(repo_path, branch) => {
+            vec!["worktree", "add", "--quiet", "-b", branch, target_path, head]
+        }
+        Some(branch)
+            if branch_tip(repo_path, branch).as_deref() == Some(head)
+                && !worktrees.iter().any(|wt| !wt.is_detached && wt.branch_name == branch) =>
+        {
+            vec!["worktree", "add", "--quiet", target_path, branch]
+        }
+        branch => {
+            detached_fallback = branch.is_some();
+            vec!["worktree", "add", "--quiet", "--detach", target_path, head]
+        }
+    };
+    git_ops::run_git_bytes_in(repo_path, &add_args)?;
+
+    let target = Path::new(target_path);
+    let index_patch = std::fs::read(dir.join(INDEX_PATCH))?;
+    if !index_patch.is_empty() {
+        git_ops::apply_patch_to_index(target, &index_patch)?;
+    }
+    let worktree_patch = std::fs::read(dir.join(WORKTREE_PATCH))?;
+    if !worktree_patch.is_empty() {
+        git_ops::apply_patch(target, &worktree_patch, false)?;
+    }
+
+    let untracked_dir = dir.join(UNTRACKED_DIR);
+    if untracked_dir.is_dir() {
+        copy_tree(&untracked_dir, target)?;
+    }
+
+    Ok(Restored { manifest, detached_fallback })
+}
+
+fn commit_exists(repo_path: &Path, sha: &str) -> bool {
+    git_ops::run_git_bytes_in(repo_path, &["cat-file", "-e", &format!("{}^{{commit}}", sha)]).is_ok()
+}
+
+fn branch_tip(repo_path: &Path, branch: &str) -> Option<String> {
+    git_ops::run_git_bytes_in(repo_path, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)])
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out).trim().to_string())
+}
+
+/// 递归复制目录内容（保留符号链接）
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_tree(&source, &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else {
+            std::fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> Result<()> {
+    std::fs::copy(source, target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim_end().to_string()
+    }
+
+    fn worktree(dirname: &str, branch: &str, path: &Path) -> Worktree {
+        let path = path.to_string_lossy().to_string();
+        Worktree::new(dirname.to_string(), branch.to_string(), path, false, false, false, None, None)
+    }
+
+    fn setup() -> (TempDir, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test User"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("file.txt"), "one\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+        (temp, repo)
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let (temp, repo) = setup();
+        let feature = temp.path().join("feature");
+        git(&repo, &["worktree", "add", "-q", "-b", "feature", feature.to_str().unwrap()]);
+
+        // 暂存的更改、暂存后又修改、未跟踪的二进制文件
+        std::fs::write(feature.join("file.txt"), "two\n").unwrap();
+        git(&feature, &["add", "file.txt"]);
+        std::fs::write(feature.join("file.txt"), "three\n").unwrap();
+        std::fs::create_dir(feature.join("data")).unwrap();
+        std::fs::write(feature.join("data/blob.bin"), [0u8, 255, 1, 2]).unwrap();
+        let status_before = git(&feature, &["status", "--porcelain"]);
+
+        let archive = temp.path().join("feature.tar.zst");
+        let manifest = create(&worktree("feature", "feature", &feature), &archive).unwrap();
+        assert_eq!(manifest.branch.as_deref(), Some("feature"));
+        assert_eq!(manifest.untracked, vec!["data/blob.bin"]);
+        assert_eq!(read_manifest(&archive).unwrap().head, manifest.head);
+
+        // 删除原 worktree 和分支后恢复
+        git(&repo, &["worktree", "remove", "--force", feature.to_str().unwrap()]);
+        git(&repo, &["branch", "-q", "-D", "feature"]);
+
+        let restored_path = temp.path().join("restored");
+        let restored = restore(&archive, &repo, restored_path.to_str().unwrap(), &[]).unwrap();
+        assert!(!restored.detached_fallback);
+
+        assert_eq!(git(&restored_path, &["branch", "--show-current"]), "feature");
+        assert_eq!(git(&restored_path, &["status", "--porcelain"]), status_before);
+        assert!(git(&restored_path, &["diff", "--cached"]).contains("+two"));
+        assert_eq!(std::fs::read(restored_path.join("data/blob.bin")).unwrap(), vec![0u8, 255, 1, 2]);
+    }
+
+    #[test]
+    fn test_restore_detached_when_branch_checked_out() {
+        let (temp, repo) = setup();
+        std::fs::write(repo.join("file.txt"), "changed\n").unwrap();
+
+        let archive = temp.path().join("main.tar.zst");
+        let main = worktree("repo", "main", &repo);
+        create(&main, &archive).unwrap();
+
+        let restored_path = temp.path().join("copy");
+        let restored = restore(&archive, &repo, restored_path.to_str().unwrap(), &[main]).unwrap();
+        assert!(restored.detached_fallback);
+        assert_eq!(std::fs::read_to_string(restored_path.join("file.txt")).unwrap(), "changed\n");
+    }
+
+    #[test]
+    fn test_bundle_only_unpushed_commits() {
+        let (temp, repo) = setup();
+        // 没有远程时不打包
+        assert!(create_bundle(&repo).unwrap().is_none());
+
+        let clone = temp.path().join("clone");
+        git(temp.path(), &["clone", "-q", repo.to_str().unwrap(), clone.to_str().unwrap()]);
+        // 全部已推送
+        assert!(create_bundle(&clone).unwrap().is_none());
+
+        std::fs::write(clone.join("file.txt"), "local\n").unwrap();
+        git(&clone, &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "-q", "-am", "local"]);
+        let bundle = create_bundle(&clone).unwrap().unwrap();
+        let file = temp.path().join("head.bundle");
+        std::fs::write(&file, bundle).unwrap();
+        // 只包含本地提交，以已推送的提交为前提：原仓库可以直接导入
+        git(&repo, &["fetch", "-q", file.to_str().unwrap(), "HEAD"]);
+        assert!(commit_exists(&repo, &git(&clone, &["rev-parse", "HEAD"])));
+    }
+
+    #[test]
+    fn test_read_manifest_rejects_other_files() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("not-a-snapshot.tar.zst");
+        std::fs::write(&file, b"hello").unwrap();
+        assert!(read_manifest(&file).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+// 回收站：删除 worktree 前把它的完整状态保存为快照（见 snapshot 模块），
+// 超过保留期限（配置项 `work.trash.retention`，默认 14 天）的条目会在下次删除时清除。
+//
+// 快照保存在 `<git common dir>/work/trash/<id>.tar.zst`，条目列表保存在同目录的 index.json。
+
+use crate::core::config;
+use crate::core::git_ops;
+use crate::core::metadata;
+use crate::core::scratch;
+use crate::core::snapshot;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 回收站条目的默认保留期限
+pub const DEFAULT_RETENTION: &str = "14d";
+
+const INDEX_FILE: &str = "index.json";
+
+/// 回收站中的一个 worktree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub dirname: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub head: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    pub fn expires_at(&self, retention: Duration) -> DateTime<Utc> {
+        self.deleted_at + retention
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrashIndex {
+    #[serde(default)]
+    entries: Vec<TrashEntry>,
+}
+
+/// 配置的保留期限
+pub fn retention() -> Result<Duration> {
+    scratch::parse_ttl(&config::get("trash.retention").unwrap_or_else(|| DEFAULT_RETENTION.to_string()))
+}
+
+fn trash_dir() -> Result<PathBuf> {
+    let dir = metadata::data_dir()?.join("trash");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 条目对应的快照文件
+pub fn archive_path(id: &str) -> Result<PathBuf> {
+    Ok(trash_dir()?.join(format!("{}.{}", id, snapshot::EXTENSION)))
+}
+
+/// 回收站中的全部条目（按删除时间顺序）
+pub fn list() -> Result<Vec<TrashEntry>> {
+    // 只读时不创建数据目录
+    Ok(load_from(&git_ops::get_git_common_dir()?.join("work").join("trash").join(INDEX_FILE))?.entries)
+}
+
+fn load_from(path: &Path) -> Result<TrashIndex> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| WorktreeError::InvalidPath(format!("{} is corrupted: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TrashIndex::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save(entries: Vec<TrashEntry>) -> Result<()> {
+    let path = trash_dir()?.join(INDEX_FILE);
+    let content = serde_json::to_string_pretty(&TrashIndex { entries })
+        .map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content + "\n")?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// 按 id 查找条目
+pub fn find(id: &str) -> Result<TrashEntry> {
+    list()?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| WorktreeError::NotFound(id.to_string()))
+}
+
+/// 把 worktree 的状态保存到回收站
+pub fn put(worktree: &Worktree) -> Result<TrashEntry> {
+    let mut entries = list()?;
+    let now = Utc::now();
+
+    let base = format!("{}-{}", worktree.dirname, now.format("%Y%m%d-%H%M%S"));
+    let id = std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{}-{}", base, n)))
+        .find(|id| !entries.iter().any(|entry| entry.id == *id))
+        .expect("unbounded sequence");
+
+    let manifest = snapshot::create(worktree, &archive_path(&id)?)?;
+    let entry = TrashEntry {
+        id,
+        dirname: worktree.dirname.clone(),
+        path: worktree.path.clone(),
+        branch: manifest.branch,
+        head: manifest.head,
+        deleted_at: now,
+    };
+    entries.push(entry.clone());
+    save(entries)?;
+
+    Ok(entry)
+}
+
+/// 从回收站移除条目及其快照
+pub fn remove(id: &str) -> Result<TrashEntry> {
+    let mut entries = list()?;
+    let index = entries
+        .iter()
+        .position(|entry| entry.id == id)
+        .ok_or_else(|| WorktreeError::NotFound(id.to_string()))?;
+    let entry = entries.remove(index);
+
+    remove_archive(&entry.id)?;
+    save(entries)?;
+    Ok(entry)
+}
+
+/// 清除删除时间早于 before 的条目，before 为 None 时清空回收站
+pub fn purge(before: Option<DateTime<Utc>>) -> Result<Vec<TrashEntry>> {
+    let (removed, kept): (Vec<_>, Vec<_>) = list()?
+        .into_iter()
+        .partition(|entry| before.is_none_or(|before| entry.deleted_at < before));
+
+    if removed.is_empty() {
+        return Ok(removed);
+    }
+    for entry in &removed {
+        remove_archive(&entry.id)?;
+    }
+    save(kept)?;
+    Ok(removed)
+}
+
+fn remove_archive(id: &str) -> Result<()> {
+    match std::fs::remove_file(archive_path(id)?) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// 删除 worktree：use_trash 为 true 时先保存到回收站（目录已不存在时跳过），
+/// 删除失败时撤回回收站条目
+pub fn delete(worktree: &Worktree, force: bool, use_trash: bool) -> Result<Option<TrashEntry>> {
+    let entry = if use_trash && Path::new(&worktree.path).is_dir() {
+        // 顺便清除过期条目，回收站不会无限增长
+        purge(Some(Utc::now() - retention()?))?;
+        Some(put(worktree)?)
+    } else {
+        None
+    };
+
+    if let Err(e) = git_ops::delete_worktree(&worktree.path, force) {
+        if let Some(entry) = &entry {
+            let _ = remove(&entry.id);
+        }
+        return Err(e);
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_index_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(INDEX_FILE);
+        assert!(load_from(&file).unwrap().entries.is_empty());
+
+        let entry = TrashEntry {
+            id: "feat-20250101-120000".to_string(),
+            dirname: "feat".to_string(),
+            path: "/w/feat".to_string(),
+            branch: Some("feat".to_string()),
+            head: "6cd7".to_string(),
+            deleted_at: Utc::now(),
+        };
+        let content = serde_json::to_string(&TrashIndex { entries: vec![entry.clone()] }).unwrap();
+        std::fs::write(&file, content).unwrap();
+
+        assert_eq!(load_from(&file).unwrap().entries, vec![entry.clone()]);
+        assert_eq!(entry.expires_at(Duration::days(14)), entry.deleted_at + Duration::days(14));
+    }
+}
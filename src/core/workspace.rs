@@ -0,0 +1,106 @@
+// 编辑器工作区文件：把所有 worktree 作为多根工作区的文件夹导出
+
+use crate::core::config;
+use crate::core::git_ops;
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+/// 默认的工作区文件路径：`<主仓库同级>/<仓库名>.code-workspace`
+pub fn default_workspace_path() -> Result<PathBuf> {
+    let repo_root = git_ops::get_main_repository_root()?;
+    let repo_name = repo_root.file_name().and_then(|n| n.to_str()).unwrap_or("repo");
+    let parent = repo_root
+        .parent()
+        .ok_or_else(|| WorktreeError::InvalidPath("Cannot determine parent directory".to_string()))?;
+
+    Ok(parent.join(format!("{}.code-workspace", repo_name)))
+}
+
+/// 生成 VS Code 工作区内容：替换 `folders`，保留已有文件中的其他设置
+pub fn build_vscode_workspace(worktrees: &[Worktree], existing: Option<Value>) -> Value {
+    let folders: Vec<Value> = worktrees
+        .iter()
+        .filter(|wt| !wt.is_bare)
+        .map(|wt| json!({ "name": wt.dirname, "path": wt.path }))
+        .collect();
+
+    let mut workspace = match existing {
+        Some(Value::Object(map)) => map,
+        _ => {
+            let mut map = Map::new();
+            map.insert("settings".to_string(), json!({}));
+            map
+        }
+    };
+    workspace.insert("folders".to_string(), Value::Array(folders));
+    Value::Object(workspace)
+}
+
+/// 导出工作区文件，返回写入的路径
+pub fn export(output: Option<&Path>) -> Result<PathBuf> {
+    let path = match output {
+        Some(path) => path.to_path_buf(),
+        None => default_workspace_path()?,
+    };
+    let worktrees = git_ops::list_worktrees()?;
+
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => Some(serde_json::from_str(&content).map_err(|e| {
+            WorktreeError::InvalidPath(format!("{} is not valid JSON: {}", path.display(), e))
+        })?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let workspace = build_vscode_workspace(&worktrees, existing);
+    let content = serde_json::to_string_pretty(&workspace)
+        .map_err(|e| WorktreeError::InvalidPath(e.to_string()))?;
+    std::fs::write(&path, content + "\n")?;
+
+    Ok(path)
+}
+
+/// 配置项 `work.workspace.auto` 为 true 时，在创建或删除 worktree 后重新导出默认工作区文件
+pub fn refresh_if_enabled() -> Result<Option<PathBuf>> {
+    let enabled = config::get_bool("workspace.auto");
+    if !enabled {
+        return Ok(None);
+    }
+    export(None).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worktree(dirname: &str, path: &str) -> Worktree {
+        Worktree::new(dirname.to_string(), dirname.to_string(), path.to_string(), false, false, false, None, None)
+    }
+
+    #[test]
+    fn test_build_lists_all_worktrees() {
+        let worktrees = vec![worktree("repo", "/src/repo"), worktree("feat-x", "/src/repo.worktrees/feat-x")];
+        let workspace = build_vscode_workspace(&worktrees, None);
+
+        let folders = workspace["folders"].as_array().unwrap();
+        assert_eq!(folders.len(), 2);
+        assert_eq!(folders[1]["name"], "feat-x");
+        assert_eq!(folders[1]["path"], "/src/repo.worktrees/feat-x");
+        assert!(workspace["settings"].is_object());
+    }
+
+    #[test]
+    fn test_build_keeps_existing_settings() {
+        let existing = json!({
+            "folders": [{ "path": "/old" }],
+            "settings": { "editor.tabSize": 2 },
+        });
+        let workspace = build_vscode_workspace(&[worktree("repo", "/src/repo")], Some(existing));
+
+        assert_eq!(workspace["settings"]["editor.tabSize"], 2);
+        assert_eq!(workspace["folders"].as_array().unwrap().len(), 1);
+        assert_eq!(workspace["folders"][0]["path"], "/src/repo");
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use crate::core::git_ops;
+use crate::utils::errors::{Result, WorktreeError};
 
 /// Git worktree 的概念表示
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,8 +23,38 @@ pub struct Worktree {
     pub is_current: bool,
     /// 是否为裸仓库
     pub is_bare: bool,
+    /// 是否为主 worktree（`git worktree list` 的第一项：普通仓库的主目录，或裸仓库本身）
+    #[serde(default)]
+    pub is_main: bool,
     /// 是否处于分离 HEAD 状态
     pub is_detached: bool,
+    /// 是否被 `git worktree lock` 锁定
+    #[serde(default)]
+    pub is_locked: bool,
+    /// 锁定原因（`git worktree lock --reason`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// 目录已不存在等原因，可由 `git worktree prune` 清除
+    #[serde(default)]
+    pub is_prunable: bool,
+    /// git 给出的可清除原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prune_reason: Option<String>,
+    /// 是否为临时 worktree（`work scratch` 创建，记录在 work 的元数据中）
+    #[serde(default)]
+    pub is_temporary: bool,
+    /// 临时 worktree 的过期时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// 别名（`work alias set`，记录在 work 的元数据中）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// 备注（在 `work ui` 中编辑，记录在 work 的元数据中）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 进行中的合并、变基、拣选或二分查找
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<GitOperation>,
     /// HEAD 提交的 SHA
     pub head_commit: Option<String>,
     /// 上游跟踪分支（如 `origin/main`）
@@ -34,6 +65,7 @@ pub struct Worktree {
 
 impl Worktree {
     /// 创建一个新的 Worktree 实例
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         dirname: String,
         branch_name: String,
@@ -52,14 +84,57 @@ impl Worktree {
             path,
             is_current,
             is_bare,
+            is_main: false,
             is_detached,
+            is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
+            prune_reason: None,
+            is_temporary: false,
+            expires_at: None,
+            alias: None,
+            note: None,
+            operation: None,
             head_commit,
             upstream_branch,
             last_modified: Utc::now(),
         }
     }
 
+    /// 临时 worktree 是否已过期
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.is_temporary && self.expires_at.is_some_and(|t| t <= now)
+    }
+
+    /// 按名称查找 worktree：依次匹配目录名、别名和分支名
+    pub fn resolve<'a>(worktrees: &'a [Worktree], name: &str) -> Result<&'a Worktree> {
+        worktrees
+            .iter()
+            .find(|wt| wt.dirname == name)
+            .or_else(|| worktrees.iter().find(|wt| wt.alias.as_deref() == Some(name)))
+            .or_else(|| worktrees.iter().find(|wt| !wt.is_detached && !wt.is_bare && wt.branch_name == name))
+            .ok_or_else(|| WorktreeError::NotFound(name.to_string()))
+    }
+
+    /// 确认有工作目录：裸仓库本身不能切换、删除或同步
+    pub fn ensure_checkout(&self) -> Result<()> {
+        if self.is_bare {
+            return Err(WorktreeError::BareRepository(self.dirname.clone()));
+        }
+        Ok(())
+    }
+
+    /// 确认工作目录仍然存在：在 ensure_checkout 的基础上排除目录已被删除（prunable）的条目
+    pub fn ensure_directory(&self) -> Result<()> {
+        self.ensure_checkout()?;
+        if self.is_prunable {
+            return Err(WorktreeError::InvalidPath(self.path.clone()));
+        }
+        Ok(())
+    }
+
     /// 基于当前工作目录查找当前的 worktree
+    #[allow(dead_code)]
     pub fn find_current_worktree(worktrees: &[Worktree]) -> Option<&Worktree> {
         worktrees.iter().find(|wt| wt.is_current)
     }
@@ -67,6 +142,7 @@ impl Worktree {
     /// 获取显示名称（用于输出）
     /// 如果目录名和分支名不同，返回 "dirname on branch" 格式
     /// 如果相同，只返回目录名
+    #[allow(dead_code)]
     pub fn display_name(&self) -> String {
         if self.dirname == self.branch_name {
             self.dirname.clone()
@@ -81,13 +157,29 @@ impl Worktree {
         git_ops::has_uncommitted_changes(path).unwrap_or(false)
     }
 
+    /// 确认没有进行中的操作：删除、同步和清理前检查，force 时跳过
+    pub fn ensure_idle(&self, force: bool) -> Result<()> {
+        if !force && self.operation.is_some() {
+            return Err(WorktreeError::OperationInProgress(self.dirname.clone()));
+        }
+        Ok(())
+    }
+
     /// 获取 worktree 的 Git 状态
     pub fn get_status(&self) -> WorktreeStatus {
-        // TODO: 实现状态检测
-        // 返回 Healthy/Modified/Conflict 等状态
-        if self.is_detached {
+        if self.is_bare {
+            return WorktreeStatus::Bare;
+        }
+        if self.is_prunable {
+            return WorktreeStatus::Prunable;
+        }
+
+        let status = git_ops::get_worktree_status(Path::new(&self.path)).ok();
+        if status.as_ref().is_some_and(|s| !s.conflicted.is_empty()) {
+            WorktreeStatus::Conflict
+        } else if self.is_detached {
             WorktreeStatus::Detached
-        } else if self.has_uncommitted_changes() {
+        } else if status.is_some_and(|s| !s.is_clean()) {
             WorktreeStatus::Modified
         } else {
             WorktreeStatus::Healthy
@@ -95,6 +187,59 @@ impl Worktree {
     }
 }
 
+/// 进行中的 git 操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OperationKind {
+    Merge,
+    Rebase,
+    CherryPick,
+    Bisect,
+}
+
+impl OperationKind {
+    /// 小写文本表示，同时也是对应的 git 子命令名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::Merge => "merge",
+            OperationKind::Rebase => "rebase",
+            OperationKind::CherryPick => "cherry-pick",
+            OperationKind::Bisect => "bisect",
+        }
+    }
+
+    /// 与 git 提示符一致的标记，如 `REBASING`
+    pub fn label(&self) -> &'static str {
+        match self {
+            OperationKind::Merge => "MERGING",
+            OperationKind::Rebase => "REBASING",
+            OperationKind::CherryPick => "CHERRY-PICKING",
+            OperationKind::Bisect => "BISECTING",
+        }
+    }
+}
+
+/// worktree 中进行中的操作（从 worktree 私有的 git 目录读取）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitOperation {
+    pub kind: OperationKind,
+    /// 变基的当前步骤和总步骤数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+}
+
+impl GitOperation {
+    /// 显示用的标记，如 `REBASING 3/7`、`MERGING`
+    pub fn marker(&self) -> String {
+        match (self.step, self.total) {
+            (Some(step), Some(total)) => format!("{} {}/{}", self.kind.label(), step, total),
+            _ => self.kind.label().to_string(),
+        }
+    }
+}
+
 /// Worktree 状态枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WorktreeStatus {
@@ -104,8 +249,26 @@ pub enum WorktreeStatus {
     Modified,
     /// 处于分离 HEAD 状态
     Detached,
-    /// 有冲突
+    /// 有未解决的冲突
     Conflict,
+    /// 裸仓库本身，没有工作目录
+    Bare,
+    /// 目录已不存在，等待 `work prune`
+    Prunable,
+}
+
+impl WorktreeStatus {
+    /// 状态的小写文本表示（用于模板和机器可读输出）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorktreeStatus::Healthy => "healthy",
+            WorktreeStatus::Modified => "modified",
+            WorktreeStatus::Detached => "detached",
+            WorktreeStatus::Conflict => "conflict",
+            WorktreeStatus::Bare => "bare",
+            WorktreeStatus::Prunable => "prunable",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -113,6 +276,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_worktree_creation() {
         let worktree = Worktree::new(
             "feature-auth".to_string(),
@@ -161,6 +325,38 @@ mod tests {
         assert_eq!(current.unwrap().dirname, "main");
     }
 
+    #[test]
+    fn test_resolve_by_dirname_alias_or_branch() {
+        let mut feature = Worktree::new(
+            "feature-auth-oauth".to_string(),
+            "feature/auth-oauth".to_string(),
+            "/home/user/project/worktrees/feature-auth-oauth".to_string(),
+            false,
+            false,
+            false,
+            None,
+            None,
+        );
+        feature.alias = Some("api".to_string());
+        let detached = Worktree::new(
+            "api-HEAD".to_string(),
+            "HEAD".to_string(),
+            "/home/user/project/worktrees/detached".to_string(),
+            false,
+            false,
+            true,
+            None,
+            None,
+        );
+        let worktrees = vec![feature, detached];
+
+        assert_eq!(Worktree::resolve(&worktrees, "feature-auth-oauth").unwrap().dirname, "feature-auth-oauth");
+        assert_eq!(Worktree::resolve(&worktrees, "api").unwrap().dirname, "feature-auth-oauth");
+        assert_eq!(Worktree::resolve(&worktrees, "feature/auth-oauth").unwrap().dirname, "feature-auth-oauth");
+        // 分离 HEAD 的 worktree 不能用 "HEAD" 查找
+        assert!(matches!(Worktree::resolve(&worktrees, "HEAD"), Err(WorktreeError::NotFound(_))));
+    }
+
     #[test]
     fn test_worktree_status() {
         let worktree = Worktree::new(
@@ -1,17 +1,23 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use env_logger::Env;
 use log::info;
 
 mod cli;
 mod core;
+mod tui;
 mod utils;
 
-use cli::output::{OutputFormat, format_worktree_table, format_worktree_compact, format_worktree_json};
-use core::git_ops::{list_worktrees, create_worktree, create_worktree_with_new_branch, delete_worktree, branch_exists, prune_worktrees, get_worktree_status};
+use cli::output::{OutputFormat, format_du_table, format_oplog_table, format_recent_table, format_global_table, format_worktree_table, format_ws_table, format_worktree_compact, format_worktree_json, format_worktree_template};
+use cli::schema;
+use cli::template::Template;
+use core::worktree::Worktree;
+use core::git_ops::{list_worktrees, prune_worktrees, get_worktree_status};
 use dialoguer::{theme::ColorfulTheme, Select, Confirm};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use colored::Colorize;
+use utils::errors::{Result, WorktreeError, EXIT_USAGE};
+use utils::i18n::{t, tf};
 
 /// 一个简化的 Git worktree 管理工具
 #[derive(Parser, Debug)]
@@ -20,41 +26,92 @@ use colored::Colorize;
 #[command(version = "0.1.9")]
 #[command(about = "简化 Git worktree 的管理", long_about = None)]
 struct Args {
+    /// 错误输出格式（json 时在 stderr 输出 {code, kind, message, hint}）
+    #[arg(long = "error-format", value_enum, global = true, default_value = "text")]
+    error_format: ErrorFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// 错误输出格式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    Text,
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// 列出所有 worktree
     #[command(alias = "ls")]
     List {
-        /// 输出格式 (table, compact, json)
+        /// 输出格式 (table, compact, json, ndjson, template=<模板>)
         #[arg(short = 'o', long = "output", default_value = "compact")]
         output_format: String,
+        /// 自定义输出模板，如 '{{dirname}}\t{{branch}}'（等同于 -o template=...）
+        #[arg(long = "format", value_name = "TEMPLATE", conflicts_with = "output_format")]
+        format: Option<String>,
+        /// 稳定的机器可读行格式（目前只有 v1）
+        #[arg(long = "porcelain", value_name = "VERSION", num_args = 0..=1, default_missing_value = "v1", conflicts_with_all = ["output_format", "format"])]
+        porcelain: Option<String>,
+        /// 列出索引中所有仓库的 worktree（支持 table、compact、json）
+        #[arg(short = 'a', long = "all", conflicts_with_all = ["format", "porcelain"])]
+        all: bool,
     },
     /// 切换到指定的 worktree
     Switch {
-        /// Worktree 名称
+        /// Worktree 名称，`-` 为上一个 worktree（省略时按最近使用排序选择）
         name: Option<String>,
         /// 输出 worktree 路径供 shell 集成使用
         #[arg(long = "print-path")]
         print_path: bool,
+        /// 在索引中的所有仓库中查找（名称可写作 <仓库>/<worktree>）
+        #[arg(short = 'g', long = "global")]
+        global: bool,
+    },
+    /// 列出最近切换过的 worktree（最近的在前）
+    Recent {
+        /// 最多显示的条数
+        #[arg(short = 'n', long = "limit")]
+        limit: Option<usize>,
+        /// 输出格式 (table, json)
+        #[arg(short = 'o', long = "output", default_value = "table", value_parser = ["table", "json"])]
+        output_format: String,
+    },
+    /// 管理 worktree 的别名
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommands,
+    },
+    /// 以裸仓库布局克隆仓库（<目录>/.bare），并为默认分支创建第一个 worktree
+    Clone {
+        /// 仓库 URL 或路径
+        source: String,
+        /// 目标目录（默认为仓库名）
+        dir: Option<PathBuf>,
     },
     /// 创建新的 worktree
     #[command(alias = "new")]
     Create {
-        /// 分支名或 worktree 名称
-        name: String,
+        /// 分支名或 worktree 名称（使用 --detach 时默认为修订名）
+        #[arg(required_unless_present = "detach")]
+        name: Option<String>,
         /// 基准分支（用于创建新分支）
         #[arg(short = 'b', long = "branch")]
         branch: Option<String>,
+        /// 在指定的提交、标签或分支上创建分离 HEAD 的 worktree（不创建分支）
+        #[arg(long = "detach", value_name = "REV", conflicts_with_all = ["branch", "interactive"])]
+        detach: Option<String>,
         /// 自定义路径
         #[arg(short = 'p', long = "path")]
         path: Option<String>,
         /// 交互式选择基准分支
         #[arg(short = 'i', long = "interactive")]
         interactive: bool,
+        /// 创建后在编辑器中打开
+        #[arg(long = "open")]
+        open: bool,
     },
     /// 删除 worktree
     #[command(alias = "rm")]
@@ -62,21 +119,36 @@ enum Commands {
         /// Worktree 名称（可指定多个）
         #[arg(required = false)]
         names: Vec<String>,
-        /// 强制删除（忽略未提交的更改）
+        /// 强制删除（忽略未提交的更改和进行中的操作）
         #[arg(short = 'f', long = "force")]
         force: bool,
         /// 交互式选择要删除的 worktree
         #[arg(short = 'i', long = "interactive")]
         interactive: bool,
+        /// 同时结束对应的终端复用器会话（不再询问）
+        #[arg(long = "kill-session")]
+        kill_session: bool,
+        /// 删除有未提交更改的 worktree 前先保存快照（不再询问）
+        #[arg(long = "snapshot")]
+        snapshot: bool,
+        /// 不保存到回收站，直接删除（无法用 undo 恢复）
+        #[arg(long = "no-trash")]
+        no_trash: bool,
     },
     /// 显示 worktree 详细信息
     #[command(alias = "show")]
     Info {
         /// Worktree 名称
         name: String,
-        /// 输出格式 (table 或 json)
+        /// 输出格式 (table, json, ndjson, template=<模板>)
         #[arg(short = 'o', long = "output", default_value = "table")]
         output_format: String,
+        /// 自定义输出模板，如 '{{path}}'（等同于 -o template=...）
+        #[arg(long = "format", value_name = "TEMPLATE", conflicts_with = "output_format")]
+        format: Option<String>,
+        /// 稳定的机器可读行格式（目前只有 v1）
+        #[arg(long = "porcelain", value_name = "VERSION", num_args = 0..=1, default_missing_value = "v1", conflicts_with_all = ["output_format", "format"])]
+        porcelain: Option<String>,
     },
     /// 清理无效的 worktree
     Prune {
@@ -84,44 +156,522 @@ enum Commands {
         #[arg(long = "dry-run")]
         dry_run: bool,
     },
+    /// 在编辑器中打开 worktree
+    Open {
+        /// Worktree 名称
+        name: String,
+        /// 编辑器命令（如 code、idea、nvim），默认使用配置项 editor 或 $VISUAL / $EDITOR
+        #[arg(short = 'e', long = "editor")]
+        editor: Option<String>,
+    },
+    /// 在指定提交上创建临时 worktree（分离 HEAD，过期后可用 clean --expired 删除）
+    Scratch {
+        /// 提交、标签或分支（默认为 HEAD）
+        #[arg(default_value = "HEAD")]
+        rev: String,
+        /// 有效期，如 30m、12h、7d、2w（默认使用配置项 scratch.ttl，未设置时为 7d）
+        #[arg(long = "ttl")]
+        ttl: Option<String>,
+        /// 只输出新 worktree 的路径供 shell 使用
+        #[arg(long = "print-path")]
+        print_path: bool,
+    },
+    /// 清理 worktree
+    Clean {
+        /// 删除已过期的临时 worktree
+        #[arg(long = "expired")]
+        expired: bool,
+        /// 预览将要删除的 worktree（不实际删除）
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+        /// 强制删除（忽略未提交的更改和进行中的操作）
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+    },
+    /// 把当前分支及其未提交的更改移到新的 worktree，当前目录切回默认分支
+    #[command(alias = "detach-here")]
+    Promote {
+        /// 当前目录要切回的分支（默认自动检测仓库的默认分支）
+        #[arg(short = 'b', long = "base")]
+        base: Option<String>,
+        /// 新 worktree 的路径
+        #[arg(short = 'p', long = "path")]
+        path: Option<String>,
+    },
+    /// 把未提交的更改从一个 worktree 转移到另一个
+    Carry {
+        /// 源 worktree 名称
+        from: String,
+        /// 目标 worktree 名称
+        to: String,
+        /// 只转移这些路径（默认转移所有更改）
+        paths: Vec<String>,
+        /// 保留源 worktree 中的更改（复制而不是移动）
+        #[arg(long = "copy")]
+        copy: bool,
+    },
+    /// 把 worktree 的完整状态（HEAD、暂存区、工作区更改和未跟踪文件）保存为归档
+    Snapshot {
+        /// Worktree 名称（默认为当前 worktree）
+        name: Option<String>,
+        /// 归档路径（默认保存在仓库的 work/snapshots 目录下）
+        #[arg(short = 'o', long = "output")]
+        output: Option<String>,
+    },
+    /// 从快照归档重新创建 worktree
+    Restore {
+        /// 归档路径，或 work/snapshots 目录下的文件名
+        archive: String,
+        /// 新 worktree 的名称（默认使用快照中的名称）
+        #[arg(long = "as", value_name = "NAME")]
+        as_name: Option<String>,
+        /// 新 worktree 的路径
+        #[arg(short = 'p', long = "path")]
+        path: Option<String>,
+    },
+    /// 统计每个 worktree 的磁盘占用（已跟踪、未跟踪和被忽略的内容）
+    Du {
+        /// Worktree 名称（默认为所有 worktree）
+        names: Vec<String>,
+        /// 排序方式 (disk, apparent, ignored, name)
+        #[arg(short = 's', long = "sort", default_value = "disk", value_parser = ["disk", "apparent", "ignored", "name"])]
+        sort: String,
+        /// 每个 worktree 显示的最大被忽略目录数
+        #[arg(long = "top", default_value_t = 3)]
+        top: usize,
+        /// 输出格式 (table, json)
+        #[arg(short = 'o', long = "output", default_value = "table", value_parser = ["table", "json"])]
+        output_format: String,
+        /// 删除匹配的被忽略内容，如 target 或 'crates/*/target'
+        #[arg(long = "clean-ignored", value_name = "GLOB")]
+        clean_ignored: Option<String>,
+        /// 与 --clean-ignored 一起使用：只显示将要删除的内容
+        #[arg(long = "dry-run", requires = "clean_ignored")]
+        dry_run: bool,
+    },
+    /// 管理已删除 worktree 的回收站
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+    /// 撤销最近一次创建或删除 worktree 的操作
+    Undo {
+        /// 只显示将要撤销的操作
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+        /// 撤销创建时忽略进行中的合并、变基等操作
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+    },
+    /// 查看修改 worktree 的操作日志（最新的在前）
+    Log {
+        /// 只显示此时间之后的操作，如 12h、7d 或 2025-01-01
+        #[arg(long = "since")]
+        since: Option<String>,
+        /// 最多显示的条数
+        #[arg(short = 'n', long = "limit")]
+        limit: Option<usize>,
+        /// 输出格式 (table, json, ndjson)
+        #[arg(short = 'o', long = "output", default_value = "table", value_parser = ["table", "json", "ndjson"])]
+        output_format: String,
+    },
+    /// 以 shell export 语句输出 worktree 的 .env.work（分配的端口等）
+    Env {
+        /// Worktree 名称（默认为当前 worktree）
+        name: Option<String>,
+        /// 同时输出 WORK_DIRNAME、WORK_BRANCH 和 WORK_MAIN_PATH（与 .envrc 相同的环境）
+        #[arg(long = "shell")]
+        shell: bool,
+    },
+    /// 创建或连接以 worktree 命名的 tmux / zellij 会话
+    Session {
+        /// Worktree 名称（默认为当前 worktree）
+        name: Option<String>,
+        /// 终端复用器（默认使用配置项 session.multiplexer，未设置时为 tmux）
+        #[arg(short = 'm', long = "multiplexer", value_parser = ["tmux", "zellij"])]
+        multiplexer: Option<String>,
+    },
+    /// 管理编辑器工作区文件
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommands,
+    },
+    /// 跨多个仓库管理同名的 worktree（仓库列在 work.repos 文件中）
+    Ws {
+        /// 工作区文件（默认从当前目录向上查找 work.repos）
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
+        #[command(subcommand)]
+        command: WsCommands,
+    },
+    /// 在目录下查找 Git 仓库并加入全局索引（供 ls --all 和 switch --global 使用）
+    Scan {
+        /// 要扫描的目录
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// 最多向下查找的层数
+        #[arg(long = "depth", default_value_t = 3)]
+        depth: usize,
+        /// 同时从索引中移除已不存在的仓库
+        #[arg(long = "prune")]
+        prune: bool,
+    },
+    /// 打开交互式终端仪表盘
+    Ui,
+    /// 输出机器可读格式（ndjson）的 JSON Schema
+    Schema {
+        /// Schema 版本
+        #[arg(default_value = "v1")]
+        version: String,
+    },
+    /// 查看或修改配置项（保存在 git config 的 work.* 下）
+    Config {
+        /// 配置项名称（省略时列出所有配置项）
+        key: Option<String>,
+        /// 新的值（省略时输出当前值）
+        value: Option<String>,
+        /// 读写用户级配置（~/.gitconfig）而不是当前仓库
+        #[arg(long = "global")]
+        global: bool,
+        /// 删除配置项
+        #[arg(long = "unset", requires = "key", conflicts_with = "value")]
+        unset: bool,
+        /// 追加一个值而不是替换（用于 session.window 等多值配置项）
+        #[arg(long = "add", requires = "value", conflicts_with = "unset")]
+        add: bool,
+    },
+    /// 生成 shell 补全脚本
+    Completions {
+        /// 目标 shell
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// 供补全脚本调用，输出动态候选
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// 正在补全的参数位置
+        index: usize,
+        /// work 之后已输入的参数
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AliasCommands {
+    /// 为 worktree 设置别名（替换原有的别名）
+    Set {
+        /// 别名，不能与已有的目录名、别名或分支名重复
+        alias: String,
+        /// Worktree 名称
+        name: String,
+    },
+    /// 删除别名
+    #[command(alias = "remove")]
+    Rm {
+        /// 别名
+        alias: String,
+    },
+    /// 列出所有别名
+    #[command(alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum WsCommands {
+    /// 在每个仓库中创建同名分支和 worktree
+    #[command(alias = "new")]
+    Create {
+        /// 分支名（已存在的分支直接检出）
+        branch: String,
+        /// 新分支的起点（默认为各仓库的 HEAD）
+        #[arg(short = 'b', long = "base")]
+        base: Option<String>,
+    },
+    /// 列出所有仓库的 worktree
+    #[command(alias = "ls")]
+    List {
+        /// 输出格式 (table, json)
+        #[arg(short = 'o', long = "output", default_value = "table", value_parser = ["table", "json"])]
+        output_format: String,
+    },
+    /// 切换到 worktree 在当前仓库（或 --repo 指定的仓库）中的目录
+    Switch {
+        /// Worktree 名称、别名或分支名
+        name: String,
+        /// 仓库名（默认为当前目录所在的仓库，不在任何仓库中时为第一个）
+        #[arg(short = 'r', long = "repo")]
+        repo: Option<String>,
+        /// 输出 worktree 路径供 shell 集成使用
+        #[arg(long = "print-path")]
+        print_path: bool,
+    },
+    /// 在每个仓库中删除同名的 worktree（放入各自的回收站）
+    #[command(alias = "rm")]
+    Delete {
+        /// Worktree 名称、别名或分支名
+        name: String,
+        /// 强制删除（即使有未提交的更改）
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TrashCommands {
+    /// 列出回收站中的 worktree
+    #[command(alias = "ls")]
+    List,
+    /// 从回收站恢复 worktree
+    Restore {
+        /// 回收站条目 id（见 trash list）
+        id: String,
+        /// 新 worktree 的名称（默认使用原名称）
+        #[arg(long = "as", value_name = "NAME")]
+        as_name: Option<String>,
+        /// 新 worktree 的路径
+        #[arg(short = 'p', long = "path")]
+        path: Option<String>,
+    },
+    /// 清空回收站
+    Empty {
+        /// 只清除超过保留期限的条目
+        #[arg(long = "expired")]
+        expired: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WorkspaceCommands {
+    /// 导出包含所有 worktree 的 VS Code 多根工作区文件（.code-workspace）
+    Export {
+        /// 输出文件路径（默认为主仓库同级的 <仓库名>.code-workspace）
+        #[arg(short = 'o', long = "output")]
+        output: Option<String>,
+    },
 }
 
-fn main() -> Result<()> {
-    // 初始化日志
-    env_logger::Builder::from_env(Env::default().default_filter_or("info"))
+fn main() -> ExitCode {
+    // 初始化日志（默认只输出警告，避免污染 stderr 上的机器可读错误）
+    env_logger::Builder::from_env(Env::default().default_filter_or("warn"))
         .init();
 
-    let args = Args::parse();
+    // 帮助文本按当前语言从消息目录加载
+    let command = cli::commands::helpers::localize_command(Args::command(), utils::i18n::current_locale());
+    let args = match command
+        .try_get_matches()
+        .and_then(|matches| Args::from_arg_matches(&matches))
+    {
+        Ok(args) => args,
+        Err(e) => return report_clap_error(e),
+    };
 
     info!("执行 work 命令: {:?}", args.command);
 
-    match args.command {
-        Commands::List { output_format } => {
-            list_command_handler(&output_format)
+    let error_format = args.error_format;
+    match run(args.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            report_error(&e, error_format);
+            ExitCode::from(e.exit_code() as u8)
         }
-        Commands::Switch { name, print_path } => {
+    }
+}
+
+/// 分发子命令
+fn run(command: Commands) -> Result<()> {
+    // 把当前仓库登记到全局索引（补全等不需要仓库的命令除外）
+    if !matches!(command, Commands::Complete { .. } | Commands::Completions { .. } | Commands::Schema { .. }) {
+        core::registry::touch_current();
+    }
+
+    match command {
+        Commands::List { all: true, output_format, .. } => {
+            list_all_command_handler(&output_format)
+        }
+        Commands::List { output_format, format, porcelain, .. } => {
+            list_command_handler(&output_format, format.as_deref(), porcelain.as_deref())
+        }
+        Commands::Switch { name, print_path, global: true } => {
+            switch_global_command_handler(name.as_deref(), print_path)
+        }
+        Commands::Switch { name, print_path, .. } => {
             switch_command_handler(name.as_deref(), print_path)
         }
-        Commands::Create { name, branch, path, interactive } => {
-            create_command_handler(&name, branch.as_deref(), path.as_deref(), interactive)
+        Commands::Recent { limit, output_format } => {
+            recent_command_handler(limit, &output_format)
         }
-        Commands::Delete { names, force, interactive } => {
-            delete_command_handler(&names, force, interactive)
+        Commands::Alias { command } => match command {
+            AliasCommands::Set { alias, name } => alias_set_command_handler(&alias, &name),
+            AliasCommands::Rm { alias } => alias_rm_command_handler(&alias),
+            AliasCommands::List => alias_list_command_handler(),
+        },
+        Commands::Clone { source, dir } => {
+            clone_command_handler(&source, dir.as_deref())
         }
-        Commands::Info { name, output_format } => {
-            info_command_handler(&name, &output_format)
+        Commands::Create { name, branch, detach, path, interactive, open } => {
+            create_command_handler(name.as_deref(), branch.as_deref(), detach.as_deref(), path.as_deref(), interactive, open)
+        }
+        Commands::Delete { names, force, interactive, kill_session, snapshot, no_trash } => {
+            delete_command_handler(&names, force, interactive, kill_session, snapshot, no_trash)
+        }
+        Commands::Info { name, output_format, format, porcelain } => {
+            info_command_handler(&name, &output_format, format.as_deref(), porcelain.as_deref())
         }
         Commands::Prune { dry_run } => {
             prune_command_handler(dry_run)
         }
+        Commands::Open { name, editor } => {
+            open_command_handler(&name, editor.as_deref())
+        }
+        Commands::Scratch { rev, ttl, print_path } => {
+            scratch_command_handler(&rev, ttl.as_deref(), print_path)
+        }
+        Commands::Clean { expired, dry_run, force } => {
+            clean_command_handler(expired, dry_run, force)
+        }
+        Commands::Promote { base, path } => {
+            promote_command_handler(base.as_deref(), path.as_deref())
+        }
+        Commands::Carry { from, to, paths, copy } => {
+            carry_command_handler(&from, &to, &paths, copy)
+        }
+        Commands::Snapshot { name, output } => {
+            snapshot_command_handler(name.as_deref(), output.as_deref())
+        }
+        Commands::Restore { archive, as_name, path } => {
+            restore_command_handler(&archive, as_name.as_deref(), path.as_deref())
+        }
+        Commands::Du { names, sort, top, output_format, clean_ignored, dry_run } => {
+            du_command_handler(&names, &sort, top, &output_format, clean_ignored.as_deref(), dry_run)
+        }
+        Commands::Trash { command } => match command {
+            TrashCommands::List => trash_list_command_handler(),
+            TrashCommands::Restore { id, as_name, path } => {
+                trash_restore_command_handler(&id, as_name.as_deref(), path.as_deref())
+            }
+            TrashCommands::Empty { expired } => trash_empty_command_handler(expired),
+        },
+        Commands::Undo { dry_run, force } => {
+            undo_command_handler(dry_run, force)
+        }
+        Commands::Log { since, limit, output_format } => {
+            log_command_handler(since.as_deref(), limit, &output_format)
+        }
+        Commands::Env { name, shell } => {
+            env_command_handler(name.as_deref(), shell)
+        }
+        Commands::Session { name, multiplexer } => {
+            session_command_handler(name.as_deref(), multiplexer.as_deref())
+        }
+        Commands::Workspace { command: WorkspaceCommands::Export { output } } => {
+            workspace_export_command_handler(output.as_deref())
+        }
+        Commands::Ws { file, command } => match command {
+            WsCommands::Create { branch, base } => ws_create_command_handler(file.as_deref(), &branch, base.as_deref()),
+            WsCommands::List { output_format } => ws_list_command_handler(file.as_deref(), &output_format),
+            WsCommands::Switch { name, repo, print_path } => {
+                ws_switch_command_handler(file.as_deref(), &name, repo.as_deref(), print_path)
+            }
+            WsCommands::Delete { name, force } => ws_delete_command_handler(file.as_deref(), &name, force),
+        },
+        Commands::Scan { dir, depth, prune } => {
+            scan_command_handler(&dir, depth, prune)
+        }
+        Commands::Ui => {
+            ui_command_handler()
+        }
+        Commands::Schema { version } => {
+            schema_command_handler(&version)
+        }
+        Commands::Config { key, value, global, unset, add } => {
+            config_command_handler(key.as_deref(), value.as_deref(), global, unset, add)
+        }
+        Commands::Completions { shell } => {
+            completions_command_handler(shell)
+        }
+        Commands::Complete { index, words } => {
+            complete_command_handler(index, &words)
+        }
+    }
+}
+
+/// 按指定格式在 stderr 输出错误
+fn report_error(error: &WorktreeError, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Json => {
+            let payload = serde_json::json!({
+                "code": error.exit_code(),
+                "kind": error.kind(),
+                "message": error.to_string(),
+                "hint": error.hint(),
+            });
+            eprintln!("{}", payload);
+        }
+        ErrorFormat::Text => {
+            eprintln!("{} {}", t("label.error").red().bold(), error);
+            if let Some(hint) = error.hint() {
+                eprintln!("{} {}", t("label.hint").yellow(), hint);
+            }
+        }
+    }
+}
+
+/// 处理 clap 解析错误：--help/--version 正常输出，其余按 --error-format 输出
+fn report_clap_error(error: clap::Error) -> ExitCode {
+    use clap::error::ErrorKind;
+
+    if matches!(error.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand) {
+        let _ = error.print();
+        return ExitCode::from(error.exit_code() as u8);
+    }
+
+    // 解析失败时无法从 Args 读取 --error-format，直接检查原始参数
+    let raw: Vec<String> = std::env::args().collect();
+    let wants_json = raw.iter().any(|a| a == "--error-format=json")
+        || raw.windows(2).any(|w| w[0] == "--error-format" && w[1] == "json");
+
+    if wants_json {
+        // 只取第一行并去掉 "error: " 前缀，不包含 Usage 部分
+        let rendered = error.render().to_string();
+        let message = rendered
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches("error: ")
+            .to_string();
+        let payload = serde_json::json!({
+            "code": EXIT_USAGE,
+            "kind": "usage",
+            "message": message,
+            "hint": t("error.hint.usage"),
+        });
+        eprintln!("{}", payload);
+    } else {
+        let _ = error.print();
+    }
+
+    ExitCode::from(EXIT_USAGE as u8)
+}
+
+/// 解析输出格式，`--porcelain` 和 `--format` 优先于 `-o`
+fn resolve_output_format(output_format: &str, template: Option<&str>, porcelain: Option<&str>) -> Result<OutputFormat> {
+    if let Some(version) = porcelain {
+        schema::check_version(version)?;
+        return Ok(OutputFormat::Porcelain);
     }
+
+    Ok(match template {
+        Some(t) => OutputFormat::Template(t.to_string()),
+        None => OutputFormat::from_str(output_format),
+    })
 }
 
 /// 处理 list 命令
-fn list_command_handler(output_format: &str) -> Result<()> {
+fn list_command_handler(output_format: &str, template: Option<&str>, porcelain: Option<&str>) -> Result<()> {
     let worktrees = list_worktrees()?;
 
-    let format = OutputFormat::from_str(output_format);
+    let format = resolve_output_format(output_format, template, porcelain)?;
 
     match format {
         OutputFormat::Table => {
@@ -133,6 +683,68 @@ fn list_command_handler(output_format: &str) -> Result<()> {
         OutputFormat::Json => {
             println!("{}", format_worktree_json(worktrees));
         }
+        OutputFormat::Ndjson => {
+            print_lines(&schema::format_worktree_ndjson(&worktrees));
+        }
+        OutputFormat::Porcelain => {
+            print!("{}", schema::format_worktree_porcelain_v1(&worktrees));
+        }
+        OutputFormat::Template(source) => {
+            print_template_output(&worktrees, &source)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 输出多行文本，空内容时不输出空行
+fn print_lines(output: &str) {
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+}
+
+/// 处理 list --all 命令
+fn list_all_command_handler(output_format: &str) -> Result<()> {
+    let registry = core::registry::Registry::load()?;
+    let (worktrees, missing) = registry.list_all()?;
+
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&worktrees).map_err(|e| WorktreeError::InvalidArgument(e.to_string()))?);
+        }
+        "table" | "compact" => {
+            if worktrees.is_empty() {
+                println!("{}", t("registry.empty"));
+            } else {
+                println!("{}", format_global_table(&worktrees));
+            }
+        }
+        other => {
+            return Err(WorktreeError::InvalidArgument(tf("error.detail.global_format", &[("format", other)])));
+        }
+    }
+
+    // 已移动或删除的仓库只提示，不影响其他仓库的列表
+    for repo in missing {
+        eprintln!(
+            "{}",
+            tf("registry.missing", &[("name", &repo.name), ("path", &repo.path.display().to_string())]).yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// 按模板输出 worktree，NUL 结尾的模板不追加换行
+fn print_template_output(worktrees: &[core::worktree::Worktree], source: &str) -> Result<()> {
+    let template = Template::parse(source)?;
+    let output = format_worktree_template(worktrees, &template);
+
+    if template.ends_with_nul() {
+        print!("{}", output);
+    } else {
+        print_lines(&output);
     }
 
     Ok(())
@@ -140,116 +752,233 @@ fn list_command_handler(output_format: &str) -> Result<()> {
 
 /// 处理 switch 命令
 fn switch_command_handler(name: Option<&str>, print_path: bool) -> Result<()> {
+    use std::io::IsTerminal;
+
     let worktrees = list_worktrees()?;
+    let history = core::history::History::load()?;
 
-    let target_worktree = if let Some(n) = name {
-        // 查找指定的 worktree
-        worktrees
-            .iter()
-            .find(|wt| wt.dirname == n)
-            .ok_or_else(|| anyhow::anyhow!("Worktree '{}' not found", n))?
-    } else {
-        // 交互式选择
-        // TODO: 使用 inquire 实现交互式选择
-        // 现在简单地选择第一个非当前的 worktree
-        worktrees
-            .iter()
-            .find(|wt| !wt.is_current)
-            .ok_or_else(|| anyhow::anyhow!("No other worktrees available"))?
+    let target_worktree = match name {
+        Some("-") => history
+            .previous(&worktrees)
+            .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.no_previous")))?,
+        Some(n) => Worktree::resolve(&worktrees, n)?,
+        None => {
+            // 按 frecency 排序，终端中交互式选择，否则选择排在最前面的（裸仓库和目录已不存在的除外）
+            let mut candidates: Vec<&Worktree> = worktrees
+                .iter()
+                .filter(|wt| !wt.is_current && !wt.is_bare && !wt.is_prunable)
+                .collect();
+            if candidates.is_empty() {
+                return Err(WorktreeError::NothingToSelect(t("error.detail.no_other_worktrees")));
+            }
+            history.sort_by_frecency(&mut candidates, chrono::Utc::now());
+
+            if std::io::stdin().is_terminal() {
+                let items: Vec<&str> = candidates.iter().map(|wt| wt.dirname.as_str()).collect();
+                let selection = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt(t("switch.select"))
+                    .items(&items)
+                    .default(0)
+                    .interact()?;
+                candidates[selection]
+            } else {
+                candidates[0]
+            }
+        }
     };
 
+    // 目录已不存在时切换过去没有意义
+    target_worktree.ensure_directory()?;
+    core::history::record_switch(&worktrees, target_worktree);
+
     if print_path {
         // 只输出路径供 shell 使用
         println!("{}", target_worktree.path);
     } else {
         // 输出友好的切换提示
-        println!("切换到 worktree: {}", target_worktree.dirname);
-        println!("路径: {}", target_worktree.path);
-        println!("\n提示: 使用 eval \"$(work switch {} --print-path)\" 自动切换目录", target_worktree.dirname);
+        println!("{}", tf("switch.target", &[("name", &target_worktree.dirname)]));
+        println!("{}", tf("switch.path", &[("path", &target_worktree.path)]));
+        println!("{}", tf("switch.hint", &[("name", &target_worktree.dirname)]));
     }
 
     Ok(())
 }
 
-/// 处理 create 命令
-fn create_command_handler(name: &str, branch: Option<&str>, path: Option<&str>, interactive: bool) -> Result<()> {
-    let worktrees = list_worktrees()?;
+/// 处理 switch --global 命令
+fn switch_global_command_handler(name: Option<&str>, print_path: bool) -> Result<()> {
+    use std::io::IsTerminal;
 
-    // 获取主仓库的 .git 目录（不是当前 worktree 的 .git 文件）
-    // 使用 git rev-parse --git-common-dir 来找到主仓库
-    let output = std::process::Command::new("git")
-        .args(["rev-parse", "--git-common-dir"])
-        .output()
-        .map_err(|e| anyhow::anyhow!("Failed to find git directory: {}", e))?;
+    let registry = core::registry::Registry::load()?;
+    let (worktrees, _) = registry.list_all()?;
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("Not in a git repository"));
+    let candidates: Vec<&core::registry::GlobalWorktree> = match name {
+        Some(n) => core::registry::find(&worktrees, n),
+        None => worktrees
+            .iter()
+            .filter(|wt| !wt.worktree.is_current && !wt.worktree.is_bare && !wt.worktree.is_prunable)
+            .collect(),
+    };
+    if candidates.is_empty() {
+        return Err(match name {
+            Some(n) => WorktreeError::NotFound(n.to_string()),
+            None => WorktreeError::NothingToSelect(t("error.detail.registry_empty")),
+        });
     }
 
-    let git_common_dir_cow = String::from_utf8_lossy(&output.stdout);
-    let git_common_dir_relative = git_common_dir_cow.trim();
-
-    // 如果是相对路径，需要转换为绝对路径
-    let current_dir = std::env::current_dir()?;
-    let git_dir = if git_common_dir_relative.starts_with("/") || git_common_dir_relative.starts_with(".") {
-        // 绝对路径或相对路径，需要规范化
-        current_dir.join(git_common_dir_relative).canonicalize()
-            .unwrap_or_else(|_| current_dir.join(git_common_dir_relative))
+    let names: Vec<String> = candidates.iter().map(|wt| wt.qualified_name()).collect();
+    let target = if candidates.len() == 1 {
+        candidates[0]
+    } else if std::io::stdin().is_terminal() {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t("switch.select"))
+            .items(&names)
+            .default(0)
+            .interact()?;
+        candidates[selection]
+    } else if name.is_none() {
+        candidates[0]
     } else {
-        // 可能是简单的 ".git"，需要基于当前目录
-        current_dir.join(git_common_dir_relative)
+        return Err(WorktreeError::InvalidArgument(tf(
+            "error.detail.ambiguous_name",
+            &[("name", name.unwrap_or_default()), ("matches", &names.join(", "))],
+        )));
     };
 
-    // git_common_dir 应该指向主仓库的 .git 目录
-    // 它的父目录就是主仓库所在目录
-    let repo_root = git_dir
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("Cannot determine repository root"))?
-        .to_path_buf();
+    target.worktree.ensure_directory()?;
+
+    // 切换历史按仓库保存，记录到目标仓库中
+    let local: Vec<Worktree> = worktrees
+        .iter()
+        .filter(|wt| wt.repo_path == target.repo_path)
+        .map(|wt| wt.worktree.clone())
+        .collect();
+    if let Err(e) = core::git_ops::in_repo(&target.repo_path, || {
+        core::history::record_switch(&local, &target.worktree);
+        Ok(())
+    }) {
+        log::warn!("Failed to record switch history: {}", e);
+    }
+
+    let qualified_name = target.qualified_name();
+    if print_path {
+        println!("{}", target.worktree.path);
+    } else {
+        println!("{}", tf("switch.target", &[("name", &qualified_name)]));
+        println!("{}", tf("switch.path", &[("path", &target.worktree.path)]));
+        println!("{}", tf("switch.global_hint", &[("name", &qualified_name)]));
+    }
+
+    Ok(())
+}
 
-    // 确定路径
-    // name 参数实际上是分支名（可能包含斜杠）
-    // 转换分支名得到目录名
-    let dirname = core::git_ops::branch_to_dirname(name);
+/// 处理 recent 命令
+fn recent_command_handler(limit: Option<usize>, output_format: &str) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let mut visits = core::history::History::load()?.recent(&worktrees);
+    if let Some(limit) = limit {
+        visits.truncate(limit);
+    }
 
-    let worktree_path = if let Some(custom_path) = path {
-        custom_path.to_string()
+    if output_format == "json" {
+        println!("{}", serde_json::to_string_pretty(&visits).map_err(|e| WorktreeError::InvalidArgument(e.to_string()))?);
+    } else if visits.is_empty() {
+        println!("{}", t("recent.empty"));
     } else {
-        // 获取主仓库目录的名称
-        let dir_name = repo_root
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("repo");
-
-        // 在主仓库目录的同一级创建 <dirname>.worktrees 目录
-        let worktrees_parent = repo_root
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine parent directory"))?;
-
-        let worktrees_dir_name = format!("{}.worktrees", dir_name);
-        let worktree_path_buf = worktrees_parent.join(worktrees_dir_name).join(&dirname);
-
-        // 规范化路径以用于 Git 命令
-        // 这会处理 Windows 的扩展路径语法 (//?/C:/)
-        utils::path::normalize_path_for_git(&worktree_path_buf)
-            .to_string_lossy()
-            .to_string()
-    };
+        println!("{}", format_recent_table(&visits));
+    }
 
-    // 检查 worktree 是否已存在（使用转换后的目录名）
-    if worktrees.iter().any(|wt| wt.dirname == dirname) {
-        return Err(anyhow::anyhow!("Worktree '{}' already exists", dirname));
+    Ok(())
+}
+
+/// 处理 alias set 命令
+fn alias_set_command_handler(alias: &str, name: &str) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let worktree = Worktree::resolve(&worktrees, name)?;
+    worktree.ensure_checkout()?;
+    core::alias::set(alias, worktree, &worktrees)?;
+    println!("{}", tf("alias.set", &[("alias", &alias.cyan().bold().to_string()), ("name", &worktree.dirname)]));
+    Ok(())
+}
+
+/// 处理 alias rm 命令
+fn alias_rm_command_handler(alias: &str) -> Result<()> {
+    core::alias::remove(alias)?;
+    println!("{}", tf("alias.removed", &[("alias", alias)]));
+    Ok(())
+}
+
+/// 处理 alias list 命令
+fn alias_list_command_handler() -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let aliased: Vec<&Worktree> = worktrees.iter().filter(|wt| wt.alias.is_some()).collect();
+    if aliased.is_empty() {
+        println!("{}", t("alias.none"));
+        return Ok(());
+    }
+
+    for wt in aliased {
+        println!("{} -> {}", wt.alias.as_deref().unwrap_or_default().cyan().bold(), wt.dirname);
     }
+    Ok(())
+}
+
+/// 处理 clone 命令
+fn clone_command_handler(source: &str, dir: Option<&Path>) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::from(core::clone::default_dir(source).ok_or_else(|| {
+            WorktreeError::InvalidArgument(tf("error.detail.clone_dirname", &[("source", source)]))
+        })?),
+    };
+
+    println!("{}", tf("clone.cloning", &[("source", source), ("path", &dir.display().to_string())]));
+    let cloned = core::clone::clone(source, &dir)?;
+    let root = cloned.root.display().to_string();
+
+    let Some(worktree_path) = cloned.worktree_path else {
+        println!("{}", tf("clone.empty", &[("path", &root)]).yellow());
+        return Ok(());
+    };
 
-    // 交互式选择基准分支
+    // 在新仓库中记录操作日志、登记到全局索引，并像 create 一样应用缓存和环境配置
+    let dirname = core::git_ops::branch_to_dirname(&cloned.branch);
+    core::git_ops::in_repo(&cloned.root, || {
+        core::oplog::record(&core::oplog::Operation::create(&dirname, &worktree_path, Some(&cloned.branch), false));
+        core::registry::touch_current();
+        print_setup(&core::lifecycle::setup_existing(Path::new(&worktree_path), &dirname, &cloned.branch)?);
+        Ok(())
+    })?;
+
+    println!("{}", tf("clone.done", &[("path", &root)]).green());
+    println!("{}", tf("clone.worktree", &[("name", &dirname), ("path", &worktree_path)]));
+    println!("{}", tf("clone.hint", &[("path", &worktree_path)]));
+    Ok(())
+}
+
+/// 处理 create 命令
+fn create_command_handler(
+    name: Option<&str>,
+    branch: Option<&str>,
+    detach: Option<&str>,
+    path: Option<&str>,
+    interactive: bool,
+    open: bool,
+) -> Result<()> {
+    // name 参数实际上是分支名（可能包含斜杠），--detach 时可省略并使用修订名
+    let name = name
+        .or(detach)
+        .ok_or_else(|| WorktreeError::InvalidArgument(t("error.detail.name_required")))?;
+
+    // 交互式选择基准分支（先检查名称，避免选完才发现无法创建）
     let base_branch = if interactive {
+        core::lifecycle::check_name(&list_worktrees()?, name)?;
         let branches = core::git_ops::list_local_branches()?;
         if branches.is_empty() {
-            return Err(anyhow::anyhow!("No branches available"));
+            return Err(WorktreeError::NothingToSelect(t("error.detail.no_branches")));
         }
 
         let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select base branch")
+            .with_prompt(t("create.select_base"))
             .items(&branches)
             .default(0)
             .interact()?;
@@ -259,174 +988,269 @@ fn create_command_handler(name: &str, branch: Option<&str>, path: Option<&str>,
         branch.map(|b| b.to_string())
     };
 
-    // 创建 worktree
-    if let Some(base) = base_branch {
-        // 基于现有分支创建
-        if !branch_exists(&base) {
-            return Err(anyhow::anyhow!("Branch '{}' does not exist", base));
-        }
-
-        match create_worktree(&base, &worktree_path) {
-            Ok(_) => {
-                // T021: 显示成功消息，包含目录名和分支名
-                if dirname == name {
-                    // 无转换（分支名无斜杠）
-                    println!("{} {} from branch {}",
-                        "Created worktree".green().bold(),
-                        dirname.cyan().bold(),
-                        base.yellow()
-                    );
-                } else {
-                    // 有转换（分支名有斜杠）
-                    println!("{} {} (directory: {}) from branch {}",
-                        "Created worktree".green().bold(),
-                        name.cyan().bold(),
-                        dirname.cyan().dimmed(),
-                        base.yellow()
-                    );
-                }
-            }
-            Err(e) => {
-                // T020: 处理 DirNameConflict 错误
-                if let crate::utils::errors::WorktreeError::DirNameConflict { dirname: conflict_dirname, existing_branch } = e {
-                    eprintln!("{}", "Error: Cannot create worktree - directory name conflict".red().bold());
-                    eprintln!();
-                    eprintln!("The branch '{}' would create directory '{}',", name, conflict_dirname);
-                    eprintln!("which conflicts with existing worktree for branch '{}'.", existing_branch);
-                    eprintln!();
-                    eprintln!("{}", "Suggested solutions:".yellow().bold());
-                    eprintln!("  1. Use a different branch name");
-                    eprintln!("  2. Delete the existing worktree with: work delete {}", conflict_dirname);
-                    return Err(anyhow::anyhow!("Directory name conflict"));
-                }
-                return Err(e.into());
-            }
-        }
-    } else {
-        // 创建新分支
-        let upstream = branch.map(|b| b.to_string());
-        match create_worktree_with_new_branch(name, &worktree_path, upstream.as_deref()) {
-            Ok(_) => {
-                // T021: 显示成功消息
-                if dirname == name {
-                    println!("{} {} with new branch",
-                        "Created worktree".green().bold(),
-                        dirname.cyan().bold()
-                    );
-                } else {
-                    println!("{} {} (directory: {}) with new branch",
-                        "Created worktree".green().bold(),
-                        name.cyan().bold(),
-                        dirname.cyan().dimmed()
-                    );
-                }
-            }
-            Err(e) => {
-                // T020: 处理错误
-                if let crate::utils::errors::WorktreeError::DirNameConflict { dirname: conflict_dirname, existing_branch } = e {
-                    eprintln!("{}", "Error: Cannot create worktree - directory name conflict".red().bold());
-                    eprintln!();
-                    eprintln!("The branch '{}' would create directory '{}',", name, conflict_dirname);
-                    eprintln!("which conflicts with existing worktree for branch '{}'.", existing_branch);
-                    eprintln!();
-                    eprintln!("{}", "Suggested solutions:".yellow().bold());
-                    eprintln!("  1. Use a different branch name");
-                    eprintln!("  2. Delete the existing worktree with: work delete {}", conflict_dirname);
-                    return Err(anyhow::anyhow!("Directory name conflict"));
-                }
-                return Err(e.into());
-            }
-        }
+    let source = match (detach, base_branch.as_deref()) {
+        (Some(rev), _) => core::lifecycle::Source::Detached(rev),
+        (None, Some(base)) => core::lifecycle::Source::Branch(base),
+        (None, None) => core::lifecycle::Source::NewBranch { upstream: None },
+    };
+    // T020: DirNameConflict 等错误由 report_error 统一输出（含修复建议）
+    let created = core::lifecycle::create(name, source, path)?;
+    let dirname = &created.dirname;
+    let worktree_path = &created.path;
+
+    // T021: 显示成功消息，分支名含斜杠时同时显示转换后的目录名
+    let label = t("create.created").green().bold().to_string();
+    let message = match source {
+        core::lifecycle::Source::Detached(rev) => tf("create.detached", &[
+            ("label", &label),
+            ("name", &dirname.cyan().bold().to_string()),
+            ("rev", &rev.yellow().to_string()),
+        ]),
+        core::lifecycle::Source::Branch(base) if dirname == name => tf("create.from_branch", &[
+            ("label", &label),
+            ("name", &dirname.cyan().bold().to_string()),
+            ("base", &base.yellow().to_string()),
+        ]),
+        core::lifecycle::Source::Branch(base) => tf("create.from_branch_dir", &[
+            ("label", &label),
+            ("name", &name.cyan().bold().to_string()),
+            ("dirname", &dirname.cyan().dimmed().to_string()),
+            ("base", &base.yellow().to_string()),
+        ]),
+        core::lifecycle::Source::NewBranch { .. } if dirname == name => tf("create.new_branch", &[
+            ("label", &label),
+            ("name", &dirname.cyan().bold().to_string()),
+        ]),
+        core::lifecycle::Source::NewBranch { .. } => tf("create.new_branch_dir", &[
+            ("label", &label),
+            ("name", &name.cyan().bold().to_string()),
+            ("dirname", &dirname.cyan().dimmed().to_string()),
+        ]),
+    };
+    println!("{}", message);
+
+    print_setup(&created.setup);
+
+    println!("\n{}: {}", t("label.path").bold(), worktree_path.dimmed());
+    refresh_workspace_file();
+
+    if open {
+        let editor = core::editor::resolve(None);
+        println!("{}", tf("open.opening", &[("name", dirname), ("editor", &editor)]));
+        return core::editor::open(&editor, worktree_path);
     }
 
-    println!("\n{}: {}", "Path".bold(), worktree_path.dimmed());
-    println!("\n{}:", "Switch to this worktree".green());
+    println!("\n{}:", t("create.switch_hint").green());
     println!("  {}", format!("cd {}", worktree_path).dimmed());
     println!("  {}", format!("eval \"$(work switch {} --print-path)\"", name).dimmed());
 
     Ok(())
 }
 
-/// 处理 delete 命令
-fn delete_command_handler(names: &[String], force: bool, interactive: bool) -> Result<()> {
-    let worktrees = list_worktrees()?;
-
-    // 如果没有指定名称且是交互式模式，显示选择列表
-    let targets = if names.is_empty() && interactive {
-        let items: Vec<String> = worktrees.iter().map(|wt| wt.dirname.clone()).collect();
-
-        if items.is_empty() {
-            return Err(anyhow::anyhow!("No worktrees to delete"));
+/// 输出新 worktree 的共享缓存、端口和环境文件设置结果，失败只给出警告
+fn print_setup(setup: &core::lifecycle::Setup) {
+    match &setup.cache {
+        Ok(actions) => {
+            for action in actions {
+                let message = match action {
+                    core::cache::CacheAction::Wrote(path) => tf("cache.wrote", &[("path", path)]),
+                    core::cache::CacheAction::SkippedExisting(path) => tf("cache.skipped", &[("path", path)]),
+                    core::cache::CacheAction::Seeded { dir, mode } => tf("cache.seeded", &[("dir", dir), ("mode", mode.as_str())]),
+                };
+                println!("{}", message.dimmed());
+            }
+        }
+        Err(e) => eprintln!("{} {}", t("label.warning").yellow(), tf("cache.failed", &[("error", &e.to_string())])),
+    }
+
+    match &setup.env {
+        Ok(Some(env)) => {
+            if let (Some(port), Some(last)) = (env.port, env.last_port()) {
+                println!("{}", tf("env.allocated", &[("port", &port.to_string()), ("last", &last.to_string())]).dimmed());
+            }
+            if env.written {
+                println!("{}", tf("env.wrote", &[("path", core::env::ENV_FILE)]).dimmed());
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("{} {}", t("label.warning").yellow(), tf("env.failed", &[("error", &e.to_string())])),
+    }
+
+    match &setup.loader {
+        Ok(Some(loader)) => {
+            let file = loader.loader.file();
+            let message = match (loader.written, loader.allowed) {
+                (false, _) => tf("cache.skipped", &[("path", file)]),
+                (true, true) => tf("env.loader_allowed", &[("path", file), ("program", loader.loader.program())]),
+                (true, false) => tf("env.wrote", &[("path", file)]),
+            };
+            println!("{}", message.dimmed());
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("{} {}", t("label.warning").yellow(), tf("env.failed", &[("error", &e.to_string())])),
+    }
+}
+
+/// 删除 worktree 后处理同名的终端复用器会话：--kill-session 直接结束，终端中询问，否则只提示
+fn handle_worktree_session(dirname: &str, kill_session: bool) -> Result<()> {
+    use std::io::IsTerminal;
+
+    // 未配置或配置无效时不影响删除
+    let Ok(mux) = core::session::Multiplexer::from_config() else {
+        return Ok(());
+    };
+    let session = core::session::session_name(dirname);
+    if !core::session::exists(mux, &session) {
+        return Ok(());
+    }
+
+    let kill = kill_session
+        || (std::io::stdin().is_terminal()
+            && Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(tf("session.confirm_kill", &[("name", &session)]))
+                .default(true)
+                .interact()?);
+
+    if kill {
+        core::session::kill(mux, &session)?;
+        println!("{}", tf("session.killed", &[("name", &session)]));
+    } else {
+        println!("{}", tf("session.still_running", &[("name", &session)]));
+    }
+
+    Ok(())
+}
+
+/// 启用 workspace.auto 时重新导出工作区文件，失败只给出警告
+fn refresh_workspace_file() {
+    if let Err(e) = core::workspace::refresh_if_enabled() {
+        eprintln!("{} {}", t("label.warning").yellow(), tf("workspace.refresh_failed", &[("error", &e.to_string())]));
+    }
+}
+
+/// 处理 delete 命令
+fn delete_command_handler(
+    names: &[String],
+    force: bool,
+    interactive: bool,
+    kill_session: bool,
+    snapshot: bool,
+    no_trash: bool,
+) -> Result<()> {
+    use std::io::IsTerminal;
+
+    let worktrees = list_worktrees()?;
+
+    // 如果没有指定名称且是交互式模式，显示选择列表
+    let targets = if names.is_empty() && interactive {
+        let items: Vec<String> = worktrees.iter().filter(|wt| !wt.is_bare).map(|wt| wt.dirname.clone()).collect();
+
+        if items.is_empty() {
+            return Err(WorktreeError::NothingToSelect(t("error.detail.nothing_to_delete")));
         }
 
         let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select worktree to delete")
+            .with_prompt(t("delete.select"))
             .items(&items)
             .interact()?;
 
         vec![items[selection].clone()]
     } else if names.is_empty() {
-        return Err(anyhow::anyhow!("No worktree names provided. Use --interactive or specify names"));
+        return Err(WorktreeError::InvalidArgument(t("error.detail.no_names")));
     } else {
         names.to_vec()
     };
 
-    // 删除每个指定的 worktree
-    for name in &targets {
-        let worktree = worktrees
-            .iter()
-            .find(|wt| wt.dirname == *name)
-            .ok_or_else(|| anyhow::anyhow!("Worktree '{}' not found", name))?;
+    // 非交互模式下因未提交更改而跳过的 worktree
+    let mut skipped_dirty = Vec::new();
+    let mut deleted_any = false;
 
-        // 检查是否为当前 worktree
-        if worktree.is_current {
-            return Err(anyhow::anyhow!("Cannot delete current worktree '{}'. Switch to another worktree first.", name));
-        }
+    // 删除每个指定的 worktree
+    for target in &targets {
+        let worktree = Worktree::resolve(&worktrees, target)?;
+        // 参数可能是别名或分支名，之后的提示、会话名和错误都使用目录名
+        let name = &worktree.dirname;
+        // 不能删除当前 worktree；进行中的合并、变基等会随 worktree 一起丢失
+        core::lifecycle::ensure_deletable(worktree, force)?;
 
         // 检查未提交的更改
         if !force && worktree.has_uncommitted_changes() {
-            println!("Worktree '{}' has uncommitted changes:", name);
+            println!("{}", tf("delete.dirty", &[("name", name)]));
 
             if !interactive || !Confirm::with_theme(&ColorfulTheme::default())
-                .with_prompt("Delete anyway?")
+                .with_prompt(t("delete.confirm_dirty"))
                 .default(false)
                 .interact()?
             {
-                println!("Skipped '{}'", name);
+                println!("{}", tf("delete.skipped", &[("name", name)]));
+                // 交互式回答“否”是有意取消，不算失败
+                if !interactive {
+                    skipped_dirty.push(name.clone());
+                }
                 continue;
             }
         }
 
         // 确认删除
         if interactive && !Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(&format!("Delete worktree '{}'?", name))
+            .with_prompt(tf("delete.confirm", &[("name", name)]))
             .default(false)
             .interact()?
         {
-            println!("Cancelled deletion of '{}'", name);
+            println!("{}", tf("delete.cancelled", &[("name", name)]));
             continue;
         }
 
+        // 有未提交更改时先保存快照：--snapshot 直接保存，否则在终端中询问（回收站中的副本过期后会被清除，快照会一直保留）
+        if worktree.has_uncommitted_changes()
+            && (snapshot
+                || (std::io::stdin().is_terminal()
+                    && Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(tf("delete.confirm_snapshot", &[("name", name)]))
+                        .default(true)
+                        .interact()?))
+        {
+            save_snapshot(worktree, None)?;
+        }
+
         // 执行删除
-        delete_worktree(&worktree.path, force)?;
-        println!("{} {}", "Deleted worktree".red().bold(), name.cyan());
+        core::lifecycle::delete(worktree, force, !no_trash)?;
+        println!("{} {}", t("delete.deleted").red().bold(), name.cyan());
+        deleted_any = true;
+
+        handle_worktree_session(name, kill_session)?;
+    }
+
+    if deleted_any {
+        refresh_workspace_file();
+    }
+
+    // 非交互模式下跳过的脏 worktree 以错误退出，便于脚本识别
+    if !skipped_dirty.is_empty() {
+        return Err(WorktreeError::UncommittedChanges(skipped_dirty.join(", ")));
     }
 
     Ok(())
 }
 
 /// 处理 info 命令
-fn info_command_handler(name: &str, output_format: &str) -> Result<()> {
+fn info_command_handler(name: &str, output_format: &str, template: Option<&str>, porcelain: Option<&str>) -> Result<()> {
     let worktrees = list_worktrees()?;
 
-    let worktree = worktrees
-        .iter()
-        .find(|wt| wt.dirname == name)
-        .ok_or_else(|| anyhow::anyhow!("Worktree '{}' not found", name))?;
+    let worktree = Worktree::resolve(&worktrees, name)?;
 
-    let format = OutputFormat::from_str(output_format);
+    let format = resolve_output_format(output_format, template, porcelain)?;
 
     match format {
+        OutputFormat::Template(source) => {
+            print_template_output(std::slice::from_ref(worktree), &source)?;
+        }
+        OutputFormat::Ndjson => {
+            print_lines(&schema::format_worktree_ndjson(std::slice::from_ref(worktree)));
+        }
+        OutputFormat::Porcelain => {
+            print!("{}", schema::format_worktree_porcelain_v1(std::slice::from_ref(worktree)));
+        }
         OutputFormat::Json => {
             // T034: 输出 JSON 格式，包含 directory 和 branch 字段
             let json_output = format_worktree_json(vec![worktree.clone()]);
@@ -434,39 +1258,64 @@ fn info_command_handler(name: &str, output_format: &str) -> Result<()> {
         }
         _ => {
             // 输出带颜色的基本信息
-            println!("{}: {}", "Worktree".bold().green(), worktree.dirname.cyan().bold());
-            println!("  {}: {}", "Branch".bold(), worktree.branch_name.yellow());
-            println!("  {}: {}", "Path".bold(), worktree.path.dimmed());
-            println!("  {}: {}", "HEAD".bold(), worktree.head_commit.as_ref().unwrap_or(&"N/A".to_string()).dimmed());
-            println!("  {}: {}", "Current".bold(), if worktree.is_current { "Yes".green() } else { "No".dimmed() });
-            println!("  {}: {}", "Detached".bold(), if worktree.is_detached { "Yes".yellow() } else { "No".dimmed() });
+            let (yes, no) = (t("label.yes"), t("label.no"));
+            println!("{}: {}", t("info.worktree").bold().green(), worktree.dirname.cyan().bold());
+            if worktree.is_bare {
+                // 裸仓库没有检出，只显示类型和路径
+                println!("  {}: {}", t("info.kind").bold(), t("info.bare").dimmed());
+                println!("  {}: {}", t("label.path").bold(), worktree.path.dimmed());
+                return Ok(());
+            }
+            println!("  {}: {}", t("info.branch").bold(), worktree.branch_name.yellow());
+            println!("  {}: {}", t("label.path").bold(), worktree.path.dimmed());
+            println!("  {}: {}", t("info.head").bold(), worktree.head_commit.clone().unwrap_or_else(|| t("label.na")).dimmed());
+            println!("  {}: {}", t("info.current").bold(), if worktree.is_current { yes.green() } else { no.dimmed() });
+            println!("  {}: {}", t("info.detached").bold(), if worktree.is_detached { yes.yellow() } else { no.dimmed() });
+            if let Some(operation) = &worktree.operation {
+                println!("  {}: {}", t("info.operation").bold(), operation.marker().red().bold());
+            }
+            if worktree.is_locked {
+                let reason = worktree.lock_reason.clone().unwrap_or_else(|| yes.clone());
+                println!("  {}: {}", t("info.lock_reason").bold(), reason.yellow());
+            }
+            if worktree.is_prunable {
+                let reason = worktree.prune_reason.clone().unwrap_or_else(|| yes.clone());
+                println!("  {}: {}", t("info.prune_reason").bold(), reason.red());
+            }
             if let Some(upstream) = &worktree.upstream_branch {
-                println!("  {}: {}", "Upstream".bold(), upstream.cyan());
+                println!("  {}: {}", t("info.upstream").bold(), upstream.cyan());
             }
-            println!("  {}: {}", "Last Modified".bold(), worktree.last_modified.format("%Y-%m-%d %H:%M:%S").to_string().dimmed());
+            println!("  {}: {}", t("info.last_modified").bold(), worktree.last_modified.format("%Y-%m-%d %H:%M:%S").to_string().dimmed());
 
             // 显示未提交的更改
             let path = Path::new(&worktree.path);
             if let Ok(status) = get_worktree_status(path) {
-                if !status.modified.is_empty() || !status.staged.is_empty() || !status.untracked.is_empty() {
-                    println!("\n{}:", "Uncommitted Changes".red().bold());
+                if !status.is_clean() {
+                    println!("\n{}:", t("info.uncommitted").red().bold());
+
+                    if !status.conflicted.is_empty() {
+                        println!("  {}:", t("info.conflicted").red());
+                        for file in &status.conflicted {
+                            println!("    {} {}", "U".red(), file.dimmed());
+                        }
+                    }
 
                     if !status.staged.is_empty() {
-                        println!("  {}:", "Staged".green());
+                        println!("  {}:", t("info.staged").green());
                         for file in &status.staged {
                             println!("    {} {}", "✓".green(), file.dimmed());
                         }
                     }
 
                     if !status.modified.is_empty() {
-                        println!("  {}:", "Modified".yellow());
+                        println!("  {}:", t("info.modified").yellow());
                         for file in &status.modified {
                             println!("    {} {}", "M".yellow(), file.dimmed());
                         }
                     }
 
                     if !status.untracked.is_empty() {
-                        println!("  {}:", "Untracked".dimmed());
+                        println!("  {}:", t("info.untracked").dimmed());
                         for file in &status.untracked {
                             println!("    {} {}", "?".dimmed(), file.dimmed());
                         }
@@ -481,15 +1330,27 @@ fn info_command_handler(name: &str, output_format: &str) -> Result<()> {
 
 /// 处理 prune 命令
 fn prune_command_handler(dry_run: bool) -> Result<()> {
-    let pruned = prune_worktrees(dry_run)?;
+    // 目录已不存在的 worktree，清理后逐个记录到操作日志
+    let missing: Vec<_> = list_worktrees()?
+        .into_iter()
+        .filter(|wt| !Path::new(&wt.path).exists())
+        .collect();
+
+    let result = prune_worktrees(dry_run);
+    if !dry_run {
+        for wt in &missing {
+            core::oplog::record_outcome(core::oplog::Operation::for_worktree(core::oplog::OpKind::Prune, wt), &result);
+        }
+    }
+    let pruned = result?;
 
     if pruned.is_empty() {
-        println!("{}", "没有需要清理的无效 worktree".dimmed());
+        println!("{}", t("prune.nothing").dimmed());
     } else {
         if dry_run {
-            println!("{}:", "预览模式 - 将要清理的无效 worktree".yellow());
+            println!("{}:", t("prune.preview").yellow());
         } else {
-            println!("{}:", "已清理以下无效 worktree".green());
+            println!("{}:", t("prune.done").green());
         }
 
         for item in &pruned {
@@ -499,3 +1360,763 @@ fn prune_command_handler(dry_run: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// 处理 open 命令
+fn open_command_handler(name: &str, editor: Option<&str>) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let worktree = Worktree::resolve(&worktrees, name)?;
+    worktree.ensure_checkout()?;
+
+    let editor = core::editor::resolve(editor);
+    println!("{}", tf("open.opening", &[("name", &worktree.dirname), ("editor", &editor)]));
+    core::editor::open(&editor, &worktree.path)
+}
+
+/// 处理 scratch 命令
+fn scratch_command_handler(rev: &str, ttl: Option<&str>, print_path: bool) -> Result<()> {
+    let ttl = match ttl {
+        Some(ttl) => ttl.to_string(),
+        None => core::config::get("scratch.ttl").unwrap_or_else(|| core::scratch::DEFAULT_TTL.to_string()),
+    };
+    let ttl = core::scratch::parse_ttl(&ttl)?;
+
+    let sha = core::git_ops::resolve_revision(rev)?;
+    let dirname = core::scratch::scratch_name(&sha[..sha.len().min(8)], &list_worktrees()?);
+    let created = core::lifecycle::create(&dirname, core::lifecycle::Source::Detached(&sha), None)?;
+    let worktree_path = &created.path;
+
+    // 以 git 记录的路径为键，与 list_worktrees 的结果一致；保留创建时写入的端口等属性
+    let recorded_path = list_worktrees()?
+        .into_iter()
+        .find(|wt| wt.dirname == dirname)
+        .map(|wt| wt.path)
+        .unwrap_or_else(|| worktree_path.clone());
+    let now = chrono::Utc::now();
+    let expires_at = now + ttl;
+    core::metadata::update(|metadata| {
+        let meta = metadata.worktrees.entry(recorded_path).or_default();
+        meta.temporary = true;
+        meta.expires_at = Some(expires_at);
+        meta.created_at = Some(now);
+    })?;
+    refresh_workspace_file();
+
+    if print_path {
+        // 标准输出只留路径，附带设置的失败仍然提示
+        for e in created.setup.errors() {
+            eprintln!("{} {}", t("label.warning").yellow(), e);
+        }
+        println!("{}", worktree_path);
+        return Ok(());
+    }
+
+    println!("{}", tf("scratch.created", &[
+        ("name", &dirname.cyan().bold().to_string()),
+        ("rev", &rev.yellow().to_string()),
+        ("expires", &expires_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()),
+    ]));
+    print_setup(&created.setup);
+    println!("\n{}: {}", t("label.path").bold(), worktree_path.dimmed());
+
+    Ok(())
+}
+
+/// 处理 clean 命令
+fn clean_command_handler(expired: bool, dry_run: bool, force: bool) -> Result<()> {
+    if !expired {
+        return Err(WorktreeError::InvalidArgument(t("error.detail.clean_nothing_selected")));
+    }
+
+    let now = chrono::Utc::now();
+    let worktrees = list_worktrees()?;
+    let targets: Vec<_> = worktrees.iter().filter(|wt| wt.is_expired(now)).collect();
+
+    if targets.is_empty() {
+        println!("{}", t("clean.nothing"));
+        return Ok(());
+    }
+
+    let mut skipped_dirty = Vec::new();
+    let mut skipped_busy = Vec::new();
+    for wt in targets {
+        if wt.is_current {
+            println!("{}", tf("clean.skipped_current", &[("name", &wt.dirname)]));
+            continue;
+        }
+        if wt.ensure_idle(force).is_err() {
+            let operation = wt.operation.as_ref().map(|operation| operation.marker()).unwrap_or_default();
+            println!("{}", tf("clean.skipped_operation", &[("name", &wt.dirname), ("operation", &operation)]));
+            skipped_busy.push(wt.dirname.clone());
+            continue;
+        }
+        if !force && wt.has_uncommitted_changes() {
+            println!("{}", tf("delete.skipped", &[("name", &wt.dirname)]));
+            skipped_dirty.push(wt.dirname.clone());
+            continue;
+        }
+
+        if dry_run {
+            println!("{}", tf("clean.would_remove", &[("name", &wt.dirname)]));
+        } else {
+            core::lifecycle::delete(wt, force, true)?;
+            println!("{} {}", t("delete.deleted").red().bold(), wt.dirname.cyan());
+        }
+    }
+
+    if !dry_run {
+        refresh_workspace_file();
+    }
+
+    if !skipped_busy.is_empty() {
+        return Err(WorktreeError::OperationInProgress(skipped_busy.join(", ")));
+    }
+    if !skipped_dirty.is_empty() {
+        return Err(WorktreeError::UncommittedChanges(skipped_dirty.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// 处理 promote 命令
+fn promote_command_handler(base: Option<&str>, path: Option<&str>) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let current = worktrees
+        .iter()
+        .find(|wt| wt.is_current)
+        .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.not_in_worktree")))?;
+
+    let default_branch = match base {
+        Some(base) => base.to_string(),
+        None => core::repository::Repository::detect_default_branch(Path::new(&current.path))?,
+    };
+
+    let dirname = core::git_ops::branch_to_dirname(&current.branch_name);
+    core::git_ops::validate_dirname(&dirname)?;
+    if worktrees.iter().any(|wt| wt.dirname == dirname && !wt.is_current) {
+        return Err(WorktreeError::AlreadyExists(dirname));
+    }
+    let worktree_path = match path {
+        Some(path) => path.to_string(),
+        None => core::git_ops::default_worktree_path(&dirname)?,
+    };
+
+    let result = core::promote::promote(current, &default_branch, &worktree_path);
+    core::oplog::record_outcome(
+        core::oplog::Operation::for_worktree(core::oplog::OpKind::Promote, current)
+            .with_target(&worktree_path)
+            .with_head_after(Path::new(&current.path)),
+        &result,
+    );
+    let carried = result?;
+
+    println!("{}", tf("promote.done", &[
+        ("branch", &current.branch_name.cyan().bold().to_string()),
+        ("path", &worktree_path),
+    ]));
+    if carried {
+        println!("{}", t("promote.carried"));
+    }
+    println!("{}", tf("promote.now_on", &[("branch", &default_branch.yellow().to_string())]));
+    refresh_workspace_file();
+
+    println!("\n{}:", t("create.switch_hint").green());
+    println!("  {}", format!("cd {}", worktree_path).dimmed());
+
+    Ok(())
+}
+
+/// 处理 carry 命令
+fn carry_command_handler(from: &str, to: &str, paths: &[String], copy: bool) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let (source, target) = (Worktree::resolve(&worktrees, from)?, Worktree::resolve(&worktrees, to)?);
+    source.ensure_checkout()?;
+    target.ensure_checkout()?;
+
+    let result = core::carry::carry(source, target, paths, copy);
+    core::oplog::record_outcome(
+        core::oplog::Operation::for_worktree(core::oplog::OpKind::Carry, source).with_target(&target.path),
+        &result,
+    );
+    let files = result?;
+
+    for file in &files {
+        println!("  {}", file.dimmed());
+    }
+    let key = if copy { "carry.copied" } else { "carry.moved" };
+    println!("{}", tf(key, &[
+        ("count", &files.len().to_string()),
+        ("from", &source.dirname.cyan().to_string()),
+        ("to", &target.dirname.cyan().to_string()),
+    ]));
+
+    Ok(())
+}
+
+/// 保存 worktree 快照并输出归档路径
+fn save_snapshot(worktree: &core::worktree::Worktree, output: Option<&str>) -> Result<PathBuf> {
+    let archive = match output {
+        Some(output) => PathBuf::from(output),
+        None => core::snapshot::default_archive_path(&worktree.dirname, chrono::Utc::now())?,
+    };
+    let manifest = core::snapshot::create(worktree, &archive)?;
+
+    println!("{}", tf("snapshot.saved", &[
+        ("name", &worktree.dirname.cyan().bold().to_string()),
+        ("count", &manifest.untracked.len().to_string()),
+    ]));
+    println!("{}: {}", t("label.path").bold(), archive.display().to_string().dimmed());
+
+    Ok(archive)
+}
+
+/// 处理 snapshot 命令
+fn snapshot_command_handler(name: Option<&str>, output: Option<&str>) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let worktree = match name {
+        Some(name) => Worktree::resolve(&worktrees, name)?,
+        None => worktrees
+            .iter()
+            .find(|wt| wt.is_current)
+            .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.not_in_worktree_name")))?,
+    };
+    worktree.ensure_checkout()?;
+
+    save_snapshot(worktree, output)?;
+    Ok(())
+}
+
+/// 处理 restore 命令
+fn restore_command_handler(archive: &str, as_name: Option<&str>, path: Option<&str>) -> Result<()> {
+    let archive = core::snapshot::resolve_archive(archive)?;
+    let (dirname, worktree_path) = restore_worktree(&archive, as_name, path)?;
+    core::oplog::record(&core::oplog::Operation::create(&dirname, &worktree_path, None, false));
+    Ok(())
+}
+
+/// 从快照归档重新创建 worktree 并输出结果，返回目录名和路径
+fn restore_worktree(archive: &Path, as_name: Option<&str>, path: Option<&str>) -> Result<(String, String)> {
+    let manifest = core::snapshot::read_manifest(archive)?;
+
+    let dirname = as_name.unwrap_or(&manifest.dirname).to_string();
+    core::git_ops::validate_dirname(&dirname)?;
+    let worktrees = list_worktrees()?;
+    if worktrees.iter().any(|wt| wt.dirname == dirname) {
+        return Err(WorktreeError::AlreadyExists(dirname));
+    }
+    let worktree_path = match path {
+        Some(path) => path.to_string(),
+        None => core::git_ops::default_worktree_path(&dirname)?,
+    };
+
+    let restored = core::snapshot::restore(archive, &std::env::current_dir()?, &worktree_path, &worktrees)?;
+    refresh_workspace_file();
+
+    println!("{}", tf("restore.restored", &[
+        ("name", &dirname.cyan().bold().to_string()),
+        ("head", &restored.manifest.head[..restored.manifest.head.len().min(8)].yellow().to_string()),
+    ]));
+    if restored.detached_fallback {
+        if let Some(branch) = &restored.manifest.branch {
+            println!("{} {}", t("label.warning").yellow(), tf("restore.detached", &[("branch", branch)]));
+        }
+    }
+    println!("\n{}: {}", t("label.path").bold(), worktree_path.dimmed());
+
+    Ok((dirname, worktree_path))
+}
+
+/// 处理 du 命令
+fn du_command_handler(
+    names: &[String],
+    sort: &str,
+    top: usize,
+    output_format: &str,
+    clean_ignored: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let selected: Vec<_> = if names.is_empty() {
+        worktrees.iter().filter(|wt| !wt.is_bare && Path::new(&wt.path).is_dir()).collect()
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                let worktree = Worktree::resolve(&worktrees, name)?;
+                worktree.ensure_directory()?;
+                Ok(worktree)
+            })
+            .collect::<Result<_>>()?
+    };
+
+    if let Some(pattern) = clean_ignored {
+        return du_clean_ignored(&selected, pattern, dry_run);
+    }
+
+    let mut usages = core::du::measure_all(&selected, top)?;
+    core::du::sort(&mut usages, core::du::SortKey::parse(sort).unwrap_or(core::du::SortKey::Disk));
+
+    if output_format == "json" {
+        let json = serde_json::to_string_pretty(&usages).map_err(|e| WorktreeError::InvalidArgument(e.to_string()))?;
+        println!("{}", json);
+    } else {
+        println!("{}", format_du_table(&usages));
+    }
+
+    Ok(())
+}
+
+/// 删除所选 worktree 中匹配 pattern 的被忽略内容
+fn du_clean_ignored(worktrees: &[&core::worktree::Worktree], pattern: &str, dry_run: bool) -> Result<()> {
+    let mut freed = 0;
+    let mut matched_any = false;
+
+    for wt in worktrees {
+        let root = Path::new(&wt.path);
+        for relative in core::du::matching_ignored(root, pattern)? {
+            matched_any = true;
+            let display = format!("{}/{}", wt.dirname, relative);
+            if dry_run {
+                println!("{}", tf("du.would_remove", &[("path", &display)]));
+                continue;
+            }
+
+            let result = core::du::remove_ignored(root, &relative);
+            core::oplog::record_outcome(
+                core::oplog::Operation::for_worktree(core::oplog::OpKind::Purge, wt).with_target(&relative),
+                &result,
+            );
+            let size = result?;
+            freed += size.disk;
+            println!("{}", tf("du.removed", &[
+                ("path", &display.cyan().to_string()),
+                ("size", &core::du::format_size(size.disk)),
+            ]));
+        }
+    }
+
+    if !matched_any {
+        println!("{}", tf("du.nothing_matched", &[("pattern", pattern)]));
+    } else if !dry_run {
+        println!("{}", tf("du.freed", &[("size", &core::du::format_size(freed).green().bold().to_string())]));
+    }
+
+    Ok(())
+}
+
+/// 处理 trash list 命令
+fn trash_list_command_handler() -> Result<()> {
+    let entries = core::trash::list()?;
+    if entries.is_empty() {
+        println!("{}", t("trash.empty"));
+        return Ok(());
+    }
+
+    let retention = core::trash::retention()?;
+    let local = |time: chrono::DateTime<chrono::Utc>| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    for entry in entries.iter().rev() {
+        let branch = entry.branch.clone().unwrap_or_else(|| entry.head[..entry.head.len().min(8)].to_string());
+        println!("{}", tf("trash.entry", &[
+            ("id", &entry.id.cyan().bold().to_string()),
+            ("branch", &branch.yellow().to_string()),
+            ("deleted", &local(entry.deleted_at)),
+            ("expires", &local(entry.expires_at(retention)).dimmed().to_string()),
+        ]));
+    }
+
+    Ok(())
+}
+
+/// 处理 trash restore 命令
+fn trash_restore_command_handler(id: &str, as_name: Option<&str>, path: Option<&str>) -> Result<()> {
+    let entry = core::trash::find(id)?;
+    let (dirname, worktree_path) = restore_worktree(&core::trash::archive_path(&entry.id)?, as_name, path)?;
+    core::trash::remove(&entry.id)?;
+    core::oplog::record(&core::oplog::Operation::create(&dirname, &worktree_path, None, false));
+    Ok(())
+}
+
+/// 处理 trash empty 命令
+fn trash_empty_command_handler(expired: bool) -> Result<()> {
+    let before = if expired {
+        Some(chrono::Utc::now() - core::trash::retention()?)
+    } else {
+        None
+    };
+
+    let removed = core::trash::purge(before)?;
+    println!("{}", tf("trash.purged", &[("count", &removed.len().to_string())]));
+    Ok(())
+}
+
+/// 处理 undo 命令：撤销最近一次 create / delete
+fn undo_command_handler(dry_run: bool, force: bool) -> Result<()> {
+    let ops = core::oplog::read_all()?;
+    let op = core::oplog::last_undoable(&ops)
+        .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.nothing_to_undo")))?;
+    let time = op.time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+
+    let key = match op.kind {
+        core::oplog::OpKind::Delete => "undo.delete",
+        _ => "undo.create",
+    };
+    println!("{}", tf(key, &[("name", &op.dirname.cyan().bold().to_string()), ("time", &time)]));
+    if dry_run {
+        return Ok(());
+    }
+
+    let result = (|| -> Result<()> {
+        match op.kind {
+            core::oplog::OpKind::Create => {
+                let worktrees = list_worktrees()?;
+                let worktree = worktrees
+                    .iter()
+                    .find(|wt| wt.path == op.path)
+                    .or_else(|| worktrees.iter().find(|wt| wt.dirname == op.dirname))
+                    .ok_or_else(|| WorktreeError::NotFound(op.dirname.clone()))?;
+                core::lifecycle::ensure_deletable(worktree, force)?;
+
+                // 未提交的更改会保存在回收站中，因此可以强制删除
+                if let Some(entry) = core::trash::delete(worktree, true, true)? {
+                    println!("{}", tf("undo.trashed", &[("id", &entry.id)]));
+                }
+                if let Some(branch) = op.branch.as_deref().filter(|_| op.created_branch) {
+                    // 只删除已合并的分支，新分支上有提交时保留
+                    match core::git_ops::delete_merged_branch(branch) {
+                        Ok(_) => println!("{}", tf("undo.branch_deleted", &[("branch", branch)])),
+                        Err(e) => eprintln!("{} {}", t("label.warning").yellow(), tf("undo.branch_kept", &[("branch", branch), ("error", &e.to_string())])),
+                    }
+                }
+                refresh_workspace_file();
+            }
+            core::oplog::OpKind::Delete => {
+                let id = op
+                    .trash_id
+                    .as_deref()
+                    .ok_or_else(|| WorktreeError::NotFound(tf("error.detail.deleted_without_trash", &[("name", &op.dirname)])))?;
+                let entry = core::trash::find(id)?;
+                // 原路径已被占用时使用默认路径
+                let path = (!Path::new(&op.path).exists()).then_some(op.path.as_str());
+                restore_worktree(&core::trash::archive_path(&entry.id)?, Some(&op.dirname), path)?;
+                core::trash::remove(&entry.id)?;
+            }
+            _ => unreachable!("last_undoable only returns create and delete operations"),
+        }
+        Ok(())
+    })();
+
+    core::oplog::record_outcome(core::oplog::Operation::undo(op), &result);
+    result
+}
+
+/// 处理 log 命令
+fn log_command_handler(since: Option<&str>, limit: Option<usize>, output_format: &str) -> Result<()> {
+    let since = since.map(|s| core::oplog::parse_since(s, chrono::Utc::now())).transpose()?;
+
+    let ops: Vec<_> = core::oplog::read_all()?
+        .into_iter()
+        .rev()
+        .filter(|op| since.is_none_or(|since| op.time >= since))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    let serialize_error = |e: serde_json::Error| WorktreeError::InvalidArgument(e.to_string());
+    match output_format {
+        "json" => println!("{}", serde_json::to_string_pretty(&ops).map_err(serialize_error)?),
+        "ndjson" => {
+            for op in &ops {
+                println!("{}", serde_json::to_string(op).map_err(serialize_error)?);
+            }
+        }
+        _ if ops.is_empty() => println!("{}", t("log.empty").dimmed()),
+        _ => println!("{}", format_oplog_table(&ops)),
+    }
+
+    Ok(())
+}
+
+/// 处理 session 命令
+fn session_command_handler(name: Option<&str>, multiplexer: Option<&str>) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let worktree = match name {
+        Some(name) => Worktree::resolve(&worktrees, name)?,
+        None => worktrees
+            .iter()
+            .find(|wt| wt.is_current)
+            .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.not_in_worktree_name")))?,
+    };
+    worktree.ensure_checkout()?;
+
+    let mux = match multiplexer.and_then(core::session::Multiplexer::parse) {
+        Some(mux) => mux,
+        None => core::session::Multiplexer::from_config()?,
+    };
+    let session = core::session::session_name(&worktree.dirname);
+    let layout = core::session::layout_from_config();
+
+    if core::session::attach_or_create(mux, &session, &worktree.path, &layout)? {
+        // 会话创建后立即连接，消息在用户离开会话后才会看到
+        eprintln!("{}", tf("session.created", &[("name", &session), ("multiplexer", mux.program())]));
+    }
+
+    Ok(())
+}
+
+/// 处理 env 命令
+fn env_command_handler(name: Option<&str>, shell: bool) -> Result<()> {
+    let worktrees = list_worktrees()?;
+    let worktree = match name {
+        Some(name) => Worktree::resolve(&worktrees, name)?,
+        None => worktrees
+            .iter()
+            .find(|wt| wt.is_current)
+            .ok_or_else(|| WorktreeError::NothingToSelect(t("error.detail.not_in_worktree_name")))?,
+    };
+    worktree.ensure_checkout()?;
+
+    let path = Path::new(&worktree.path);
+    let vars = if shell {
+        let branch = if worktree.is_detached { "" } else { &worktree.branch_name };
+        core::env::worktree_vars(path, &worktree.dirname, branch)?
+    } else {
+        core::env::load(path)?
+    };
+    if vars.is_empty() {
+        eprintln!("{}", tf("env.none", &[("name", &worktree.dirname), ("file", core::env::ENV_FILE)]));
+        return Ok(());
+    }
+
+    for (key, value) in vars {
+        println!("export {}={}", key, core::env::shell_quote(&value));
+    }
+
+    Ok(())
+}
+
+/// 处理 ws create 命令
+fn ws_create_command_handler(file: Option<&Path>, branch: &str, base: Option<&str>) -> Result<()> {
+    let multi = core::multi_repo::MultiRepo::load(file)?;
+    report_ws_outcomes(multi.create(branch, base), "ws.created")
+}
+
+/// 处理 ws list 命令
+fn ws_list_command_handler(file: Option<&Path>, output_format: &str) -> Result<()> {
+    let multi = core::multi_repo::MultiRepo::load(file)?;
+    let worktrees = multi.list()?;
+
+    if output_format == "json" {
+        println!("{}", serde_json::to_string_pretty(&worktrees).map_err(|e| WorktreeError::InvalidArgument(e.to_string()))?);
+    } else {
+        println!("{}", format_ws_table(&worktrees));
+    }
+    Ok(())
+}
+
+/// 处理 ws switch 命令
+fn ws_switch_command_handler(file: Option<&Path>, name: &str, repo: Option<&str>, print_path: bool) -> Result<()> {
+    let multi = core::multi_repo::MultiRepo::load(file)?;
+    let found = multi.find(name)?;
+
+    let repo = match repo {
+        Some(repo) => repo.to_string(),
+        None => multi
+            .current_repo()
+            .map(|entry| entry.name.clone())
+            .unwrap_or_else(|| found[0].repo.clone()),
+    };
+    let target = found
+        .iter()
+        .find(|wt| wt.repo == repo)
+        .ok_or_else(|| WorktreeError::NotFound(tf("error.detail.name_in_repo", &[("name", name), ("repo", &repo)])))?;
+
+    if print_path {
+        println!("{}", target.worktree.path);
+    } else {
+        for wt in &found {
+            println!("{:<16} {}", wt.repo.yellow(), wt.worktree.path);
+        }
+        println!("{}", tf("ws.switch_hint", &[("name", name), ("repo", &target.repo)]));
+    }
+    Ok(())
+}
+
+/// 处理 ws delete 命令
+fn ws_delete_command_handler(file: Option<&Path>, name: &str, force: bool) -> Result<()> {
+    let multi = core::multi_repo::MultiRepo::load(file)?;
+    report_ws_outcomes(multi.delete(name, force), "ws.deleted")
+}
+
+/// 逐个仓库输出结果，有仓库失败时返回最后一个错误
+fn report_ws_outcomes(outcomes: Vec<(String, core::multi_repo::RepoOutcome)>, done_key: &str) -> Result<()> {
+    use core::multi_repo::RepoOutcome;
+
+    let mut last_error = None;
+    for (repo, outcome) in outcomes {
+        let repo = format!("{:<16}", repo).yellow().to_string();
+        match outcome {
+            RepoOutcome::Created(created) => {
+                println!("{} {}", repo, tf(done_key, &[("path", &created.path)]).green());
+                print_setup(&created.setup);
+            }
+            RepoOutcome::Done(path) => println!("{} {}", repo, tf(done_key, &[("path", &path)]).green()),
+            RepoOutcome::Skipped(reason) => println!("{} {}", repo, tf("ws.skipped", &[("reason", &reason)]).dimmed()),
+            RepoOutcome::Failed(e) => {
+                println!("{} {}", repo, e.to_string().red());
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// 处理 scan 命令
+fn scan_command_handler(dir: &Path, depth: usize, prune: bool) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(WorktreeError::InvalidPath(dir.display().to_string()));
+    }
+
+    let mut registry = core::registry::Registry::load()?;
+    let now = chrono::Utc::now();
+    let found = core::registry::scan(dir, depth);
+    for path in &found {
+        if registry.add(path, now) {
+            println!("{}", tf("registry.added", &[("path", &path.display().to_string())]).green());
+        }
+    }
+
+    if prune {
+        for repo in registry.prune() {
+            println!("{}", tf("registry.pruned", &[("path", &repo.path.display().to_string())]).dimmed());
+        }
+    }
+
+    registry.save()?;
+    println!("{}", tf("registry.summary", &[("found", &found.len().to_string()), ("total", &registry.repos.len().to_string())]));
+    Ok(())
+}
+
+/// 处理 workspace export 命令
+fn workspace_export_command_handler(output: Option<&str>) -> Result<()> {
+    let path = core::workspace::export(output.map(Path::new))?;
+    println!("{}", tf("workspace.exported", &[("path", &path.display().to_string())]));
+    Ok(())
+}
+
+/// 处理 ui 命令
+fn ui_command_handler() -> Result<()> {
+    // 界面在 stderr 上绘制，选中的路径输出到 stdout 供 cd "$(work ui)" 使用
+    if let Some(path) = tui::run()? {
+        let worktrees = list_worktrees()?;
+        if let Some(target) = worktrees.iter().find(|wt| wt.path == path) {
+            core::history::record_switch(&worktrees, target);
+        }
+        println!("{}", path);
+    }
+
+    Ok(())
+}
+
+/// 处理 config 命令
+fn config_command_handler(key: Option<&str>, value: Option<&str>, global: bool, unset: bool, add: bool) -> Result<()> {
+    let Some(key) = key else {
+        for key in core::config::KNOWN_KEYS {
+            let values = core::config::get_all(key);
+            if values.is_empty() {
+                println!("work.{} = {}", key, t("config.unset"));
+            }
+            for value in values {
+                println!("work.{} = {}", key, value);
+            }
+        }
+        return Ok(());
+    };
+
+    core::config::check_key(key)?;
+
+    if unset {
+        core::config::unset(key, global)?;
+    } else if let Some(value) = value {
+        if add {
+            core::config::add(key, value, global)?;
+        } else {
+            core::config::set(key, value, global)?;
+        }
+    } else {
+        for value in core::config::get_all(key) {
+            println!("{}", value);
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理 completions 命令
+fn completions_command_handler(shell: clap_complete::Shell) -> Result<()> {
+    let mut command = cli::commands::helpers::localize_command(Args::command(), utils::i18n::current_locale());
+    print!("{}", cli::completion::generate_script(shell, &mut command));
+    Ok(())
+}
+
+/// 处理 __complete 命令：每行输出一个候选，出错时不输出（不在仓库中等）
+fn complete_command_handler(index: usize, words: &[String]) -> Result<()> {
+    use cli::completion::CompletionContext;
+
+    let Some(context) = cli::completion::context(&Args::command(), words, index) else {
+        return Ok(());
+    };
+
+    let candidates: Vec<String> = match context {
+        CompletionContext::Worktree => list_worktrees()
+            .map(|worktrees| cli::completion::worktree_candidates(&worktrees))
+            .unwrap_or_default(),
+        CompletionContext::Branch => {
+            let mut branches = core::git_ops::list_local_branches().unwrap_or_default();
+            branches.extend(core::git_ops::list_remote_branches().unwrap_or_default());
+            branches
+        }
+        CompletionContext::ConfigKey => core::config::KNOWN_KEYS.iter().map(|k| k.to_string()).collect(),
+    };
+
+    let prefix = cli::completion::current_word(words, index);
+    for candidate in candidates.iter().filter(|c| c.starts_with(prefix)) {
+        println!("{}", candidate);
+    }
+
+    Ok(())
+}
+
+/// 处理 schema 命令
+fn schema_command_handler(version: &str) -> Result<()> {
+    print!("{}", schema::schema_for_version(version)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::i18n::{has_key, Locale};
+
+    #[test]
+    fn test_cli_help_is_translated() {
+        let mut command = Args::command();
+        command.build();
+
+        let mut keys = Vec::new();
+        cli::commands::helpers::collect_help_keys(&command, "cli", &mut keys);
+
+        for locale in Locale::ALL {
+            let missing: Vec<_> = keys.iter().filter(|k| !has_key(locale, k)).collect();
+            assert!(missing.is_empty(), "help keys missing in {}: {:?}", locale.code(), missing);
+        }
+    }
+
+    #[test]
+    fn test_localized_command_is_valid() {
+        for locale in Locale::ALL {
+            cli::commands::helpers::localize_command(Args::command(), locale).debug_assert();
+        }
+    }
+}
@@ -0,0 +1,519 @@
+use crate::core::git_ops::{self, WorktreeStatusInfo};
+use crate::core::{lifecycle, metadata, oplog, workspace};
+use crate::core::worktree::Worktree;
+use crate::utils::errors::{Result, WorktreeError};
+use crate::utils::i18n::{t, tf};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::Path;
+
+/// 列表中的一项：worktree 及其实时状态
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub worktree: Worktree,
+    /// `get_worktree_status` 的结果（目录不存在等情况下为 None）
+    pub status: Option<WorktreeStatusInfo>,
+    /// 相对上游领先/落后的提交数
+    pub ahead_behind: Option<(usize, usize)>,
+}
+
+impl Entry {
+    /// 读取 worktree 的实时状态
+    pub fn load(worktree: Worktree) -> Self {
+        // 裸仓库没有工作目录，不读取状态
+        if worktree.is_bare {
+            return Entry { worktree, status: None, ahead_behind: None };
+        }
+
+        let path = Path::new(&worktree.path);
+        let status = git_ops::get_worktree_status(path).ok();
+        let ahead_behind = git_ops::get_ahead_behind(path).ok().flatten();
+
+        Entry {
+            worktree,
+            status,
+            ahead_behind,
+        }
+    }
+
+    /// 是否有未提交的更改
+    pub fn is_dirty(&self) -> bool {
+        self.status.as_ref().map(|s| !s.is_clean()).unwrap_or(false)
+    }
+}
+
+/// 交互模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// 浏览列表
+    Normal,
+    /// 输入新 worktree 的分支名
+    CreateInput(String),
+    /// 编辑选中 worktree 的备注
+    NoteInput(String),
+    /// 确认删除选中的 worktree
+    ConfirmDelete { force: bool },
+}
+
+/// 需要在主循环中处理的动作（涉及终端切换或退出）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    /// 退出界面
+    Quit,
+    /// 退出界面并输出路径供 shell 切换目录
+    Switch(String),
+    /// 暂停界面并在编辑器中打开路径
+    OpenEditor(String),
+}
+
+/// 底部状态栏消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// 仪表盘状态
+pub struct App {
+    pub entries: Vec<Entry>,
+    pub selected: usize,
+    pub mode: Mode,
+    pub message: Option<Message>,
+}
+
+impl App {
+    /// 读取当前仓库的 worktree 创建仪表盘
+    pub fn load() -> Result<Self> {
+        let mut app = App::with_entries(Vec::new());
+        app.refresh()?;
+        Ok(app)
+    }
+
+    /// 使用给定条目创建仪表盘
+    pub fn with_entries(entries: Vec<Entry>) -> Self {
+        App {
+            entries,
+            selected: 0,
+            mode: Mode::Normal,
+            message: None,
+        }
+    }
+
+    /// 重新读取 worktree 列表和状态，尽量保持选中项不变
+    pub fn refresh(&mut self) -> Result<()> {
+        let selected_path = self.selected_entry().map(|e| e.worktree.path.clone());
+
+        self.entries = git_ops::list_worktrees()?
+            .into_iter()
+            .map(Entry::load)
+            .collect();
+
+        self.selected = selected_path
+            .and_then(|path| self.entries.iter().position(|e| e.worktree.path == path))
+            .unwrap_or(0)
+            .min(self.entries.len().saturating_sub(1));
+
+        Ok(())
+    }
+
+    pub fn selected_entry(&self) -> Option<&Entry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1) % self.entries.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    fn info(&mut self, text: String) {
+        self.message = Some(Message { text, is_error: false });
+    }
+
+    /// 选中 worktree 的工作目录；裸仓库和目录已不存在的条目显示错误并返回 None
+    fn selected_directory(&mut self) -> Option<String> {
+        let worktree = &self.selected_entry()?.worktree;
+        match worktree.ensure_directory().map(|_| worktree.path.clone()) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                self.error(e);
+                None
+            }
+        }
+    }
+
+    fn error(&mut self, error: WorktreeError) {
+        self.message = Some(Message {
+            text: error.to_string(),
+            is_error: true,
+        });
+    }
+
+    /// 处理按键，返回需要主循环执行的动作
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+
+        match self.mode.clone() {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::CreateInput(buffer) => {
+                self.handle_input_key(key, buffer, Mode::CreateInput, |app, name| {
+                    if !name.is_empty() {
+                        app.create(name);
+                    }
+                });
+                Action::None
+            }
+            Mode::NoteInput(buffer) => {
+                self.handle_input_key(key, buffer, Mode::NoteInput, Self::save_note);
+                Action::None
+            }
+            Mode::ConfirmDelete { force } => {
+                self.handle_confirm_key(key, force);
+                Action::None
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Down | KeyCode::Char('j') => self.next(),
+            KeyCode::Up | KeyCode::Char('k') => self.previous(),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = self.entries.len().saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(path) = self.selected_directory() {
+                    return Action::Switch(path);
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(path) = self.selected_directory() {
+                    return Action::OpenEditor(path);
+                }
+            }
+            KeyCode::Char('c') => {
+                self.message = None;
+                self.mode = Mode::CreateInput(String::new());
+            }
+            KeyCode::Char('d') => self.begin_delete(),
+            KeyCode::Char('n') => {
+                if let Some(entry) = self.selected_entry() {
+                    let note = entry.worktree.note.clone().unwrap_or_default();
+                    self.message = None;
+                    self.mode = Mode::NoteInput(note);
+                }
+            }
+            KeyCode::Char('l') => self.toggle_lock(),
+            KeyCode::Char('s') => self.sync_selected(),
+            KeyCode::Char('r') => match self.refresh() {
+                Ok(()) => self.info(t("ui.refreshed")),
+                Err(e) => self.error(e),
+            },
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    /// 输入框：Esc 取消，Enter 提交去掉首尾空白的内容，其余按键编辑内容
+    fn handle_input_key(&mut self, key: KeyEvent, mut buffer: String, mode: fn(String) -> Mode, submit: fn(&mut Self, &str)) {
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                submit(self, buffer.trim());
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                self.mode = mode(buffer);
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                self.mode = mode(buffer);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_confirm_key(&mut self, key: KeyEvent, force: bool) {
+        self.mode = Mode::Normal;
+        if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+            self.delete_selected(force);
+        } else {
+            self.info(t("ui.cancelled"));
+        }
+    }
+
+    fn begin_delete(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+
+        // 界面中没有 --force，进行中的操作需要先在终端中完成或中止
+        if let Err(e) = lifecycle::ensure_deletable(&entry.worktree, false) {
+            self.error(e);
+            return;
+        }
+
+        let force = entry.is_dirty();
+        self.mode = Mode::ConfirmDelete { force };
+    }
+
+    fn create(&mut self, name: &str) {
+        let created = match lifecycle::create(name, lifecycle::Source::for_branch(name, None), None) {
+            Ok(created) => created,
+            Err(e) => return self.error(e),
+        };
+        let mut warnings: Vec<String> = created.setup.errors().iter().map(|e| e.to_string()).collect();
+        warnings.extend(workspace::refresh_if_enabled().err().map(|e| e.to_string()));
+
+        if let Err(e) = self.refresh() {
+            return self.error(e);
+        }
+        if let Some(index) = self.entries.iter().position(|e| e.worktree.path == created.path) {
+            self.selected = index;
+        }
+        self.done(tf("ui.created", &[("name", name)]), warnings);
+    }
+
+    fn delete_selected(&mut self, force: bool) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let worktree = entry.worktree.clone();
+
+        if let Err(e) = lifecycle::delete(&worktree, force, true) {
+            return self.error(e);
+        }
+        let warnings: Vec<String> = workspace::refresh_if_enabled().err().map(|e| e.to_string()).into_iter().collect();
+
+        match self.refresh() {
+            Ok(()) => self.done(tf("ui.deleted", &[("name", &worktree.dirname)]), warnings),
+            Err(e) => self.error(e),
+        }
+    }
+
+    /// 操作成功后的提示；附带设置失败时以警告显示
+    fn done(&mut self, text: String, warnings: Vec<String>) {
+        if warnings.is_empty() {
+            self.info(text);
+        } else {
+            self.message = Some(Message {
+                text: tf("ui.with_warnings", &[("message", &text), ("warnings", &warnings.join("; "))]),
+                is_error: true,
+            });
+        }
+    }
+
+    /// 保存选中 worktree 的备注，内容为空时清除
+    fn save_note(&mut self, note: &str) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let (name, path) = (entry.worktree.dirname.clone(), entry.worktree.path.clone());
+
+        let result = metadata::update(|metadata| metadata.set_note(&path, Some(note)));
+        match result.and_then(|_| self.refresh()) {
+            Ok(()) => {
+                let key = if note.is_empty() { "ui.note_cleared" } else { "ui.note_saved" };
+                self.info(tf(key, &[("name", &name)]));
+            }
+            Err(e) => self.error(e),
+        }
+    }
+
+    fn toggle_lock(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let worktree = entry.worktree.clone();
+        if let Err(e) = worktree.ensure_checkout() {
+            self.error(e);
+            return;
+        }
+        let (name, path, locked) = (worktree.dirname.clone(), worktree.path.clone(), worktree.is_locked);
+
+        let result = if locked {
+            git_ops::unlock_worktree(&path)
+        } else {
+            git_ops::lock_worktree(&path, None)
+        };
+        let kind = if locked { oplog::OpKind::Unlock } else { oplog::OpKind::Lock };
+        oplog::record_outcome(oplog::Operation::for_worktree(kind, &worktree), &result);
+
+        match result.and_then(|_| self.refresh()) {
+            Ok(()) => {
+                let key = if locked { "ui.unlocked" } else { "ui.locked" };
+                self.info(tf(key, &[("name", &name)]));
+            }
+            Err(e) => self.error(e),
+        }
+    }
+
+    fn sync_selected(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let worktree = entry.worktree.clone();
+        if let Err(e) = worktree.ensure_checkout().and_then(|_| worktree.ensure_idle(false)) {
+            self.error(e);
+            return;
+        }
+        let (name, path) = (worktree.dirname.clone(), worktree.path.clone());
+
+        let result = git_ops::sync_worktree(Path::new(&path));
+        oplog::record_outcome(
+            oplog::Operation::for_worktree(oplog::OpKind::Sync, &worktree).with_head_after(Path::new(&path)),
+            &result,
+        );
+
+        match result.and_then(|_| self.refresh()) {
+            Ok(()) => self.info(tf("ui.synced", &[("name", &name)])),
+            Err(e) => self.error(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyEventKind;
+
+    fn entry(name: &str, current: bool, dirty: bool) -> Entry {
+        let worktree = Worktree::new(
+            name.to_string(),
+            name.to_string(),
+            format!("/nonexistent/{}", name),
+            current,
+            false,
+            false,
+            Some("abc123".to_string()),
+            None,
+        );
+        let status = WorktreeStatusInfo {
+            modified: if dirty { vec!["a.txt".to_string()] } else { Vec::new() },
+            staged: Vec::new(),
+            untracked: Vec::new(),
+            conflicted: Vec::new(),
+        };
+
+        Entry {
+            worktree,
+            status: Some(status),
+            ahead_behind: None,
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        }
+    }
+
+    fn sample_app() -> App {
+        App::with_entries(vec![
+            entry("main", true, false),
+            entry("feat-a", false, true),
+            entry("feat-b", false, false),
+        ])
+    }
+
+    #[test]
+    fn test_navigation_wraps() {
+        let mut app = sample_app();
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.selected, 2);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected, 0);
+        app.handle_key(key(KeyCode::Char('G')));
+        assert_eq!(app.selected, 2);
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.selected, 0);
+    }
+
+    #[test]
+    fn test_enter_switches_to_selected_path() {
+        let mut app = sample_app();
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::Switch("/nonexistent/feat-a".to_string()));
+        assert_eq!(app.handle_key(key(KeyCode::Char('e'))), Action::OpenEditor("/nonexistent/feat-a".to_string()));
+        assert_eq!(app.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn test_bare_and_prunable_entries_are_not_opened() {
+        let mut bare = entry("repo", false, false);
+        bare.worktree.is_bare = true;
+        let mut prunable = entry("gone", false, false);
+        prunable.worktree.is_prunable = true;
+        let mut app = App::with_entries(vec![bare, prunable]);
+
+        for _ in 0..2 {
+            for code in [KeyCode::Enter, KeyCode::Char('e')] {
+                app.message = None;
+                assert_eq!(app.handle_key(key(code)), Action::None);
+                assert!(app.message.as_ref().unwrap().is_error);
+            }
+            app.handle_key(key(KeyCode::Down));
+        }
+    }
+
+    #[test]
+    fn test_delete_current_is_refused() {
+        let mut app = sample_app();
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.message.as_ref().unwrap().is_error);
+    }
+
+    #[test]
+    fn test_delete_dirty_requires_force_confirmation() {
+        let mut app = sample_app();
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.mode, Mode::ConfirmDelete { force: true });
+
+        // 任何非 y 的按键都会取消
+        app.handle_key(key(KeyCode::Char('n')));
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_create_input_editing() {
+        let mut app = sample_app();
+        app.handle_key(key(KeyCode::Char('c')));
+        for c in "feat/xy".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        app.handle_key(key(KeyCode::Backspace));
+        assert_eq!(app.mode, Mode::CreateInput("feat/x".to_string()));
+
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_note_input_starts_with_existing_note() {
+        let mut app = sample_app();
+        app.entries[1].worktree.note = Some("review".to_string());
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Char('n')));
+        app.handle_key(key(KeyCode::Char('!')));
+        assert_eq!(app.mode, Mode::NoteInput("review!".to_string()));
+
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+}
@@ -0,0 +1,97 @@
+// work ui：全屏终端仪表盘
+//
+// 界面绘制在 stderr 上，stdout 只用于输出选中 worktree 的路径，
+// 因此可以用 `cd "$(work ui)"` 切换目录。
+
+pub mod app;
+pub mod ui;
+
+use crate::utils::errors::Result;
+use app::{Action, App};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::crossterm::cursor::Show;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::Terminal;
+use std::io::Stderr;
+use std::time::{Duration, Instant};
+
+/// 空闲时自动刷新状态的间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+type Backend = CrosstermBackend<Stderr>;
+
+/// 运行仪表盘，返回用户选择切换到的 worktree 路径
+pub fn run() -> Result<Option<String>> {
+    let mut app = App::load()?;
+    install_panic_hook();
+    let mut terminal = setup_terminal()?;
+
+    let result = event_loop(&mut terminal, &mut app);
+
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+/// panic 时先恢复终端再输出 panic 信息，否则 shell 会停留在原始模式和备用屏幕中
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(std::io::stderr(), LeaveAlternateScreen, Show);
+        previous(info);
+    }));
+}
+
+fn setup_terminal() -> Result<Terminal<Backend>> {
+    enable_raw_mode()?;
+    let mut stderr = std::io::stderr();
+    execute!(stderr, EnterAlternateScreen)?;
+    Ok(Terminal::new(CrosstermBackend::new(stderr))?)
+}
+
+fn restore_terminal(terminal: &mut Terminal<Backend>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+fn event_loop(terminal: &mut Terminal<Backend>, app: &mut App) -> Result<Option<String>> {
+    let mut last_refresh = Instant::now();
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            // 只在浏览模式下自动刷新，避免打断输入和确认
+            if app.mode == app::Mode::Normal && last_refresh.elapsed() >= REFRESH_INTERVAL {
+                app.refresh()?;
+                last_refresh = Instant::now();
+            }
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key) {
+            Action::None => {}
+            Action::Quit => return Ok(None),
+            Action::Switch(path) => return Ok(Some(path)),
+            Action::OpenEditor(path) => {
+                restore_terminal(terminal)?;
+                let status = crate::core::editor::open(&crate::core::editor::resolve(None), &path);
+                *terminal = setup_terminal()?;
+                terminal.clear()?;
+                status?;
+                app.refresh()?;
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::tui::app::{App, Entry, Mode};
+use crate::utils::i18n::{t, tf};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// 绘制整个仪表盘
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, footer] = Layout::vertical([Constraint::Min(3), Constraint::Length(2)]).areas(frame.area());
+    let [list_area, detail_area] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+
+    draw_list(frame, app, list_area);
+    draw_detail(frame, app.selected_entry(), detail_area);
+    draw_footer(frame, app, footer);
+}
+
+/// 列表中每项的状态摘要，如 `[REBASING 3/7] !1 +1 ~2 ?3 ↑1 ↓0 [locked]`
+pub fn status_summary(entry: &Entry) -> String {
+    let mut parts = Vec::new();
+
+    if let Some(operation) = &entry.worktree.operation {
+        parts.push(format!("[{}]", operation.marker()));
+    }
+
+    if let Some(status) = &entry.status {
+        if !status.conflicted.is_empty() {
+            parts.push(format!("!{}", status.conflicted.len()));
+        }
+        if !status.staged.is_empty() {
+            parts.push(format!("+{}", status.staged.len()));
+        }
+        if !status.modified.is_empty() {
+            parts.push(format!("~{}", status.modified.len()));
+        }
+        if !status.untracked.is_empty() {
+            parts.push(format!("?{}", status.untracked.len()));
+        }
+    }
+
+    if let Some((ahead, behind)) = entry.ahead_behind {
+        if ahead > 0 {
+            parts.push(format!("↑{}", ahead));
+        }
+        if behind > 0 {
+            parts.push(format!("↓{}", behind));
+        }
+    }
+
+    if entry.worktree.is_bare {
+        parts.push(format!("[{}]", t("list.bare")));
+    }
+    if entry.worktree.is_prunable {
+        parts.push(format!("[{}]", t("list.prunable")));
+    }
+    if entry.worktree.is_locked {
+        parts.push(format!("[{}]", t("ui.lock_marker")));
+    }
+
+    parts.join(" ")
+}
+
+fn draw_list(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .entries
+        .iter()
+        .map(|entry| {
+            let wt = &entry.worktree;
+            let marker = if wt.is_current { "* " } else { "  " };
+            let name_style = if entry.is_dirty() {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(Color::Green)),
+                Span::styled(wt.dirname.clone(), name_style),
+            ];
+            if !wt.is_bare && wt.branch_name != wt.dirname {
+                spans.push(Span::styled(format!(" {}", wt.branch_name), Style::default().fg(Color::Yellow)));
+            }
+            let summary = status_summary(entry);
+            if !summary.is_empty() {
+                spans.push(Span::styled(format!("  {}", summary), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some(note) = &wt.note {
+                spans.push(Span::styled(format!("  {}", note), Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC)));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::bordered().title(tf("ui.title", &[("count", &app.entries.len().to_string())])))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_detail(frame: &mut Frame, entry: Option<&Entry>, area: Rect) {
+    let block = Block::bordered().title(t("ui.detail"));
+
+    let Some(entry) = entry else {
+        frame.render_widget(Paragraph::new(t("ui.empty")).block(block), area);
+        return;
+    };
+
+    let wt = &entry.worktree;
+    let label = |key: &str| Span::styled(format!("{}: ", t(key)), Style::default().add_modifier(Modifier::BOLD));
+    let yes_no = |value: bool| if value { t("label.yes") } else { t("label.no") };
+
+    if wt.is_bare {
+        // 裸仓库没有检出，只显示类型和路径
+        let lines = vec![
+            Line::from(vec![label("info.kind"), Span::styled(t("info.bare"), Style::default().fg(Color::DarkGray))]),
+            Line::from(vec![label("label.path"), Span::raw(wt.path.clone())]),
+        ];
+        let paragraph = Paragraph::new(Text::from(lines)).block(block.title(wt.dirname.clone())).wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let mut lines = vec![
+        Line::from(vec![label("info.branch"), Span::styled(wt.branch_name.clone(), Style::default().fg(Color::Yellow))]),
+        Line::from(vec![label("label.path"), Span::raw(wt.path.clone())]),
+        Line::from(vec![label("info.head"), Span::raw(wt.head_commit.clone().unwrap_or_else(|| t("label.na")))]),
+        Line::from(vec![label("info.current"), Span::raw(yes_no(wt.is_current))]),
+        Line::from(vec![label("ui.locked_label"), Span::raw(yes_no(wt.is_locked))]),
+    ];
+
+    if let Some(note) = &wt.note {
+        lines.push(Line::from(vec![label("ui.note"), Span::styled(note.clone(), Style::default().fg(Color::Magenta))]));
+    }
+    if let Some(operation) = &wt.operation {
+        lines.push(Line::from(vec![
+            label("info.operation"),
+            Span::styled(operation.marker(), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    if let Some(reason) = &wt.lock_reason {
+        lines.push(Line::from(vec![label("info.lock_reason"), Span::styled(reason.clone(), Style::default().fg(Color::Yellow))]));
+    }
+    if wt.is_prunable {
+        let reason = wt.prune_reason.clone().unwrap_or_else(|| t("label.yes"));
+        lines.push(Line::from(vec![label("info.prune_reason"), Span::styled(reason, Style::default().fg(Color::Red))]));
+    }
+    if let Some(upstream) = &wt.upstream_branch {
+        lines.push(Line::from(vec![label("info.upstream"), Span::styled(upstream.clone(), Style::default().fg(Color::Cyan))]));
+    }
+    if let Some((ahead, behind)) = entry.ahead_behind {
+        lines.push(Line::from(vec![
+            label("ui.ahead_behind"),
+            Span::raw(format!("↑{} ↓{}", ahead, behind)),
+        ]));
+    }
+
+    match &entry.status {
+        Some(status) if !status.is_clean() => {
+            lines.push(Line::default());
+            lines.push(Line::styled(t("info.uncommitted"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+
+            let sections = [
+                ("info.conflicted", "U", Color::Red, &status.conflicted),
+                ("info.staged", "✓", Color::Green, &status.staged),
+                ("info.modified", "M", Color::Yellow, &status.modified),
+                ("info.untracked", "?", Color::DarkGray, &status.untracked),
+            ];
+            for (key, symbol, color, files) in sections {
+                if files.is_empty() {
+                    continue;
+                }
+                lines.push(Line::styled(format!("  {}:", t(key)), Style::default().fg(color)));
+                for file in files {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("    {} ", symbol), Style::default().fg(color)),
+                        Span::raw(file.clone()),
+                    ]));
+                }
+            }
+        }
+        Some(_) => {
+            lines.push(Line::default());
+            lines.push(Line::styled(t("ui.clean"), Style::default().fg(Color::Green)));
+        }
+        None => {
+            lines.push(Line::default());
+            lines.push(Line::styled(t("ui.status_unavailable"), Style::default().fg(Color::DarkGray)));
+        }
+    }
+
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block.title(wt.dirname.clone()))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let [message_area, keys_area] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(area);
+
+    let message = match &app.mode {
+        Mode::CreateInput(buffer) => Line::from(vec![
+            Span::styled(format!("{} ", t("ui.create_prompt")), Style::default().fg(Color::Green)),
+            Span::raw(buffer.clone()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]),
+        Mode::NoteInput(buffer) => {
+            let name = app.selected_entry().map(|e| e.worktree.dirname.clone()).unwrap_or_default();
+            Line::from(vec![
+                Span::styled(format!("{} ", tf("ui.note_prompt", &[("name", &name)])), Style::default().fg(Color::Magenta)),
+                Span::raw(buffer.clone()),
+                Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+            ])
+        }
+        Mode::ConfirmDelete { force } => {
+            let name = app.selected_entry().map(|e| e.worktree.dirname.clone()).unwrap_or_default();
+            let key = if *force { "ui.confirm_delete_dirty" } else { "ui.confirm_delete" };
+            Line::styled(tf(key, &[("name", &name)]), Style::default().fg(Color::Red))
+        }
+        Mode::Normal => match &app.message {
+            Some(message) if message.is_error => Line::styled(message.text.clone(), Style::default().fg(Color::Red)),
+            Some(message) => Line::styled(message.text.clone(), Style::default().fg(Color::Green)),
+            None => Line::default(),
+        },
+    };
+
+    frame.render_widget(Paragraph::new(message), message_area);
+    frame.render_widget(
+        Paragraph::new(Line::styled(t("ui.keys"), Style::default().fg(Color::DarkGray))),
+        keys_area,
+    );
+}
@@ -1,45 +1,324 @@
+use crate::utils::i18n::{t, tf};
+use std::fmt;
 use std::path::PathBuf;
-use thiserror::Error;
 
 /// Worktree 管理工具的错误类型
-#[derive(Error, Debug)]
+///
+/// 每个变体对应一个稳定的退出码（见 [`WorktreeError::exit_code`]）和
+/// 机器可读的类别名（见 [`WorktreeError::kind`]），脚本可以据此区分错误原因。
+/// 错误消息和修复建议来自消息目录（`error.*`、`hint.*`），随界面语言切换。
+#[derive(Debug)]
 pub enum WorktreeError {
-    #[error("Worktree not found: {0}")]
     NotFound(String),
-
-    #[error("Worktree already exists: {0}")]
     AlreadyExists(String),
-
-    #[error("Invalid worktree name: {0}")]
+    AliasConflict(String),
     InvalidName(String),
-
-    #[error("Invalid worktree path: {0}")]
     InvalidPath(String),
-
-    #[error("Git operation failed: {0}")]
     GitError(String),
-
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-
-    #[error("Worktree has uncommitted changes")]
-    UncommittedChanges,
-
-    #[error("Cannot delete current worktree")]
-    CannotDeleteCurrent,
-
-    #[error("Not a git repository: {0}")]
+    GitNotFound,
+    IoError(std::io::Error),
+    UncommittedChanges(String),
+    CannotDeleteCurrent(String),
+    BareRepository(String),
+    OperationInProgress(String),
     NotGitRepository(PathBuf),
-
-    #[error("Invalid branch name: {0}")]
+    BranchNotFound(String),
+    RevisionNotFound(String),
     InvalidBranchName(String),
-
-    #[error("Directory name conflict: '{dirname}' already exists for branch '{existing_branch}'")]
+    NothingToSelect(String),
+    InvalidArgument(String),
+    UnsupportedSchemaVersion(String),
+    InvalidTemplate(String),
+    EditorFailed(String),
+    SessionFailed(String),
     DirNameConflict {
         dirname: String,
         existing_branch: String,
     },
 }
 
+/// 命令行参数错误的退出码（与 clap 保持一致）
+pub const EXIT_USAGE: i32 = 2;
+
+impl WorktreeError {
+    /// 根据启动 git 进程失败的 IO 错误构造错误（区分 git 未安装的情况）
+    pub fn git_spawn(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            WorktreeError::GitNotFound
+        } else {
+            WorktreeError::GitError(tf("error.detail.git_spawn", &[("error", &e.to_string())]))
+        }
+    }
+
+    /// 进程退出码
+    ///
+    /// | 退出码 | 类别 |
+    /// |---|---|
+    /// | 1 | `io_error` |
+    /// | 2 | `invalid_argument` `unsupported_schema_version` `invalid_template`（及 clap 参数错误） |
+    /// | 3 | `not_found` `nothing_to_select` |
+    /// | 4 | `already_exists` `alias_conflict` |
+    /// | 5 | `dir_name_conflict` |
+    /// | 6 | `uncommitted_changes` |
+    /// | 7 | `cannot_delete_current` |
+    /// | 8 | `not_git_repository` |
+    /// | 9 | `git_not_found` |
+    /// | 10 | `git_error` |
+    /// | 11 | `branch_not_found` `revision_not_found` |
+    /// | 12 | `invalid_name` `invalid_branch_name` `invalid_path` |
+    /// | 13 | `editor_failed` |
+    /// | 14 | `session_failed` |
+    /// | 15 | `bare_repository` |
+    /// | 16 | `operation_in_progress` |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WorktreeError::IoError(_) => 1,
+            WorktreeError::InvalidArgument(_)
+            | WorktreeError::UnsupportedSchemaVersion(_)
+            | WorktreeError::InvalidTemplate(_) => EXIT_USAGE,
+            WorktreeError::NotFound(_) | WorktreeError::NothingToSelect(_) => 3,
+            WorktreeError::AlreadyExists(_) | WorktreeError::AliasConflict(_) => 4,
+            WorktreeError::DirNameConflict { .. } => 5,
+            WorktreeError::UncommittedChanges(_) => 6,
+            WorktreeError::CannotDeleteCurrent(_) => 7,
+            WorktreeError::NotGitRepository(_) => 8,
+            WorktreeError::GitNotFound => 9,
+            WorktreeError::GitError(_) => 10,
+            WorktreeError::BranchNotFound(_) | WorktreeError::RevisionNotFound(_) => 11,
+            WorktreeError::InvalidName(_)
+            | WorktreeError::InvalidBranchName(_)
+            | WorktreeError::InvalidPath(_) => 12,
+            WorktreeError::EditorFailed(_) => 13,
+            WorktreeError::SessionFailed(_) => 14,
+            WorktreeError::BareRepository(_) => 15,
+            WorktreeError::OperationInProgress(_) => 16,
+        }
+    }
+
+    /// 稳定的错误类别名（用于 `--error-format json`）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WorktreeError::NotFound(_) => "not_found",
+            WorktreeError::AlreadyExists(_) => "already_exists",
+            WorktreeError::AliasConflict(_) => "alias_conflict",
+            WorktreeError::InvalidName(_) => "invalid_name",
+            WorktreeError::InvalidPath(_) => "invalid_path",
+            WorktreeError::GitError(_) => "git_error",
+            WorktreeError::GitNotFound => "git_not_found",
+            WorktreeError::IoError(_) => "io_error",
+            WorktreeError::UncommittedChanges(_) => "uncommitted_changes",
+            WorktreeError::CannotDeleteCurrent(_) => "cannot_delete_current",
+            WorktreeError::NotGitRepository(_) => "not_git_repository",
+            WorktreeError::BranchNotFound(_) => "branch_not_found",
+            WorktreeError::RevisionNotFound(_) => "revision_not_found",
+            WorktreeError::InvalidBranchName(_) => "invalid_branch_name",
+            WorktreeError::NothingToSelect(_) => "nothing_to_select",
+            WorktreeError::InvalidArgument(_) => "invalid_argument",
+            WorktreeError::UnsupportedSchemaVersion(_) => "unsupported_schema_version",
+            WorktreeError::InvalidTemplate(_) => "invalid_template",
+            WorktreeError::DirNameConflict { .. } => "dir_name_conflict",
+            WorktreeError::EditorFailed(_) => "editor_failed",
+            WorktreeError::SessionFailed(_) => "session_failed",
+            WorktreeError::BareRepository(_) => "bare_repository",
+            WorktreeError::OperationInProgress(_) => "operation_in_progress",
+        }
+    }
+
+    /// 给用户的修复建议
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            WorktreeError::NotFound(_) => t("hint.not_found"),
+            WorktreeError::AlreadyExists(name) => tf("hint.already_exists", &[("name", name)]),
+            WorktreeError::AliasConflict(_) => t("hint.alias_conflict"),
+            WorktreeError::UncommittedChanges(_) => t("hint.uncommitted_changes"),
+            WorktreeError::CannotDeleteCurrent(_) => t("hint.cannot_delete_current"),
+            WorktreeError::BareRepository(_) => t("hint.bare_repository"),
+            WorktreeError::OperationInProgress(_) => t("hint.operation_in_progress"),
+            WorktreeError::NotGitRepository(_) => t("hint.not_git_repository"),
+            WorktreeError::GitNotFound => t("hint.git_not_found"),
+            WorktreeError::BranchNotFound(_) => t("hint.branch_not_found"),
+            WorktreeError::RevisionNotFound(_) => t("hint.revision_not_found"),
+            WorktreeError::DirNameConflict { dirname, .. } => tf("hint.dir_name_conflict", &[("dirname", dirname)]),
+            WorktreeError::InvalidTemplate(_) => t("hint.invalid_template"),
+            WorktreeError::EditorFailed(_) => t("hint.editor_failed"),
+            WorktreeError::SessionFailed(_) => t("hint.session_failed"),
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+impl fmt::Display for WorktreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            WorktreeError::NotFound(detail) => tf("error.not_found", &[("detail", detail)]),
+            WorktreeError::AlreadyExists(detail) => tf("error.already_exists", &[("detail", detail)]),
+            WorktreeError::AliasConflict(alias) => tf("error.alias_conflict", &[("alias", alias)]),
+            WorktreeError::InvalidName(detail) => tf("error.invalid_name", &[("detail", detail)]),
+            WorktreeError::InvalidPath(detail) => tf("error.invalid_path", &[("detail", detail)]),
+            WorktreeError::GitError(detail) => tf("error.git_error", &[("detail", detail)]),
+            WorktreeError::GitNotFound => t("error.git_not_found"),
+            WorktreeError::IoError(e) => tf("error.io_error", &[("detail", &e.to_string())]),
+            WorktreeError::UncommittedChanges(detail) => tf("error.uncommitted_changes", &[("detail", detail)]),
+            WorktreeError::CannotDeleteCurrent(detail) => tf("error.cannot_delete_current", &[("detail", detail)]),
+            WorktreeError::BareRepository(name) => tf("error.bare_repository", &[("name", name)]),
+            WorktreeError::OperationInProgress(detail) => tf("error.operation_in_progress", &[("detail", detail)]),
+            WorktreeError::NotGitRepository(path) => {
+                tf("error.not_git_repository", &[("detail", &path.display().to_string())])
+            }
+            WorktreeError::BranchNotFound(detail) => tf("error.branch_not_found", &[("detail", detail)]),
+            WorktreeError::RevisionNotFound(detail) => tf("error.revision_not_found", &[("detail", detail)]),
+            WorktreeError::InvalidBranchName(detail) => tf("error.invalid_branch_name", &[("detail", detail)]),
+            WorktreeError::NothingToSelect(detail) => tf("error.nothing_to_select", &[("detail", detail)]),
+            WorktreeError::InvalidArgument(detail) => tf("error.invalid_argument", &[("detail", detail)]),
+            WorktreeError::UnsupportedSchemaVersion(detail) => {
+                tf("error.unsupported_schema_version", &[("detail", detail)])
+            }
+            WorktreeError::InvalidTemplate(detail) => tf("error.invalid_template", &[("detail", detail)]),
+            WorktreeError::EditorFailed(detail) => tf("error.editor_failed", &[("detail", detail)]),
+            WorktreeError::SessionFailed(detail) => tf("error.session_failed", &[("detail", detail)]),
+            WorktreeError::DirNameConflict { dirname, existing_branch } => tf(
+                "error.dir_name_conflict",
+                &[("dirname", dirname), ("branch", existing_branch)],
+            ),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for WorktreeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorktreeError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WorktreeError {
+    fn from(e: std::io::Error) -> Self {
+        WorktreeError::IoError(e)
+    }
+}
+
+impl From<dialoguer::Error> for WorktreeError {
+    fn from(e: dialoguer::Error) -> Self {
+        match e {
+            dialoguer::Error::IO(io) => WorktreeError::IoError(io),
+        }
+    }
+}
+
 /// Result 类型别名
 pub type Result<T> = std::result::Result<T, WorktreeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_stable() {
+        assert_eq!(WorktreeError::NotFound("x".to_string()).exit_code(), 3);
+        assert_eq!(WorktreeError::UncommittedChanges("x".to_string()).exit_code(), 6);
+        assert_eq!(WorktreeError::CannotDeleteCurrent("x".to_string()).exit_code(), 7);
+        assert_eq!(WorktreeError::GitNotFound.exit_code(), 9);
+        assert_eq!(WorktreeError::BareRepository("x".to_string()).exit_code(), 15);
+        assert_eq!(WorktreeError::OperationInProgress("x".to_string()).exit_code(), 16);
+        assert_eq!(WorktreeError::InvalidArgument("x".to_string()).exit_code(), EXIT_USAGE);
+    }
+
+    #[test]
+    fn test_git_spawn_detects_missing_git() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        assert!(matches!(WorktreeError::git_spawn(missing), WorktreeError::GitNotFound));
+
+        let other = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(WorktreeError::git_spawn(other).kind(), "git_error");
+    }
+
+    /// 每个变体各取一个样例；match 不带通配分支，新增变体时编译器会提醒补充样例
+    fn samples() -> Vec<WorktreeError> {
+        let samples = vec![
+            WorktreeError::NotFound("sample".to_string()),
+            WorktreeError::AlreadyExists("sample".to_string()),
+            WorktreeError::AliasConflict("sample".to_string()),
+            WorktreeError::InvalidName("sample".to_string()),
+            WorktreeError::InvalidPath("sample".to_string()),
+            WorktreeError::GitError("sample".to_string()),
+            WorktreeError::GitNotFound,
+            WorktreeError::IoError(std::io::Error::other("sample")),
+            WorktreeError::UncommittedChanges("sample".to_string()),
+            WorktreeError::CannotDeleteCurrent("sample".to_string()),
+            WorktreeError::BareRepository("sample".to_string()),
+            WorktreeError::OperationInProgress("sample".to_string()),
+            WorktreeError::NotGitRepository(PathBuf::from("sample")),
+            WorktreeError::BranchNotFound("sample".to_string()),
+            WorktreeError::RevisionNotFound("sample".to_string()),
+            WorktreeError::InvalidBranchName("sample".to_string()),
+            WorktreeError::NothingToSelect("sample".to_string()),
+            WorktreeError::InvalidArgument("sample".to_string()),
+            WorktreeError::UnsupportedSchemaVersion("sample".to_string()),
+            WorktreeError::InvalidTemplate("sample".to_string()),
+            WorktreeError::EditorFailed("sample".to_string()),
+            WorktreeError::SessionFailed("sample".to_string()),
+            WorktreeError::DirNameConflict {
+                dirname: "sample".to_string(),
+                existing_branch: "sample".to_string(),
+            },
+        ];
+        for sample in &samples {
+            match sample {
+                WorktreeError::NotFound(_)
+                | WorktreeError::AlreadyExists(_)
+                | WorktreeError::AliasConflict(_)
+                | WorktreeError::InvalidName(_)
+                | WorktreeError::InvalidPath(_)
+                | WorktreeError::GitError(_)
+                | WorktreeError::GitNotFound
+                | WorktreeError::IoError(_)
+                | WorktreeError::UncommittedChanges(_)
+                | WorktreeError::CannotDeleteCurrent(_)
+                | WorktreeError::BareRepository(_)
+                | WorktreeError::OperationInProgress(_)
+                | WorktreeError::NotGitRepository(_)
+                | WorktreeError::BranchNotFound(_)
+                | WorktreeError::RevisionNotFound(_)
+                | WorktreeError::InvalidBranchName(_)
+                | WorktreeError::NothingToSelect(_)
+                | WorktreeError::InvalidArgument(_)
+                | WorktreeError::UnsupportedSchemaVersion(_)
+                | WorktreeError::InvalidTemplate(_)
+                | WorktreeError::EditorFailed(_)
+                | WorktreeError::SessionFailed(_)
+                | WorktreeError::DirNameConflict { .. } => {}
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_every_error_has_a_translated_message() {
+        // 消息和建议的键都以字面量写在 t / tf 中，两种语言是否齐全由 i18n 的键扫描测试检查；
+        // 这里确认每个变体都真正查到了消息，而不是回退成键名
+        for err in samples() {
+            let message = err.to_string();
+            assert!(!message.starts_with("error."), "untranslated message for {}: {}", err.kind(), message);
+            if !matches!(err, WorktreeError::GitNotFound) {
+                assert!(message.contains("sample"), "message for {} drops its detail: {}", err.kind(), message);
+            }
+            if let Some(hint) = err.hint() {
+                assert!(!hint.starts_with("hint."), "untranslated hint for {}: {}", err.kind(), hint);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hint_mentions_conflicting_dirname() {
+        let err = WorktreeError::DirNameConflict {
+            dirname: "feat-x".to_string(),
+            existing_branch: "feat/x".to_string(),
+        };
+        assert_eq!(err.kind(), "dir_name_conflict");
+        assert!(err.hint().unwrap().contains("work delete feat-x"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::core::config;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 英文消息目录
+const EN_CATALOG: &str = include_str!("../../locales/en.txt");
+/// 简体中文消息目录
+const ZH_CN_CATALOG: &str = include_str!("../../locales/zh-CN.txt");
+
+/// 支持的界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    /// 所有支持的语言
+    #[cfg(test)]
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::ZhCn];
+
+    /// 解析语言标识（如 `zh-CN`、`zh_CN.UTF-8`、`en_US`、`C`）
+    pub fn parse(value: &str) -> Option<Self> {
+        let lang = value
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+            .replace('_', "-");
+
+        if lang.is_empty() {
+            None
+        } else if lang == "zh" || lang.starts_with("zh-") {
+            Some(Locale::ZhCn)
+        } else if lang == "en" || lang.starts_with("en-") || lang == "c" || lang == "posix" {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    /// 检测当前语言：配置项 `work.language` 优先，其次是 LC_ALL、LC_MESSAGES、LANG，默认英文
+    pub fn detect() -> Self {
+        if let Some(locale) = config::get("language").and_then(|v| Locale::parse(&v)) {
+            return locale;
+        }
+
+        for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+            match std::env::var(var) {
+                // 按 POSIX 语义，第一个非空变量决定语言；无法识别的语言回退到英文
+                Ok(value) if !value.is_empty() => return Locale::parse(&value).unwrap_or(Locale::En),
+                _ => continue,
+            }
+        }
+
+        Locale::En
+    }
+
+    /// 语言标识
+    #[cfg(test)]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    fn catalog_source(&self) -> &'static str {
+        match self {
+            Locale::En => EN_CATALOG,
+            Locale::ZhCn => ZH_CN_CATALOG,
+        }
+    }
+}
+
+/// 解析消息目录：每行 `key = value`，`#` 开头为注释，值中支持 `\n`、`\t`、`\\` 转义
+fn parse_catalog(source: &'static str) -> HashMap<&'static str, String> {
+    let mut catalog = HashMap::new();
+
+    for line in source.lines() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            catalog.insert(key.trim(), unescape(value.trim()));
+        }
+    }
+
+    catalog
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('s') => result.push(' '),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+fn catalog(locale: Locale) -> &'static HashMap<&'static str, String> {
+    static EN: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+    static ZH_CN: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+
+    let cell = match locale {
+        Locale::En => &EN,
+        Locale::ZhCn => &ZH_CN,
+    };
+    cell.get_or_init(|| parse_catalog(locale.catalog_source()))
+}
+
+/// 当前进程使用的语言（首次调用时检测）
+pub fn current_locale() -> Locale {
+    static LOCALE: OnceLock<Locale> = OnceLock::new();
+    *LOCALE.get_or_init(Locale::detect)
+}
+
+/// 在指定语言中查找消息并替换 `{name}` 占位符
+///
+/// 缺失时回退到英文，英文也缺失时返回键名本身
+pub fn translate_in(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let template = catalog(locale)
+        .get(key)
+        .or_else(|| catalog(Locale::En).get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+
+    let mut message = template.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+/// 查找当前语言的消息
+pub fn t(key: &str) -> String {
+    translate_in(current_locale(), key, &[])
+}
+
+/// 查找当前语言的消息并替换占位符
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    translate_in(current_locale(), key, args)
+}
+
+/// 指定语言的消息目录中是否存在某个键
+#[cfg(test)]
+pub fn has_key(locale: Locale, key: &str) -> bool {
+    catalog(locale).contains_key(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    fn keys(locale: Locale) -> BTreeSet<&'static str> {
+        catalog(locale).keys().copied().collect()
+    }
+
+    /// 收集源码中通过 t / tf 函数引用的所有键（只识别字符串字面量参数，允许键换行书写）
+    fn collect_used_keys(dir: &Path, keys: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_used_keys(&path, keys);
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for pattern in ["t(", "tf("] {
+                    for (index, _) in source.match_indices(pattern) {
+                        // 排除 get("...")、split("...") 等以 t 结尾的其他函数
+                        let before = source[..index].chars().last();
+                        if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                            continue;
+                        }
+                        let Some(rest) = source[index + pattern.len()..].trim_start().strip_prefix('"') else {
+                            continue;
+                        };
+                        // 只收集形如 `delete.skipped` 的键，跳过本测试自身的模式字面量
+                        let key = rest.split('"').next().unwrap_or_default();
+                        if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_') {
+                            keys.insert(key.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalogs_have_same_keys() {
+        let en = keys(Locale::En);
+        let zh = keys(Locale::ZhCn);
+
+        let missing_in_zh: Vec<_> = en.difference(&zh).collect();
+        let missing_in_en: Vec<_> = zh.difference(&en).collect();
+        assert!(missing_in_zh.is_empty(), "keys missing in zh-CN: {:?}", missing_in_zh);
+        assert!(missing_in_en.is_empty(), "keys missing in en: {:?}", missing_in_en);
+    }
+
+    #[test]
+    fn test_all_used_keys_are_translated() {
+        let mut used = BTreeSet::new();
+        collect_used_keys(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut used);
+        assert!(!used.is_empty());
+
+        for locale in Locale::ALL {
+            let missing: Vec<_> = used.iter().filter(|k| !has_key(locale, k)).collect();
+            assert!(missing.is_empty(), "keys missing in {}: {:?}", locale.code(), missing);
+        }
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(Locale::parse("zh_CN.UTF-8"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("C"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr_FR"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn test_translate_with_placeholders() {
+        let message = translate_in(Locale::En, "delete.skipped", &[("name", "feat-x")]);
+        assert!(message.contains("feat-x"));
+        assert_eq!(translate_in(Locale::En, "no.such.key", &[]), "no.such.key");
+    }
+
+    #[test]
+    fn test_translate_keeps_literal_braces() {
+        // 模板语法中的 `{{`、`}}` 与消息占位符相邻时只替换占位符
+        for locale in Locale::ALL {
+            let message = translate_in(locale, "error.detail.port_outside_block", &[("offset", "10"), ("size", "10")]);
+            assert!(message.contains("'{{port+10}}'"), "{}", message);
+            let message = translate_in(locale, "error.detail.unknown_placeholder", &[("name", "nope")]);
+            assert!(message.contains("'{{nope}}'"), "{}", message);
+        }
+    }
+}
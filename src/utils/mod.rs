@@ -1,2 +1,3 @@
 pub mod errors;
+pub mod i18n;
 pub mod path;
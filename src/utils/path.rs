@@ -55,7 +55,7 @@ fn clean_path(path: &Path) -> PathBuf {
 
     for component in path.components() {
         match component {
-            Component::Prefix(prefix) => {
+            Component::Prefix(_) => {
                 // 保留前缀 (如 C: 或 \\server\share)
                 result.push(component);
             }
@@ -123,6 +123,7 @@ pub fn validate_worktree_name(name: &str) -> Result<()> {
 }
 
 /// 验证 worktree 路径
+#[allow(dead_code)]
 pub fn validate_worktree_path(path: &Path, repo_root: &Path) -> Result<()> {
     // 必须是绝对路径
     if !path.is_absolute() {
@@ -160,6 +161,7 @@ pub fn validate_worktree_path(path: &Path, repo_root: &Path) -> Result<()> {
 }
 
 /// 验证分支名
+#[allow(dead_code)]
 pub fn validate_branch_name(name: &str) -> Result<()> {
     // 不能为空
     if name.is_empty() {